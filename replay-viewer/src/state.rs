@@ -270,6 +270,7 @@ impl<'rvs> ReplayViewerState<'rvs> {
                         }
                     },
                     ObjectKind::Slider(_) => continue,
+                    ObjectKind::Spinner(_) => continue,
                 }
             };
 
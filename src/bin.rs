@@ -29,10 +29,12 @@ impl<'a> ApplicationHandler for OsuApp<'a> {
         let (stream, stream_handle) = OutputStream::try_default().unwrap();
         let sink = Sink::try_new(&stream_handle).unwrap();
         sink.pause();
+
+        let effects_sink = Sink::try_new(&stream_handle).unwrap();
         
         let window = window_orig.clone();
         let state = pollster::block_on(async move {
-            OsuState::new(window, graphics, sink)
+            OsuState::new(window, graphics, sink, effects_sink)
         });
 
         self.state = Some(state);
@@ -80,6 +82,14 @@ impl<'a> ApplicationHandler for OsuApp<'a> {
                     }
                 }
             },
+            winit::event::WindowEvent::MouseInput { state: button_state, button, .. } => {
+                if let Some(state) = &mut self.state {
+                    match button_state {
+                        winit::event::ElementState::Pressed => state.on_mouse_pressed(*button),
+                        winit::event::ElementState::Released => state.on_mouse_released(*button),
+                    }
+                }
+            },
             winit::event::WindowEvent::CursorMoved { position, .. } => {
                 if let Some(state) = &mut self.state {
                     state.on_cursor_moved(*position);
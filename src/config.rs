@@ -1,3 +1,5 @@
+use crate::keybindings::KeyBindings;
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct SliderConfig {
@@ -37,6 +39,10 @@ pub struct Config {
     pub slider: SliderConfig,
     pub judgements: JudgementsConfig,
     pub cursor: CursorConfig,
+    pub keybindings: KeyBindings,
+    /// Dim of the beatmap background during gameplay, 0.0..=1.0.
+    /// Background is not dimmed during breaks
+    pub background_dim: f32,
 }
 
 impl Default for Config {
@@ -58,6 +64,8 @@ impl Default for Config {
             cursor: CursorConfig {
                 size: 1.0
             },
+            keybindings: KeyBindings::default(),
+            background_dim: 0.8,
         }
     }
 }
@@ -0,0 +1,128 @@
+use rosu_map::{section::general::CountdownType, Beatmap};
+
+/// Used when the beatmap has no timing points, 120 BPM
+const DEFAULT_BEAT_LEN: f64 = 500.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CountdownStep {
+    Three,
+    Two,
+    One,
+    Go,
+}
+
+impl CountdownStep {
+    pub const ALL: [CountdownStep; 4] = [
+        CountdownStep::Three,
+        CountdownStep::Two,
+        CountdownStep::One,
+        CountdownStep::Go,
+    ];
+
+    /// Beats left until the first object
+    fn beats_before(&self) -> f64 {
+        match self {
+            CountdownStep::Three => 4.0,
+            CountdownStep::Two => 3.0,
+            CountdownStep::One => 2.0,
+            CountdownStep::Go => 1.0,
+        }
+    }
+}
+
+/// "3, 2, 1, Go" before the first object, one step per beat.
+/// Every step is shown for a beat after it's time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Countdown {
+    pub beat_len: f64,
+    /// Time of the first object
+    pub end_time: f64,
+    /// Countdown is started that many beats earlier
+    pub offset: f64,
+}
+
+impl Countdown {
+    pub fn new(beat_len: f64, end_time: f64, offset: f64) -> Self {
+        Self {
+            beat_len,
+            end_time,
+            offset,
+        }
+    }
+
+    /// `None` if the countdown is disabled or there are no objects
+    pub fn from_rosu(map: &Beatmap) -> Option<Self> {
+        let multiplier = match map.countdown {
+            CountdownType::None => return None,
+            CountdownType::Normal => 1.0,
+            CountdownType::HalfSpeed => 2.0,
+            CountdownType::DoubleSpeed => 0.5,
+        };
+
+        let first = map.hit_objects.first()?;
+
+        let beat_len = map.control_points
+            .timing_point_at(first.start_time)
+            .map_or(DEFAULT_BEAT_LEN, |x| x.beat_len);
+
+        Some(Self::new(
+            beat_len * multiplier,
+            first.start_time,
+            map.countdown_offset as f64,
+        ))
+    }
+
+    #[inline]
+    pub fn step_time(&self, step: CountdownStep) -> f64 {
+        self.end_time - self.beat_len * (step.beats_before() + self.offset)
+    }
+
+    /// Time of the first step
+    #[inline]
+    pub fn start_time(&self) -> f64 {
+        self.step_time(CountdownStep::Three)
+    }
+
+    /// Step shown at `time` and how much of it has passed, in 0.0..1.0
+    pub fn step_at(&self, time: f64) -> Option<(CountdownStep, f64)> {
+        CountdownStep::ALL.into_iter()
+            .map(|step| (step, (time - self.step_time(step)) / self.beat_len))
+            .find(|(_, progress)| (0.0..1.0).contains(progress))
+    }
+
+    /// Steps that are due at or before `time`, in order
+    pub fn steps_until(&self, time: f64) -> impl Iterator<Item = CountdownStep> + '_ {
+        CountdownStep::ALL.into_iter()
+            .filter(move |step| self.step_time(*step) <= time)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::case;
+
+    use super::{Countdown, CountdownStep};
+
+    #[case(5000.0, 0.0, CountdownStep::Three, 3000.0)]
+    #[case(5000.0, 0.0, CountdownStep::Go, 4500.0)]
+    #[case(5000.0, 2.0, CountdownStep::Three, 2000.0)]
+    #[case(1000.0, 0.0, CountdownStep::Three, -1000.0)]
+    fn test_countdown_step_time(end_time: f64, offset: f64, step: CountdownStep, expected: f64) {
+        let countdown = Countdown::new(500.0, end_time, offset);
+
+        assert_eq!(countdown.step_time(step), expected);
+    }
+
+    #[test]
+    fn test_countdown_step_at() {
+        let countdown = Countdown::new(500.0, 5000.0, 0.0);
+
+        assert_eq!(countdown.step_at(2999.0), None);
+        assert_eq!(countdown.step_at(3000.0), Some((CountdownStep::Three, 0.0)));
+        assert_eq!(countdown.step_at(3750.0), Some((CountdownStep::Two, 0.5)));
+        assert_eq!(countdown.step_at(4500.0), Some((CountdownStep::Go, 0.0)));
+        assert_eq!(countdown.step_at(5000.0), None);
+
+        assert_eq!(countdown.steps_until(4000.0).count(), 3);
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    sync::mpsc::{channel, Receiver, RecvError, Sender, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use cgmath::Vector2;
+
+use crate::{
+    hit_objects::{hit_window::HitWindow, ruleset::Ruleset, Object, ObjectState},
+    mods::Mods,
+    osu_input::{KeyboardState, OsuInput},
+    processor::{judgement::Judgement, OsuProcessor},
+    timer::ClockSnapshot,
+};
+
+/// How often gameplay is processed, independent of the framerate
+pub const GAMEPLAY_TICK: Duration = Duration::from_millis(1);
+
+/// Messages sent from the main thread. Inputs are
+/// timestamped right when they're received from the window
+#[derive(Debug, Clone)]
+pub enum GameplayMessage {
+    /// Gameplay clock was started, paused or changed it's rate
+    Clock(ClockSnapshot),
+    Pressed(Instant, KeyboardState),
+    Released(Instant, KeyboardState),
+    /// Position in osu!pixels
+    CursorMoved(Instant, Vector2<f64>),
+    /// Objects starting from the index are played again,
+    /// clock is already moved to where they're started from
+    Restart(usize, ClockSnapshot),
+}
+
+/// Results of processing, published only when something has changed
+#[derive(Debug, Clone)]
+pub struct GameplayUpdate {
+    pub judgements: Vec<Judgement>,
+    /// New states of the objects that could have been changed
+    pub objects: Vec<(usize, ObjectState)>,
+    pub cursor_pos: Vector2<f64>,
+    /// Every input of the play, published once
+    /// together with the last object being finished
+    pub replay: Option<Vec<OsuInput>>,
+    /// Restarts handled before the update was published
+    pub restarts: usize,
+}
+
+/// Everything the processor has to be configured with
+pub struct GameplaySettings {
+    pub hit_window: HitWindow,
+    pub circle_diameter: f32,
+    pub rate: f64,
+    pub ruleset: Ruleset,
+    /// Relax and autopilot, empty while watching a replay
+    /// since it's frames are already assisted
+    pub assists: Mods,
+    /// Fed to the processor by the gameplay thread
+    /// itself, so autoplay timing doesn't depend on FPS either
+    pub autoplay_inputs: Vec<OsuInput>,
+}
+
+/// Handle of the thread that owns [`OsuProcessor`] and its own copy
+/// of the objects. Inputs and results are passed through channels,
+/// so neither of the threads is ever blocked by the other one.
+///
+/// Thread stops once the handle is dropped
+pub struct GameplayThread {
+    sender: Sender<GameplayMessage>,
+    receiver: Receiver<GameplayUpdate>,
+    /// Restarts sent so far, updates published
+    /// before the last one of them are dropped
+    restarts: usize,
+}
+
+impl GameplayThread {
+    /// Processing starts only once the `clock` is unpaused,
+    /// so a thread of a new play should be spawned with
+    /// [`ClockSnapshot::paused_at`] the time play starts at
+    pub fn spawn(objects: Vec<Object>, settings: GameplaySettings, clock: ClockSnapshot) -> Self {
+        let (sender, messages) = channel();
+        let (updates, receiver) = channel();
+
+        let mut processor = OsuProcessor::default();
+        processor.set_rate(settings.rate);
+        processor.set_assists(settings.assists);
+        processor.set_ruleset(settings.ruleset);
+
+        let worker = GameplayWorker {
+            processor,
+            objects,
+            hit_window: settings.hit_window,
+            circle_diameter: settings.circle_diameter,
+            autoplay_inputs: settings.autoplay_inputs,
+            autoplay_index: 0,
+            replay_published: false,
+            restarts: 0,
+            clock,
+            messages,
+            updates,
+        };
+
+        thread::Builder::new()
+            .name("gameplay".to_owned())
+            .spawn(move || worker.run())
+            .expect("failed to spawn gameplay thread");
+
+        Self {
+            sender,
+            receiver,
+            restarts: 0,
+        }
+    }
+
+    pub fn sync_clock(&self, clock: ClockSnapshot) {
+        self.send(GameplayMessage::Clock(clock));
+    }
+
+    pub fn pressed(&self, keys: KeyboardState) {
+        self.send(GameplayMessage::Pressed(Instant::now(), keys));
+    }
+
+    pub fn released(&self, keys: KeyboardState) {
+        self.send(GameplayMessage::Released(Instant::now(), keys));
+    }
+
+    pub fn cursor_moved(&self, pos: Vector2<f64>) {
+        self.send(GameplayMessage::CursorMoved(Instant::now(), pos));
+    }
+
+    /// Main thread has to reset it's own copy of the objects as well
+    pub fn restart(&mut self, first_object: usize, clock: ClockSnapshot) {
+        self.restarts += 1;
+        self.send(GameplayMessage::Restart(first_object, clock));
+    }
+
+    pub fn send(&self, message: GameplayMessage) {
+        // Thread only stops on it's own when it panics,
+        // main thread has nothing to do about it
+        if self.sender.send(message).is_err() {
+            tracing::error!("Gameplay thread has stopped");
+        }
+    }
+
+    /// Updates published since the last call, in order
+    pub fn updates(&self) -> impl Iterator<Item = GameplayUpdate> + '_ {
+        self.receiver.try_iter()
+            .filter(|x| x.restarts == self.restarts)
+    }
+}
+
+struct GameplayWorker {
+    processor: OsuProcessor,
+    objects: Vec<Object>,
+    hit_window: HitWindow,
+    circle_diameter: f32,
+
+    autoplay_inputs: Vec<OsuInput>,
+    autoplay_index: usize,
+
+    replay_published: bool,
+    restarts: usize,
+
+    clock: ClockSnapshot,
+    messages: Receiver<GameplayMessage>,
+    updates: Sender<GameplayUpdate>,
+}
+
+impl GameplayWorker {
+    fn run(mut self) {
+        loop {
+            let tick_start = Instant::now();
+
+            let mut has_inputs = false;
+
+            // Nothing to process until the clock is started again
+            if self.clock.paused {
+                match self.messages.recv() {
+                    Ok(message) => has_inputs |= self.handle(message),
+                    Err(RecvError) => return,
+                }
+            }
+
+            loop {
+                match self.messages.try_recv() {
+                    Ok(message) => has_inputs |= self.handle(message),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => return,
+                }
+            }
+
+            // Taken after all received inputs,
+            // so none of them is in the future
+            let time = self.clock.time_at(Instant::now());
+
+            if !self.clock.paused {
+                has_inputs |= self.feed_autoplay_inputs(time);
+
+                if !self.tick(time, has_inputs) {
+                    return;
+                }
+            }
+
+            let elapsed = tick_start.elapsed();
+
+            if elapsed < GAMEPLAY_TICK {
+                thread::sleep(GAMEPLAY_TICK - elapsed);
+            }
+        }
+    }
+
+    /// Returns `true` if an input was stored
+    fn handle(&mut self, message: GameplayMessage) -> bool {
+        let _span = tracy_client::span!("gameplay_thread::handle");
+
+        match message {
+            GameplayMessage::Clock(clock) => {
+                self.clock = clock;
+                false
+            },
+            GameplayMessage::Pressed(at, keys) => {
+                let ts = self.clock.time_at(at);
+                self.processor.store_keyboard_pressed(ts, keys);
+                true
+            },
+            GameplayMessage::Released(at, keys) => {
+                let ts = self.clock.time_at(at);
+                self.processor.store_keyboard_released(ts, keys);
+                true
+            },
+            GameplayMessage::CursorMoved(at, pos) => {
+                let ts = self.clock.time_at(at);
+                self.processor.store_cursor_moved(ts, pos);
+                true
+            },
+            GameplayMessage::Restart(first_object, clock) => {
+                for object in self.objects.iter_mut().skip(first_object) {
+                    object.reset_state();
+                }
+
+                self.processor.restart_from(first_object);
+                self.clock = clock;
+
+                // Autoplay continues from the new time
+                self.autoplay_index = self.autoplay_inputs
+                    .partition_point(|x| x.ts < clock.time);
+                self.replay_published = false;
+                self.restarts += 1;
+
+                false
+            },
+        }
+    }
+
+    /// Passes generated inputs up to `time` to the processor,
+    /// returns `true` if there were any
+    fn feed_autoplay_inputs(&mut self, time: f64) -> bool {
+        let start = self.autoplay_index;
+
+        while let Some(input) = self.autoplay_inputs.get(self.autoplay_index) {
+            if input.ts > time {
+                break;
+            }
+
+            self.processor.set_cursor_pos(input.pos);
+            self.processor.store_input(input.clone());
+            self.autoplay_index += 1;
+        }
+
+        self.autoplay_index != start
+    }
+
+    /// Processes everything up to `time` and publishes the results,
+    /// returns `false` if the renderer is gone
+    fn tick(&mut self, time: f64, has_inputs: bool) -> bool {
+        let _span = tracy_client::span!("gameplay_thread::tick");
+
+        let from = self.processor.active_object();
+
+        self.processor.process(
+            time,
+            &mut self.objects,
+            &self.hit_window,
+            self.circle_diameter,
+        );
+
+        let judgements = self.processor.take_judgements();
+
+        // Every object before the active one is finished
+        let is_finished = !self.replay_published
+            && !self.objects.is_empty()
+            && self.processor.active_object() >= self.objects.len();
+
+        if !has_inputs
+            && !is_finished
+            && judgements.is_empty()
+            && self.processor.assists().is_empty() {
+            return true;
+        }
+
+        let replay = is_finished.then(|| {
+            self.replay_published = true;
+            self.processor.replay_frames().to_vec()
+        });
+
+        // Early presses are judged up to the miss window before the object
+        let until = time + self.hit_window.xmiss + 1.0;
+        let objects = self.objects.iter()
+            .enumerate()
+            .skip(from)
+            .take_while(|(_, x)| x.start_time <= until)
+            .map(|(i, x)| (i, x.state()))
+            .collect();
+
+        self.updates.send(GameplayUpdate {
+            judgements,
+            objects,
+            cursor_pos: self.processor.cursor_pos(),
+            replay,
+            restarts: self.restarts,
+        }).is_ok()
+    }
+}
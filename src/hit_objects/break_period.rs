@@ -0,0 +1,176 @@
+use rosu_map::Beatmap;
+
+/// Breaks shorter than that are not shown, same as stable
+pub const MIN_BREAK_DURATION: f64 = 650.0;
+
+/// Pass or fail indicator is only shown during breaks this long
+pub const MIN_SECTION_RESULT_DURATION: f64 = 2880.0;
+
+/// Skip lands that long before the end of the break
+pub const BREAK_SKIP_LEAD: f64 = 1000.0;
+
+/// Background dim, overlay and skip button are fading in and out that long
+pub const BREAK_FADE_TIME: f64 = 300.0;
+
+/// Health needed to pass the section at the middle of the break
+pub const SECTION_PASS_HEALTH: f64 = 0.5;
+
+/// Indicator shown in the middle of a long break
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SectionResult {
+    Pass,
+    Fail,
+}
+
+impl SectionResult {
+    /// `health` is in 0.0..=1.0
+    pub fn from_health(health: f64) -> Self {
+        if health >= SECTION_PASS_HEALTH {
+            SectionResult::Pass
+        } else {
+            SectionResult::Fail
+        }
+    }
+}
+
+/// Part of the beatmap without any objects. HP is not
+/// drained during breaks and they don't count
+/// towards drain time of the beatmap
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BreakPeriod {
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+impl BreakPeriod {
+    pub fn new(start_time: f64, end_time: f64) -> Self {
+        Self {
+            start_time,
+            end_time,
+        }
+    }
+
+    pub fn from_rosu(map: &Beatmap) -> Vec<BreakPeriod> {
+        map.breaks.iter()
+            .map(|x| BreakPeriod::new(x.start_time, x.end_time))
+            .collect()
+    }
+
+    /// Break that is shown at `time`, short breaks are ignored
+    pub fn at(breaks: &[BreakPeriod], time: f64) -> Option<(usize, &BreakPeriod)> {
+        breaks.iter()
+            .enumerate()
+            .find(|(_, x)| x.has_effect() && x.contains(time))
+    }
+
+    #[inline]
+    pub fn duration(&self) -> f64 {
+        self.end_time - self.start_time
+    }
+
+    /// Break is long enough to be shown
+    #[inline]
+    pub fn has_effect(&self) -> bool {
+        self.duration() >= MIN_BREAK_DURATION
+    }
+
+    #[inline]
+    pub fn contains(&self, time: f64) -> bool {
+        time >= self.start_time && time < self.end_time
+    }
+
+    /// Time the pass or fail is decided at
+    #[inline]
+    pub fn middle(&self) -> f64 {
+        self.start_time + self.duration() / 2.0
+    }
+
+    #[inline]
+    pub fn has_section_result(&self) -> bool {
+        self.duration() >= MIN_SECTION_RESULT_DURATION
+    }
+
+    /// Amount of the break that is still left, 1.0 at the start
+    pub fn remaining(&self, time: f64) -> f64 {
+        ((self.end_time - time) / self.duration()).clamp(0.0, 1.0)
+    }
+
+    /// 0.0 outside of the break, 1.0 once it fully faded in
+    /// and until it starts fading out at the end
+    pub fn visibility(&self, time: f64) -> f64 {
+        if !self.contains(time) {
+            return 0.0;
+        }
+
+        let fade_in = (time - self.start_time) / BREAK_FADE_TIME;
+        let fade_out = (self.end_time - time) / BREAK_FADE_TIME;
+
+        fade_in.min(fade_out).clamp(0.0, 1.0)
+    }
+
+    /// Time skip jumps to
+    #[inline]
+    pub fn skip_target(&self) -> f64 {
+        self.end_time - BREAK_SKIP_LEAD
+    }
+
+    #[inline]
+    pub fn is_skippable(&self, time: f64) -> bool {
+        self.contains(time) && time < self.skip_target()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::case;
+
+    use super::{BreakPeriod, SectionResult};
+
+    #[test]
+    fn test_break_at() {
+        let breaks = [
+            BreakPeriod::new(1000.0, 5000.0),
+            BreakPeriod::new(8000.0, 12000.0),
+        ];
+
+        assert_eq!(BreakPeriod::at(&breaks, 500.0), None);
+        assert_eq!(BreakPeriod::at(&breaks, 1000.0), Some((0, &breaks[0])));
+        assert_eq!(BreakPeriod::at(&breaks, 5000.0), None);
+        assert_eq!(BreakPeriod::at(&breaks, 9000.0), Some((1, &breaks[1])));
+
+        // Too short to be shown
+        let breaks = [BreakPeriod::new(1000.0, 1500.0)];
+        assert_eq!(BreakPeriod::at(&breaks, 1200.0), None);
+    }
+
+    #[case(1000.0, 0.0)]
+    #[case(1150.0, 0.5)]
+    #[case(3000.0, 1.0)]
+    #[case(4850.0, 0.5)]
+    #[case(5000.0, 0.0)]
+    fn test_break_visibility(time: f64, expected: f64) {
+        let period = BreakPeriod::new(1000.0, 5000.0);
+
+        assert_eq!(period.visibility(time), expected);
+    }
+
+    #[test]
+    fn test_break_skip() {
+        let period = BreakPeriod::new(1000.0, 5000.0);
+
+        assert!(period.is_skippable(1000.0));
+        assert!(period.is_skippable(3999.0));
+        assert!(!period.is_skippable(4000.0));
+        assert!(!period.is_skippable(500.0));
+        assert_eq!(period.skip_target(), 4000.0);
+    }
+
+    #[test]
+    fn test_section_result() {
+        assert!(BreakPeriod::new(1000.0, 5000.0).has_section_result());
+        assert!(!BreakPeriod::new(1000.0, 2000.0).has_section_result());
+
+        assert_eq!(SectionResult::from_health(0.5), SectionResult::Pass);
+        assert_eq!(SectionResult::from_health(0.49), SectionResult::Fail);
+    }
+}
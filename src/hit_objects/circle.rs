@@ -3,9 +3,9 @@ use rosu_map::util::Pos;
 
 use crate::osu_input::OsuInput;
 
-use super::{hit_window::HitWindow, Hit, CIRCLE_FADEOUT_TIME, JUDGMENTS_FADEOUT_TIME};
+use super::{hit_window::HitWindow, ruleset::Ruleset, Hit, CIRCLE_FADEOUT_TIME, JUDGMENTS_FADEOUT_TIME};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CircleHitResult {
     pub at: f64,
     pub pos: Vector2<f64>,
@@ -28,24 +28,31 @@ impl Circle {
         time > self.start_time - preempt as f64 && time < self.start_time + (CIRCLE_FADEOUT_TIME * 2.0) + (JUDGMENTS_FADEOUT_TIME * 2.0)
     }
 
-    pub fn update(
-        &mut self,
+    /// Circle can still be hit at provided time
+    #[inline]
+    pub fn is_hittable(&self, time: f64, hit_window: &HitWindow, ruleset: Ruleset) -> bool {
+        self.hit_result.is_none() && ruleset.is_inside(time - self.start_time, hit_window.x50)
+    }
+
+    /// Checks if provided input would hit the circle
+    /// without assigning any hit result
+    pub fn check_hit(
+        &self,
         input: &OsuInput,
         hit_window: &HitWindow,
         circle_diameter: f32,
-    ) -> bool {
-        let _span = tracy_client::span!("hit_objects::circle::update");
-
+        ruleset: Ruleset,
+    ) -> Option<Hit> {
         if self.hit_result.is_some() {
-            return false;
+            return None;
         }
 
         if !input.keys.is_keys_hit() {
-            return false;
+            return None;
         }
 
         if !input.is_keys_hit_no_hold() {
-            return false;
+            return None;
         }
 
         let (cx, cy) = (self.pos.x as f64, self.pos.y as f64);
@@ -54,39 +61,69 @@ impl Circle {
         let distance = ((px - cx).powf(2.0) + (py - cy).powf(2.0)).sqrt();
 
         if !(distance <= (circle_diameter / 2.0) as f64) {
-            return false;
+            return None;
         }
 
         let hit_error = (self.start_time - input.ts).abs();
 
-        if hit_error < hit_window.x300.round() {
-            self.hit_result = Some(CircleHitResult {
-                at: input.ts,
-                pos: input.pos,
-                result: Hit::X300,
-            });
-            return true;
+        if let Some(hit) = ruleset.circle_hit(hit_error, hit_window) {
+            return Some(hit);
         }
 
-        if hit_error < hit_window.x100.round() {
-            self.hit_result = Some(CircleHitResult {
-                at: input.ts,
-                pos: input.pos,
-                result: Hit::X100,
-            });
-            return true;
+        // Too early press, but still close enough to be punished
+        if input.ts < self.start_time && ruleset.is_inside(hit_error, hit_window.xmiss) {
+            return Some(Hit::MISS);
         }
 
-        if hit_error < hit_window.x50.round() {
-            self.hit_result = Some(CircleHitResult {
-                at: input.ts,
-                pos: input.pos,
-                result: Hit::X50,
-            });
+        None
+    }
+
+    /// Assigns a miss if circle wasn't hit during it's hit window
+    ///
+    /// Returns `true` if miss was assigned
+    pub fn update_miss(&mut self, time: f64, hit_window: &HitWindow, ruleset: Ruleset) -> bool {
+        if self.hit_result.is_some() {
+            return false;
+        }
 
-            return true;
+        if ruleset.is_inside(time - self.start_time, hit_window.x50) {
+            return false;
         }
 
-        return false;
+        self.miss(self.start_time + ruleset.window(hit_window.x50));
+
+        true
+    }
+
+    /// Assigns a miss at provided time, used when
+    /// circle is skipped by a hit on a later object
+    pub fn miss(&mut self, at: f64) {
+        self.hit_result = Some(CircleHitResult {
+            at,
+            pos: Vector2::new(self.pos.x as f64, self.pos.y as f64),
+            result: Hit::MISS,
+        });
+    }
+
+    pub fn update(
+        &mut self,
+        input: &OsuInput,
+        hit_window: &HitWindow,
+        circle_diameter: f32,
+        ruleset: Ruleset,
+    ) -> bool {
+        let _span = tracy_client::span!("hit_objects::circle::update");
+
+        let Some(result) = self.check_hit(input, hit_window, circle_diameter, ruleset) else {
+            return false;
+        };
+
+        self.hit_result = Some(CircleHitResult {
+            at: input.ts,
+            pos: input.pos,
+            result,
+        });
+
+        true
     }
 }
@@ -1,15 +1,20 @@
 pub mod circle;
 pub mod slider;
+pub mod spinner;
+pub mod stacking;
 pub mod hit_window;
+pub mod ruleset;
+pub mod break_period;
 
 use cgmath::Vector2;
 use hit_window::HitWindow;
 use rosu_map::Beatmap;
 
-use slider::{Slider, Tick};
-use circle::Circle;
+use slider::{Slider, SliderResult, Tick};
+use circle::{Circle, CircleHitResult};
+use spinner::{Spinner, SpinnerHitResult, SpinnerState};
 
-use crate::math::{calc_opposite_direction_degree, calc_progress};
+use crate::{math::calc_progress, transforms::TransformPipeline};
 
 // In ms
 pub const SLIDER_FADEOUT_TIME: f64 = 80.0;
@@ -18,6 +23,16 @@ pub const JUDGMENTS_FADEOUT_TIME: f64 = 300.0;
 pub const CIRCLE_SCALEOUT_MAX: f64 = 1.4;
 pub const REVERSE_ARROW_FADEOUT: f64 = 200.0;
 pub const REVERSE_ARROW_FADEIN: f64 = 300.0;
+pub const SPINNER_FADEIN_TIME: f64 = 400.0;
+pub const SPINNER_FADEOUT_TIME: f64 = 200.0;
+pub const NOTE_LOCK_SHAKE_TIME: f64 = 200.0;
+
+/// Hidden fade in and fade out durations as a part of preempt, same as stable
+pub const HIDDEN_FADE_IN_MULTIPLIER: f64 = 0.4;
+pub const HIDDEN_FADE_OUT_MULTIPLIER: f64 = 0.3;
+
+/// Max horizontal offset of a note locked object in osu!pixels
+pub const NOTE_LOCK_SHAKE_AMPLITUDE: f64 = 8.0;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -28,6 +43,35 @@ pub enum Hit {
     MISS,
 }
 
+/// Special judgement given at the end of the combo
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ComboResult {
+    /// Every object in combo is x300
+    Geki,
+    /// Combo has x100 but no x50 or misses
+    Katu,
+}
+
+impl ComboResult {
+    pub fn from_hits(hits: impl IntoIterator<Item = Hit>) -> Option<ComboResult> {
+        let mut has_x100 = false;
+
+        for hit in hits {
+            match hit {
+                Hit::X300 => {},
+                Hit::X100 => has_x100 = true,
+                Hit::X50 | Hit::MISS => return None,
+            }
+        }
+
+        if has_x100 {
+            Some(ComboResult::Katu)
+        } else {
+            Some(ComboResult::Geki)
+        }
+    }
+}
+
 // TODO: Remove this rectangle shit or move it to other place
 #[derive(Clone)]
 pub struct Rectangle {
@@ -49,24 +93,149 @@ pub struct Object {
     pub start_time: f64,
     pub kind: ObjectKind,
     pub color: usize,
+
+    /// Time of the last press that was ignored because of note lock
+    pub shake_at: Option<f64>,
+
+    /// Object starts a new combo
+    pub new_combo: bool,
+    /// Set on the last object of the combo
+    /// once whole combo is judged
+    pub combo_result: Option<ComboResult>,
 }
 
 impl Object {
+    /// Horizontal offset of the object caused by note lock shake
+    pub fn shake_offset(&self, time: f64) -> f32 {
+        let Some(shake_at) = self.shake_at else {
+            return 0.0;
+        };
+
+        if time < shake_at || time > shake_at + NOTE_LOCK_SHAKE_TIME {
+            return 0.0;
+        }
+
+        let progress = calc_progress(time, shake_at, shake_at + NOTE_LOCK_SHAKE_TIME);
+        let decay = 1.0 - progress;
+
+        (NOTE_LOCK_SHAKE_AMPLITUDE * decay * (progress * std::f64::consts::PI * 6.0).sin()) as f32
+    }
+
     pub fn is_visible(&self, time: f64, preempt: f32, hit_window: &HitWindow) -> bool {
         match &self.kind {
             ObjectKind::Circle(circle) => circle.is_visible(time, preempt, hit_window),
             ObjectKind::Slider(slider) => slider.is_visible(time, preempt),
+            ObjectKind::Spinner(spinner) => spinner.is_visible(time),
         }
     }
 
     pub fn is_judgements_visible(&self, time: f64, preempt: f32) -> bool {
         match &self.kind {
             ObjectKind::Circle(circle) => circle.is_judgements_visible(time, preempt),
-            ObjectKind::Slider(_) => false,
+            ObjectKind::Slider(slider) => slider.is_judgements_visible(time),
+            ObjectKind::Spinner(spinner) => spinner.is_judgements_visible(time),
+        }
+    }
+
+    pub fn end_time(&self) -> f64 {
+        match &self.kind {
+            ObjectKind::Circle(circle) => circle.start_time,
+            ObjectKind::Slider(slider) => slider.end_time(),
+            ObjectKind::Spinner(spinner) => spinner.end_time(),
+        }
+    }
+
+    /// Final judgement of the object if it's finished
+    pub fn final_hit(&self) -> Option<Hit> {
+        match &self.kind {
+            ObjectKind::Circle(circle) => circle.hit_result.as_ref().map(|x| x.result),
+            ObjectKind::Slider(slider) => match slider.hit_result.as_ref().map(|x| x.state) {
+                Some(slider::SliderResultState::Passed(hit)) => Some(hit),
+                _ => None,
+            },
+            ObjectKind::Spinner(spinner) => spinner.hit_result.as_ref().map(|x| x.result),
+        }
+    }
+
+    /// Object got it's final judgement and
+    /// can't be affected by any input anymore
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.final_hit().is_some()
+    }
+
+    /// Everything about the object that is changed by the processor
+    pub fn state(&self) -> ObjectState {
+        let kind = match &self.kind {
+            ObjectKind::Circle(circle) => ObjectKindState::Circle(circle.hit_result.clone()),
+            ObjectKind::Slider(slider) => ObjectKindState::Slider(slider.hit_result.clone()),
+            ObjectKind::Spinner(spinner) => ObjectKindState::Spinner(
+                spinner.state.clone(),
+                spinner.hit_result.clone(),
+            ),
+        };
+
+        ObjectState {
+            shake_at: self.shake_at,
+            combo_result: self.combo_result,
+            kind,
+        }
+    }
+
+    /// Applies state of the same object processed somewhere else,
+    /// state of a different kind is ignored
+    pub fn set_state(&mut self, state: ObjectState) {
+        self.shake_at = state.shake_at;
+        self.combo_result = state.combo_result;
+
+        match (&mut self.kind, state.kind) {
+            (ObjectKind::Circle(circle), ObjectKindState::Circle(hit_result)) => {
+                circle.hit_result = hit_result;
+            },
+            (ObjectKind::Slider(slider), ObjectKindState::Slider(hit_result)) => {
+                slider.hit_result = hit_result;
+            },
+            (ObjectKind::Spinner(spinner), ObjectKindState::Spinner(state, hit_result)) => {
+                spinner.state = state;
+                spinner.hit_result = hit_result;
+            },
+            _ => {},
+        }
+    }
+
+    /// Forgets everything processing has done to the object
+    pub fn reset_state(&mut self) {
+        self.shake_at = None;
+        self.combo_result = None;
+
+        match &mut self.kind {
+            ObjectKind::Circle(circle) => circle.hit_result = None,
+            ObjectKind::Slider(slider) => slider.hit_result = None,
+            ObjectKind::Spinner(spinner) => {
+                spinner.state = SpinnerState::default();
+                spinner.hit_result = None;
+            },
         }
     }
 
     pub fn from_rosu(map: &Beatmap) -> Vec<Object> {
+        Self::from_rosu_transformed(map, &TransformPipeline::default())
+    }
+
+    /// Same as [`Self::from_rosu`], but `transforms` are applied
+    /// before stacking, so stacks are calculated from final positions
+    pub fn from_rosu_transformed(map: &Beatmap, transforms: &TransformPipeline) -> Vec<Object> {
+        let _span = tracy_client::span!("hit_objects::from_rosu_transformed");
+
+        let mut objects = Self::convert(map);
+
+        transforms.apply(&mut objects);
+        stacking::apply_stacking(&mut objects, map);
+
+        objects
+    }
+
+    fn convert(map: &Beatmap) -> Vec<Object> {
 
         let mut color_index = 1;
         let tick_rate = map.slider_tick_rate;
@@ -76,6 +245,13 @@ impl Object {
         let mut objects = Vec::with_capacity(values.len());
 
         for value in values {
+            // Spinners are always starting a new combo
+            // and the object after them too
+            let new_combo = value.new_combo()
+                || objects.is_empty()
+                || matches!(value.kind, rosu_map::section::hit_objects::HitObjectKind::Spinner(_))
+                || matches!(objects.last(), Some(Object { kind: ObjectKind::Spinner(_), .. }));
+
             if value.new_combo() {
                 color_index += 1;
             }
@@ -162,25 +338,7 @@ impl Object {
                         let repeat = repeat + 1;
                         let v2 = duration / slider.span_count() as f64;
 
-                        let (pos1, pos2) = if repeat % 2 == 0 {
-                            let p1 = curve.position_at(0.0);
-                            let p2 = curve.position_at(0.05);
-
-                            (
-                                Vector2::new(slider.pos.x + p1.x, slider.pos.y + p1.y),
-                                Vector2::new(slider.pos.x + p2.x, slider.pos.y + p2.y),
-                            )
-                        } else {
-                            let p1 = curve.position_at(1.0);
-                            let p2 = curve.position_at(0.95);
-
-                            (
-                                Vector2::new(slider.pos.x + p1.x, slider.pos.y + p1.y),
-                                Vector2::new(slider.pos.x + p2.x, slider.pos.y + p2.y),
-                            )
-                        };
-
-                        let angle = -calc_opposite_direction_degree(pos2, pos1);
+                        let (pos1, angle) = slider::reverse_arrow_placement(&curve, pos, repeat);
 
                         let slide_start = value.start_time + (v2 * (repeat as f64));
 
@@ -209,12 +367,16 @@ impl Object {
                     objects.push(Self {
                         start_time: value.start_time,
                         color: color_index,
+                        shake_at: None,
+                        new_combo,
+                        combo_result: None,
                         kind: ObjectKind::Slider(Slider {
                             repeats: slider.span_count(),
                             start_time: value.start_time,
                             pos,
                             duration,
                             curve,
+                            path: slider.path,
                             ticks,
                             render: None,
                             reverse_arrows,
@@ -226,12 +388,27 @@ impl Object {
                 rosu_map::section::hit_objects::HitObjectKind::Circle(circle) => objects.push(Self {
                     start_time: value.start_time,
                     color: color_index,
+                    shake_at: None,
+                    new_combo,
+                    combo_result: None,
                     kind: ObjectKind::Circle(Circle {
                         start_time: value.start_time,
                         pos: circle.pos,
                         hit_result: None,
                     }),
                 }),
+                rosu_map::section::hit_objects::HitObjectKind::Spinner(spinner) => objects.push(Self {
+                    start_time: value.start_time,
+                    color: color_index,
+                    shake_at: None,
+                    new_combo,
+                    combo_result: None,
+                    kind: ObjectKind::Spinner(Spinner::new(
+                        value.start_time,
+                        spinner.duration,
+                        map.overall_difficulty,
+                    )),
+                }),
                 _ => {},
             };
         };
@@ -243,4 +420,21 @@ impl Object {
 pub enum ObjectKind {
     Circle(Circle),
     Slider(Slider),
+    Spinner(Spinner),
+}
+
+/// Gameplay state of the [`Object`], used to pass processed
+/// objects from the gameplay thread to the renderer
+#[derive(Debug, Clone)]
+pub struct ObjectState {
+    pub shake_at: Option<f64>,
+    pub combo_result: Option<ComboResult>,
+    pub kind: ObjectKindState,
+}
+
+#[derive(Debug, Clone)]
+pub enum ObjectKindState {
+    Circle(Option<CircleHitResult>),
+    Slider(Option<SliderResult>),
+    Spinner(SpinnerState, Option<SpinnerHitResult>),
 }
@@ -0,0 +1,98 @@
+use super::{hit_window::HitWindow, Hit};
+
+/// Rules used to judge the play.
+///
+/// Stable compares hit errors against rounded windows and excludes
+/// the window edge, lazer uses exact windows including the edge.
+/// Lazer also judges slider heads by accuracy, checks slider
+/// tracking right at the tail leniency time and uses a different
+/// note lock, see [`crate::processor::OsuProcessor`]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Ruleset {
+    #[default]
+    Stable,
+    Lazer,
+}
+
+impl Ruleset {
+    /// Window as it's compared against hit errors
+    #[inline]
+    pub fn window(&self, window: f64) -> f64 {
+        match self {
+            Ruleset::Stable => window.round(),
+            Ruleset::Lazer => window,
+        }
+    }
+
+    /// Hit error fits inside of the `window`
+    #[inline]
+    pub fn is_inside(&self, hit_error: f64, window: f64) -> bool {
+        match self {
+            Ruleset::Stable => hit_error < self.window(window),
+            Ruleset::Lazer => hit_error <= self.window(window),
+        }
+    }
+
+    /// Judgement of a hit circle press with the provided
+    /// absolute hit error, `None` if it's outside of x50 window
+    pub fn circle_hit(&self, hit_error: f64, hit_window: &HitWindow) -> Option<Hit> {
+        if self.is_inside(hit_error, hit_window.x300) {
+            return Some(Hit::X300);
+        }
+
+        if self.is_inside(hit_error, hit_window.x100) {
+            return Some(Hit::X100);
+        }
+
+        if self.is_inside(hit_error, hit_window.x50) {
+            return Some(Hit::X50);
+        }
+
+        None
+    }
+
+    /// Judgement of a slider head press with the provided
+    /// absolute hit error, `None` if it's outside of x50 window.
+    ///
+    /// Slider head is always x300 in stable
+    pub fn slider_head_hit(&self, hit_error: f64, hit_window: &HitWindow) -> Option<Hit> {
+        match self {
+            Ruleset::Stable => self.is_inside(hit_error, hit_window.x50).then_some(Hit::X300),
+            Ruleset::Lazer => self.circle_hit(hit_error, hit_window),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::case;
+
+    use crate::hit_objects::{hit_window::HitWindow, Hit};
+
+    use super::Ruleset;
+
+    // OD 7: x300 38, x100 84, x50 130
+    #[case(Ruleset::Stable, 37.0, Some(Hit::X300))]
+    #[case(Ruleset::Stable, 38.0, Some(Hit::X100))]
+    #[case(Ruleset::Lazer, 38.0, Some(Hit::X300))]
+    #[case(Ruleset::Stable, 84.0, Some(Hit::X50))]
+    #[case(Ruleset::Lazer, 84.0, Some(Hit::X100))]
+    #[case(Ruleset::Stable, 130.0, None)]
+    #[case(Ruleset::Lazer, 131.0, None)]
+    fn test_circle_hit_window_edges(ruleset: Ruleset, hit_error: f64, expected: Option<Hit>) {
+        let hit_window = HitWindow::from_od(7.0);
+
+        assert_eq!(ruleset.circle_hit(hit_error, &hit_window), expected);
+    }
+
+    #[case(Ruleset::Stable, 60.0, Some(Hit::X300))]
+    #[case(Ruleset::Lazer, 60.0, Some(Hit::X100))]
+    #[case(Ruleset::Lazer, 100.0, Some(Hit::X50))]
+    #[case(Ruleset::Stable, 200.0, None)]
+    #[case(Ruleset::Lazer, 200.0, None)]
+    fn test_slider_head_hit(ruleset: Ruleset, hit_error: f64, expected: Option<Hit>) {
+        let hit_window = HitWindow::from_od(7.0);
+
+        assert_eq!(ruleset.slider_head_hit(hit_error, &hit_window), expected);
+    }
+}
@@ -1,11 +1,15 @@
 use std::sync::Arc;
 
 use cgmath::Vector2;
-use rosu_map::{section::hit_objects::Curve, util::Pos};
+use rosu_map::{section::hit_objects::{Curve, SliderPath}, util::Pos};
 
-use crate::{osu_input::OsuInput, texture::Texture};
+use crate::{math::calc_opposite_direction_degree, osu_input::OsuInput, texture::Texture};
 
-use super::{circle::CircleHitResult, hit_window::HitWindow, Hit, Rectangle, SLIDER_FADEOUT_TIME};
+use super::{circle::CircleHitResult, hit_window::HitWindow, ruleset::Ruleset, Hit, Rectangle, JUDGMENTS_FADEOUT_TIME, SLIDER_FADEOUT_TIME};
+
+/// Follow circle radius relative to the hit circle,
+/// cursor can leave the ball that far once it's tracking
+const FOLLOW_CIRCLE_SCALE: f64 = 2.4;
 
 #[derive(Debug)]
 pub struct ReverseArrow {
@@ -21,6 +25,21 @@ pub struct Tick {
     pub is_reverse: bool,
 }
 
+/// Position and rotation of the reverse arrow
+/// at the end of the `repeat` slide, starting from 1
+pub fn reverse_arrow_placement(curve: &Curve, pos: Pos, repeat: i32) -> (Vector2<f32>, f32) {
+    let (p1, p2) = if repeat % 2 == 0 {
+        (curve.position_at(0.0), curve.position_at(0.05))
+    } else {
+        (curve.position_at(1.0), curve.position_at(0.95))
+    };
+
+    let pos1 = Vector2::new(pos.x + p1.x, pos.y + p1.y);
+    let pos2 = Vector2::new(pos.x + p2.x, pos.y + p2.y);
+
+    (pos1, -calc_opposite_direction_degree(pos2, pos1))
+}
+
 pub struct SliderRender {
     pub texture: Arc<Texture>,
     pub quad: Arc<wgpu::Buffer>,
@@ -41,11 +60,43 @@ pub enum SliderResultState {
     Passed(Hit)
 }
 
-#[derive(Debug)]
+/// Part of the slider that is judged on it's own
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SliderPart {
+    Head,
+    Tick,
+    Repeat,
+    End,
+}
+
+/// Judgement of a single slider part
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SliderPartResult {
+    pub part: SliderPart,
+    /// Time of the part itself, not the input that judged it
+    pub at: f64,
+    pub pos: Vector2<f32>,
+    pub hit: bool,
+    /// Index inside of [`Slider::checkpoints`] for ticks and repeats
+    pub checkpoint: Option<usize>,
+}
+
+impl SliderPartResult {
+    /// Missed tick or repeat, resets combo
+    #[inline]
+    pub fn is_slider_break(&self) -> bool {
+        !self.hit && matches!(self.part, SliderPart::Tick | SliderPart::Repeat)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct SliderResult {
     pub state: SliderResultState,
     pub head: CircleHitResult,
     pub passed_checkpoints: Vec<usize>,
+    /// Every judged part in the order they were judged.
+    /// Parts are only added, so it can be read incrementally
+    pub parts: Vec<SliderPartResult>,
     pub lenience_passed: bool,
     pub holding_since: Option<f64>,
     pub in_radius_since: Option<f64>,
@@ -86,6 +137,96 @@ impl SliderResult {
             Some(Hit::MISS)
         }
     }
+
+    /// Result of the whole slider. Lazer doesn't judge sliders
+    /// by parts, the slider gets the accuracy of it's head
+    fn final_result(&self, total_checkpoints: usize, ruleset: Ruleset) -> Option<Hit> {
+        match ruleset {
+            Ruleset::Stable => self.calculate_hit_result(total_checkpoints),
+            Ruleset::Lazer => Some(self.head.result),
+        }
+    }
+
+    fn head_part(&self, pos: Pos) -> SliderPartResult {
+        SliderPartResult {
+            part: SliderPart::Head,
+            at: self.head.at,
+            pos: Vector2::new(pos.x, pos.y),
+            hit: self.head.result != Hit::MISS,
+            checkpoint: None,
+        }
+    }
+
+    #[inline]
+    fn is_checkpoint_judged(&self, index: usize) -> bool {
+        self.parts.iter().any(|x| x.checkpoint == Some(index))
+    }
+
+    fn judge_checkpoint(&mut self, checkpoints: &[Tick], index: usize, hit: bool) {
+        let checkpoint = &checkpoints[index];
+
+        if hit {
+            self.passed_checkpoints.push(index);
+        }
+
+        self.parts.push(SliderPartResult {
+            part: if checkpoint.is_reverse { SliderPart::Repeat } else { SliderPart::Tick },
+            at: checkpoint.time,
+            pos: checkpoint.pos,
+            hit,
+            checkpoint: Some(index),
+        });
+    }
+
+    /// Misses checkpoints that can't be passed anymore. Checkpoint
+    /// needs holding to start before it, and `holding_since`
+    /// is never moving back, so it's known right after it's time
+    fn miss_passed_checkpoints(&mut self, checkpoints: &[Tick], time: f64) {
+        for (i, checkpoint) in checkpoints.iter().enumerate() {
+            if checkpoint.time >= time || self.is_checkpoint_judged(i) {
+                continue;
+            }
+
+            let can_pass = self.holding_since
+                .is_some_and(|holding_since| holding_since < checkpoint.time);
+
+            if !can_pass {
+                self.judge_checkpoint(checkpoints, i, false);
+            }
+        }
+    }
+
+    /// Misses everything that wasn't judged yet,
+    /// judges slider end and sets the final state
+    fn finish(
+        &mut self,
+        checkpoints: &[Tick],
+        end_time: f64,
+        end_pos: Pos,
+        ruleset: Ruleset,
+    ) -> Hit {
+        for i in 0..checkpoints.len() {
+            if !self.is_checkpoint_judged(i) {
+                self.judge_checkpoint(checkpoints, i, false);
+            }
+        }
+
+        self.parts.push(SliderPartResult {
+            part: SliderPart::End,
+            at: end_time,
+            pos: Vector2::new(end_pos.x, end_pos.y),
+            hit: self.lenience_passed,
+            checkpoint: None,
+        });
+
+        let Some(final_result) = self.final_result(checkpoints.len(), ruleset) else {
+            panic!("Trying to set Passed slider state without final hit result");
+        };
+
+        self.state = SliderResultState::Passed(final_result);
+
+        final_result
+    }
 }
 
 pub struct Slider {
@@ -93,6 +234,9 @@ pub struct Slider {
     pub duration: f64,
 
     pub curve: Curve,
+    /// Path the curve is calculated from, kept
+    /// to rebuild the curve after transformations
+    pub path: SliderPath,
     pub pos: Pos, // TODO: Make the same as in circle
 
     /// Total repeats
@@ -171,25 +315,41 @@ impl Slider {
         }
     }
 
+    /// Cursor is following the slider ball at provided time
+    #[inline]
+    pub fn is_tracking(&self, time: f64) -> bool {
+        time >= self.start_time
+            && time <= self.end_time()
+            && self.hit_result.as_ref().is_some_and(|x| x.is_tracking)
+    }
+
     pub fn is_visible(&self, time: f64, preempt: f32) -> bool {
         time > (self.start_time - preempt as f64)
             && time < self.start_time + self.duration + SLIDER_FADEOUT_TIME
     }
     
-    /// Check slider head hit
-    /// 
-    /// Return values
-    /// - `Some()` - there's was a successfull hit result
-    /// - `None` - there's no successfull hit result or circle was already
-    /// processed
-    pub fn update(
-        &mut self, 
+    /// Missed parts are shown during the slider
+    /// and the final judgement after it's end
+    pub fn is_judgements_visible(&self, time: f64) -> bool {
+        time > self.start_time
+            && time < self.end_time() + SLIDER_FADEOUT_TIME + (JUDGMENTS_FADEOUT_TIME * 2.0)
+    }
+
+    /// Slider head can still be hit at provided time
+    #[inline]
+    pub fn is_head_hittable(&self, time: f64, hit_window: &HitWindow, ruleset: Ruleset) -> bool {
+        self.hit_result.is_none() && ruleset.is_inside(time - self.start_time, hit_window.x50)
+    }
+
+    /// Checks if provided input would hit the slider head
+    /// without assigning any hit result
+    pub fn check_head_hit(
+        &self,
         input: &OsuInput,
         hit_window: &HitWindow,
-        circle_diameter: f32
-    ) -> Option<()> {
-        let _span = tracy_client::span!("hit_objects::slider::update");
-
+        circle_diameter: f32,
+        ruleset: Ruleset,
+    ) -> Option<Hit> {
         if self.hit_result.is_some() {
             return None;
         }
@@ -209,6 +369,35 @@ impl Slider {
 
         let hit_error = (self.start_time - input.ts).abs();
 
+        if let Some(hit) = ruleset.slider_head_hit(hit_error, hit_window) {
+            return Some(hit);
+        }
+
+        // Too early press, but still close enough to be punished
+        if input.ts < self.start_time && ruleset.is_inside(hit_error, hit_window.xmiss) {
+            return Some(Hit::MISS);
+        }
+
+        None
+    }
+
+    /// Check slider head hit
+    /// 
+    /// Return values
+    /// - `Some()` - there's was a successfull hit result
+    /// - `None` - there's no successfull hit result or circle was already
+    /// processed
+    pub fn update(
+        &mut self, 
+        input: &OsuInput,
+        hit_window: &HitWindow,
+        circle_diameter: f32,
+        ruleset: Ruleset,
+    ) -> Option<()> {
+        let _span = tracy_client::span!("hit_objects::slider::update");
+
+        let head_result = self.check_head_hit(input, hit_window, circle_diameter, ruleset)?;
+
         let slider_radius = circle_diameter as f64 / 2.0;
         let slider_ball_progress = self.get_slider_progress(input.ts);
         let slider_ball_pos = self.curve.position_at(
@@ -225,62 +414,126 @@ impl Slider {
 
         let is_inside_slider_ball = slider_ball_distance <= slider_radius;
 
-        if hit_error < hit_window.x50.round() {
-            //println!("[{}] Cursor pos: ({:.2}, {:.2}) Hit error: {} distance: {:2.}, circle_radius: {:.2}, is_in_ball: {}, ball_dist: {:.2}, ball_rad: {:.2}", 
-                //input.ts, 
-                //input.pos.x,
-                //input.pos.y,
-                //hit_error, 
-                //distance, 
-                //circle_diameter / 2.0,
-                //is_inside_slider_ball,
-                //slider_ball_distance,
-                //slider_radius
-            //);
-
-            // For situations when head was hit perfectly but not in
-            // slider ball position
-            let head = CircleHitResult {
-                at: input.ts,
-                pos: input.pos,
-                result: { Hit::X300 }
-            };
+        //println!("[{}] Cursor pos: ({:.2}, {:.2}) Hit error: {} distance: {:2.}, circle_radius: {:.2}, is_in_ball: {}, ball_dist: {:.2}, ball_rad: {:.2}", 
+            //input.ts, 
+            //input.pos.x,
+            //input.pos.y,
+            //hit_error, 
+            //distance, 
+            //circle_diameter / 2.0,
+            //is_inside_slider_ball,
+            //slider_ball_distance,
+            //slider_radius
+        //);
 
-            self.hit_result = Some(
-                SliderResult {
-                    head,
-                    passed_checkpoints: vec![],
-                    state: SliderResultState::Middle,
-                    holding_since: Some(input.ts),
-                    in_radius_since: if is_inside_slider_ball { Some(input.ts) } else { None },
-                    lenience_passed: false,
-                    start_keys: {
-                        if input.keys.k1 && !input.hold.k1 {
-                            1
-                        } else if input.keys.k2 && !input.hold.k2 {
-                            2
-                        } else { panic!("Hitting a slider without any keys pressed?") }
-                    },
-                    is_tracking: is_inside_slider_ball,
-                }
-            );
+        // For situations when head was hit perfectly but not in
+        // slider ball position
+        let head = CircleHitResult {
+            at: input.ts,
+            pos: input.pos,
+            result: head_result,
+        };
+
+        let mut result = SliderResult {
+            head,
+            passed_checkpoints: vec![],
+            parts: Vec::new(),
+            state: SliderResultState::Middle,
+            holding_since: Some(input.ts),
+            in_radius_since: if is_inside_slider_ball { Some(input.ts) } else { None },
+            lenience_passed: false,
+            start_keys: {
+                let pressed = input.keys.difference(&input.hold);
+
+                if pressed.is_left() {
+                    1
+                } else if pressed.is_right() {
+                    2
+                } else { panic!("Hitting a slider without any keys pressed?") }
+            },
+            is_tracking: is_inside_slider_ball,
+        };
 
-            return Some(());
+        result.parts.push(result.head_part(self.pos));
+        self.hit_result = Some(result);
+
+        return Some(());
+    }
+
+    /// Assigns a miss to the whole slider if it wasn't
+    /// touched at all until it's end
+    pub fn update_miss(&mut self, time: f64, hit_window: &HitWindow, ruleset: Ruleset) -> Option<Hit> {
+        if self.hit_result.is_some() {
+            return None;
+        }
+
+        if time < self.end_time() {
+            return None;
+        }
+
+        let mut result = SliderResult {
+            head: CircleHitResult {
+                at: self.start_time + ruleset.window(hit_window.x50),
+                pos: Vector2::new(self.pos.x as f64, self.pos.y as f64),
+                result: Hit::MISS,
+            },
+            passed_checkpoints: vec![],
+            parts: Vec::new(),
+            state: SliderResultState::Middle,
+            holding_since: None,
+            in_radius_since: None,
+            lenience_passed: false,
+            start_keys: 0,
+            is_tracking: false,
+        };
+
+        result.parts.push(result.head_part(self.pos));
+        let hit = result.finish(&self.checkpoints, self.end_time(), self.end_pos(), ruleset);
+        self.hit_result = Some(result);
+
+        Some(hit)
+    }
+
+    /// Misses the head at provided time, used when it's skipped
+    /// by a hit on a later object. Slider body is still
+    /// judged as if the head was missed on it's own
+    pub fn miss_head(&mut self, at: f64) {
+        if self.hit_result.is_some() {
+            return;
         }
 
-        return None;
+        let mut result = SliderResult {
+            head: CircleHitResult {
+                at,
+                pos: Vector2::new(self.pos.x as f64, self.pos.y as f64),
+                result: Hit::MISS,
+            },
+            passed_checkpoints: vec![],
+            parts: Vec::new(),
+            state: SliderResultState::Middle,
+            holding_since: None,
+            in_radius_since: None,
+            lenience_passed: false,
+            start_keys: 0,
+            is_tracking: false,
+        };
+
+        result.parts.push(result.head_part(self.pos));
+        self.hit_result = Some(result);
     }
 
     pub fn update_post(
         &mut self, 
         input: &OsuInput,
         hit_window: &HitWindow,
-        circle_diameter: f32
+        circle_diameter: f32,
+        ruleset: Ruleset,
     ) -> Option<Hit> {
         let _span = tracy_client::span!("hit_objects::slider::update_post");
 
         let slider_radius = circle_diameter as f64 / 2.0;
-        let total_checkpoints = self.checkpoints.len();
+        let end_time = self.end_time();
+        let end_pos = self.end_pos();
 
         // Position at slider for current input
         let slider_progress = self.get_slider_progress(input.ts);
@@ -305,36 +558,36 @@ impl Slider {
                 // Init slider state in case if sliderhead missed
                 // but holding and radius is fine
 
-                let start_window_end = self.start_time + hit_window.x50.round();
-
                 let is_holding = input.is_keys_hold();
                 let is_in_radius = is_inside_hit_circle;
                 let is_tracking = is_holding && is_in_radius;
                 
                 // TODO: Might cause issues, be caution
-                if input.ts >= start_window_end {
-                    self.hit_result = Some(
-                        SliderResult {
-                            head: CircleHitResult {
-                                at: input.ts,
-                                pos: input.pos,
-                                result: Hit::MISS,
-                            },
-                            passed_checkpoints: vec![],
-                            state: SliderResultState::Middle,
-                            holding_since: if is_holding { Some(input.ts) } else { None },
-                            in_radius_since: if is_in_radius { Some(input.ts) } else { None },
-                            lenience_passed: false,
-                            start_keys: {
-                                if input.keys.k1 {
-                                    1
-                                } else if input.keys.k2 {
-                                    2
-                                } else { 0 }
-                            },
-                            is_tracking,
-                        }
-                    );
+                if !ruleset.is_inside(input.ts - self.start_time, hit_window.x50) {
+                    let mut result = SliderResult {
+                        head: CircleHitResult {
+                            at: input.ts,
+                            pos: input.pos,
+                            result: Hit::MISS,
+                        },
+                        passed_checkpoints: vec![],
+                        parts: Vec::new(),
+                        state: SliderResultState::Middle,
+                        holding_since: if is_holding { Some(input.ts) } else { None },
+                        in_radius_since: if is_in_radius { Some(input.ts) } else { None },
+                        lenience_passed: false,
+                        start_keys: {
+                            if input.keys.is_left() {
+                                1
+                            } else if input.keys.is_right() {
+                                2
+                            } else { 0 }
+                        },
+                        is_tracking,
+                    };
+
+                    result.parts.push(result.head_part(self.pos));
+                    self.hit_result = Some(result);
                 }
 
                 return None;
@@ -368,6 +621,22 @@ impl Slider {
                     //input.ts, lenience_hack_time, result.holding_since, result.in_radius_since
                 //);
 
+                // Lazer checks tracking only at the tail
+                // time, there's no second chance until the end
+                if ruleset == Ruleset::Lazer {
+                    let follow_radius = if result.is_tracking {
+                        slider_radius * FOLLOW_CIRCLE_SCALE
+                    } else {
+                        slider_radius
+                    };
+
+                    result.is_tracking = input.keys.is_keys_hit() && distance <= follow_radius;
+                    result.lenience_passed = result.is_tracking;
+                    let final_result = result.finish(&self.checkpoints, end_time, end_pos, ruleset);
+
+                    return Some(final_result);
+                }
+
                 match (result.holding_since, result.in_radius_since) {
                     (Some(holding_since), Some(in_radius_since)) => {
                         if holding_since <= lenience_hack_time
@@ -377,11 +646,8 @@ impl Slider {
                                 //input.ts, is_holding, is_inside_hit_circle
                             //);
                             result.lenience_passed = true;
-                            let Some(final_result) = result.calculate_hit_result(total_checkpoints) else {
-                                panic!("Trying to set Passed slider state without final hit result");
-                            };
+                            let final_result = result.finish(&self.checkpoints, end_time, end_pos, ruleset);
 
-                            result.state = SliderResultState::Passed(final_result);
                             return Some(final_result);
                         }
                     },
@@ -391,14 +657,14 @@ impl Slider {
         }
 
         if result.start_keys > 0 {
-            if result.start_keys == 2 && !input.keys.k1 
-            || result.start_keys == 1 && !input.keys.k2 {
+            if result.start_keys == 2 && !input.keys.is_left()
+            || result.start_keys == 1 && !input.keys.is_right() {
                 result.start_keys = 0;
             }
         }
 
         let is_inside_hit_circle = if result.is_tracking {
-            distance <= slider_radius * 2.4
+            distance <= slider_radius * FOLLOW_CIRCLE_SCALE
         } else {
             is_inside_hit_circle
         };
@@ -410,9 +676,9 @@ impl Slider {
         // when incoming input is after slider end time
         if input.ts <= self.start_time + self.duration {
             let mouse_down_acceptance = if result.start_keys == 1 { 
-                input.is_k1_hold()
+                input.is_left_hold()
             } else {
-                input.is_k2_hold()
+                input.is_right_hold()
             };
 
             let is_holding = if result.start_keys < 1 {
@@ -473,21 +739,26 @@ impl Slider {
             });
 
             if let Some((i, checkpoint)) = closest_checkpoint {
-                if let Some(holding_since) = result.holding_since {
-                    if holding_since < checkpoint.time {
-                        result.passed_checkpoints.push(i)
-                    }
-                }
+                let is_held = result.holding_since
+                    .is_some_and(|holding_since| holding_since < checkpoint.time);
+
+                // Lazer also needs the cursor inside of the follow circle
+                let is_hit = match ruleset {
+                    Ruleset::Stable => is_held,
+                    Ruleset::Lazer => is_held && result.is_tracking,
+                };
 
+                if is_hit {
+                    result.judge_checkpoint(&self.checkpoints, i, true);
+                }
             }
+
+            result.miss_passed_checkpoints(&self.checkpoints, input.ts);
         }
 
         if input.ts >= self.start_time + self.duration {
-            let Some(final_result) = result.calculate_hit_result(total_checkpoints) else {
-                panic!("Trying to set Passed slider state without final hit result");
-            };
+            let final_result = result.finish(&self.checkpoints, end_time, end_pos, ruleset);
 
-            result.state = SliderResultState::Passed(final_result);
             return Some(final_result);
         }
 
@@ -496,6 +767,76 @@ impl Slider {
     
 
 
+    /// Position where the slider ends taking repeats into account
+    pub fn end_pos(&self) -> Pos {
+        let progress = if self.repeats % 2 == 0 { 0.0 } else { 1.0 };
+        let pos = self.curve.position_at(progress);
+
+        Pos {
+            x: self.pos.x + pos.x,
+            y: self.pos.y + pos.y,
+        }
+    }
+
+    /// Position of the end of the slider path, ignoring repeats
+    pub fn path_end_pos(&self) -> Pos {
+        let pos = self.curve.position_at(1.0);
+
+        Pos {
+            x: self.pos.x + pos.x,
+            y: self.pos.y + pos.y,
+        }
+    }
+
+    /// Moves slider with everything attached to it
+    /// by the same amount on both axes
+    pub fn apply_stack_offset(&mut self, offset: f32) {
+        self.pos.x -= offset;
+        self.pos.y -= offset;
+
+        for tick in self.ticks.iter_mut().chain(self.checkpoints.iter_mut()) {
+            tick.pos.x -= offset;
+            tick.pos.y -= offset;
+        }
+    }
+
+    /// Moves slider with an affine transformation `f` of the
+    /// playfield, like mirroring or rotation around a point.
+    ///
+    /// Control points are relative to the slider position, so
+    /// curve is rebuilt from them and ticks, checkpoints and
+    /// reverse arrows are matching the new curve
+    pub fn transform(&mut self, f: impl Fn(Vector2<f32>) -> Vector2<f32>) {
+        let origin = Vector2::new(self.pos.x, self.pos.y);
+        let new_origin = f(origin);
+
+        for control_point in self.path.control_points_mut() {
+            let pos = f(origin + Vector2::new(control_point.pos.x, control_point.pos.y)) - new_origin;
+
+            control_point.pos = Pos {
+                x: pos.x,
+                y: pos.y,
+            };
+        }
+
+        self.curve = self.path.curve().clone();
+        self.pos = Pos {
+            x: new_origin.x,
+            y: new_origin.y,
+        };
+
+        for tick in self.ticks.iter_mut().chain(self.checkpoints.iter_mut()) {
+            tick.pos = f(tick.pos);
+        }
+
+        for (i, arrow) in self.reverse_arrows.iter_mut().enumerate() {
+            let (_, angle) = reverse_arrow_placement(&self.curve, self.pos, i as i32 + 1);
+            arrow.angle = angle;
+        }
+
+        self.render = None;
+    }
+
     /// (x, y, width, height)
     pub fn bounding_box(&self, radius: f32) -> Rectangle {
         let mut min_x = f32::MAX;
@@ -0,0 +1,227 @@
+use std::f64::consts::PI;
+
+use rosu_map::util::Pos;
+
+use crate::{math::diff_rate, osu_input::OsuInput};
+
+use super::{Hit, JUDGMENTS_FADEOUT_TIME, SPINNER_FADEIN_TIME, SPINNER_FADEOUT_TIME};
+
+/// Spinners are always placed at the center of the playfield
+pub const SPINNER_CENTER: Pos = Pos { x: 256.0, y: 192.0 };
+
+/// Maximum amount of radians that can be spun in one real millisecond.
+/// Stable caps spinning speed at ~477 rpm
+pub const SPINNER_MAX_RADIANS_PER_MS: f64 = 0.05;
+
+/// Amount of spins after the required ones that doesn't count
+/// towards bonus. Stable gives bonus starting from the third extra spin
+pub const SPINNER_BONUS_SPINS_GAP: f64 = 2.0;
+
+#[derive(Debug, Clone)]
+pub struct SpinnerHitResult {
+    pub at: f64,
+    pub result: Hit,
+    /// Total spins done during spinner
+    pub spins: f64,
+    /// Full spins above required ones, each one is worth 1000 points
+    pub bonus_spins: u32,
+}
+
+/// Live state of the spinner, updated on every input
+/// inside spinner duration
+#[derive(Debug, Default, Clone)]
+pub struct SpinnerState {
+    /// Total amount of radians spun, direction doesn't matter
+    pub total_rotation: f64,
+
+    /// Current visual rotation of the spinner in radians
+    pub angle: f64,
+
+    /// Smoothed rotations per minute, only used for displaying
+    pub rpm: f64,
+
+    last_angle: Option<f64>,
+    last_ts: Option<f64>,
+}
+
+impl SpinnerState {
+    #[inline]
+    pub fn spins(&self) -> f64 {
+        self.total_rotation / (2.0 * PI)
+    }
+}
+
+pub struct Spinner {
+    pub start_time: f64,
+    pub duration: f64,
+    pub pos: Pos,
+
+    /// Amount of full spins required to get x300
+    pub required_spins: f64,
+
+    pub state: SpinnerState,
+    pub hit_result: Option<SpinnerHitResult>,
+}
+
+impl Spinner {
+    pub fn new(start_time: f64, duration: f64, od: f32) -> Self {
+        Self {
+            start_time,
+            duration,
+            pos: SPINNER_CENTER,
+            required_spins: Self::calc_required_spins(duration, od),
+            state: SpinnerState::default(),
+            hit_result: None,
+        }
+    }
+
+    /// Amount of spins required to fully clear the spinner
+    /// based on it's duration and OD
+    pub fn calc_required_spins(duration: f64, od: f32) -> f64 {
+        let spins_per_second = diff_rate(od, 3.0, 5.0, 7.5) as f64;
+        ((duration / 1000.0) * spins_per_second).floor()
+    }
+
+    #[inline]
+    pub fn end_time(&self) -> f64 {
+        self.start_time + self.duration
+    }
+
+    /// Progress of clearing the spinner
+    ///
+    /// 0.0 >= Return value <= 1.0
+    #[inline]
+    pub fn clear_progress(&self) -> f64 {
+        if self.required_spins <= 0.0 {
+            return 1.0;
+        }
+
+        (self.state.spins() / self.required_spins).clamp(0.0, 1.0)
+    }
+
+    pub fn is_visible(&self, time: f64) -> bool {
+        time > self.start_time - SPINNER_FADEIN_TIME
+            && time < self.end_time() + SPINNER_FADEOUT_TIME
+    }
+
+    pub fn is_judgements_visible(&self, time: f64) -> bool {
+        time > self.start_time
+            && time < self.end_time() + SPINNER_FADEOUT_TIME + (JUDGMENTS_FADEOUT_TIME * 2.0)
+    }
+
+    /// Calculates final judgement based on the amount of spins.
+    /// Same thresholds as stable uses
+    pub fn calculate_hit_result(&self) -> Hit {
+        if self.required_spins <= 0.0 {
+            return Hit::X300;
+        }
+
+        let ratio = self.state.spins() / self.required_spins;
+
+        if ratio >= 1.0 {
+            Hit::X300
+        } else if ratio >= 0.9 {
+            Hit::X100
+        } else if ratio >= 0.75 {
+            Hit::X50
+        } else {
+            Hit::MISS
+        }
+    }
+
+    /// Finalizes the spinner if it's end time is passed
+    /// without any inputs
+    pub fn update_miss(&mut self, time: f64) -> Option<Hit> {
+        if self.hit_result.is_some() || time < self.end_time() {
+            return None;
+        }
+
+        let result = self.calculate_hit_result();
+
+        self.hit_result = Some(SpinnerHitResult {
+            at: self.end_time(),
+            result,
+            spins: self.state.spins(),
+            bonus_spins: 0,
+        });
+
+        Some(result)
+    }
+
+    /// Tracks cursor rotation around the spinner center
+    ///
+    /// Spinning speed is capped in real time, so with `rate`
+    /// above 1.0 less spins can be done during the same spinner
+    ///
+    /// Return values
+    /// - `Some()` - spinner is finished and got it's final judgement
+    /// - `None` - spinner is still going or was already processed
+    pub fn update(&mut self, input: &OsuInput, rate: f64) -> Option<Hit> {
+        let _span = tracy_client::span!("hit_objects::spinner::update");
+
+        if self.hit_result.is_some() {
+            return None;
+        }
+
+        if input.ts < self.start_time {
+            return None;
+        }
+
+        let ts = input.ts.min(self.end_time());
+
+        let angle = (input.pos.y - self.pos.y as f64)
+            .atan2(input.pos.x - self.pos.x as f64);
+
+        // Rotation only counts while any key is pressed
+        if input.keys.is_keys_hit() {
+            if let (Some(last_angle), Some(last_ts)) = (self.state.last_angle, self.state.last_ts) {
+                let mut delta = angle - last_angle;
+
+                // Shortest way around the circle
+                if delta > PI {
+                    delta -= 2.0 * PI;
+                } else if delta < -PI {
+                    delta += 2.0 * PI;
+                }
+
+                let elapsed = (ts - last_ts).max(0.0) / rate;
+                let max_delta = SPINNER_MAX_RADIANS_PER_MS * elapsed;
+
+                let delta = delta.clamp(-max_delta, max_delta);
+
+                self.state.total_rotation += delta.abs();
+                self.state.angle += delta;
+
+                if elapsed > 0.0 {
+                    let current_rpm = (delta.abs() / elapsed) * 60000.0 / (2.0 * PI);
+                    self.state.rpm = self.state.rpm * 0.9 + current_rpm * 0.1;
+                }
+            }
+
+            self.state.last_angle = Some(angle);
+            self.state.last_ts = Some(ts);
+        } else {
+            self.state.last_angle = None;
+            self.state.last_ts = None;
+        }
+
+        if input.ts >= self.end_time() {
+            let result = self.calculate_hit_result();
+
+            let bonus_spins = (self.state.spins() - self.required_spins - SPINNER_BONUS_SPINS_GAP)
+                .floor()
+                .max(0.0) as u32;
+
+            self.hit_result = Some(SpinnerHitResult {
+                at: self.end_time(),
+                result,
+                spins: self.state.spins(),
+                bonus_spins,
+            });
+
+            return Some(result);
+        }
+
+        None
+    }
+}
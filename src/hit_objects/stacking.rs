@@ -0,0 +1,191 @@
+use rosu_map::{util::Pos, Beatmap};
+
+use crate::math::{calc_hitcircle_diameter, calculate_preempt_fadein};
+
+use super::{Object, ObjectKind};
+
+/// Objects closer than that are considered as stacked
+pub const STACK_DISTANCE: f32 = 3.0;
+
+#[inline]
+fn distance(a: Pos, b: Pos) -> f32 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+#[inline]
+fn is_spinner(object: &Object) -> bool {
+    matches!(object.kind, ObjectKind::Spinner(_))
+}
+
+#[inline]
+fn is_slider(object: &Object) -> bool {
+    matches!(object.kind, ObjectKind::Slider(_))
+}
+
+fn pos(object: &Object) -> Pos {
+    match &object.kind {
+        ObjectKind::Circle(circle) => circle.pos,
+        ObjectKind::Slider(slider) => slider.pos,
+        ObjectKind::Spinner(spinner) => spinner.pos,
+    }
+}
+
+fn end_pos(object: &Object) -> Pos {
+    match &object.kind {
+        ObjectKind::Slider(slider) => slider.end_pos(),
+        _ => pos(object),
+    }
+}
+
+/// Calculates stack heights and moves objects to their
+/// stacked positions. Same algorithm as stable uses,
+/// beatmaps before v6 are using legacy one
+pub fn apply_stacking(objects: &mut [Object], map: &Beatmap) {
+    let _span = tracy_client::span!("hit_objects::stacking::apply_stacking");
+
+    if objects.is_empty() {
+        return;
+    }
+
+    let (preempt, _) = calculate_preempt_fadein(map.approach_rate);
+    let stack_threshold = preempt as f64 * map.stack_leniency as f64;
+
+    let heights = if map.format_version >= 6 {
+        calc_stack_heights(objects, stack_threshold)
+    } else {
+        calc_stack_heights_legacy(objects, stack_threshold)
+    };
+
+    // Stable uses `radius / 10` as an offset for every stacked object
+    let stack_offset = calc_hitcircle_diameter(map.circle_size) / 20.0;
+
+    for (object, height) in objects.iter_mut().zip(heights) {
+        if height == 0 {
+            continue;
+        }
+
+        let offset = height as f32 * stack_offset;
+
+        match &mut object.kind {
+            ObjectKind::Circle(circle) => {
+                circle.pos.x -= offset;
+                circle.pos.y -= offset;
+            },
+            ObjectKind::Slider(slider) => slider.apply_stack_offset(offset),
+            ObjectKind::Spinner(_) => {},
+        }
+    }
+}
+
+fn calc_stack_heights(objects: &[Object], stack_threshold: f64) -> Vec<i32> {
+    let mut heights = vec![0; objects.len()];
+
+    // Whole beatmap is processed at once, so there's no need
+    // to extend the end index like lazer does for partial ranges
+    let start_index = 0;
+    let end_index = objects.len() - 1;
+
+    for i in (start_index + 1..=end_index).rev() {
+        let mut n = i;
+        let mut i = i;
+
+        if heights[i] != 0 || is_spinner(&objects[i]) {
+            continue;
+        }
+
+        match &objects[i].kind {
+            ObjectKind::Circle(_) => {
+                while n > 0 {
+                    n -= 1;
+
+                    let object_n = &objects[n];
+
+                    if is_spinner(object_n) {
+                        continue;
+                    }
+
+                    if objects[i].start_time - object_n.end_time() > stack_threshold {
+                        break;
+                    }
+
+                    // Stacking going downwards from slider end
+                    if is_slider(object_n) && distance(end_pos(object_n), pos(&objects[i])) < STACK_DISTANCE {
+                        let offset = heights[i] - heights[n] + 1;
+
+                        for (j, object_j) in objects.iter().enumerate().take(i + 1).skip(n + 1) {
+                            if distance(end_pos(object_n), pos(object_j)) < STACK_DISTANCE {
+                                heights[j] -= offset;
+                            }
+                        }
+
+                        break;
+                    }
+
+                    if distance(pos(object_n), pos(&objects[i])) < STACK_DISTANCE {
+                        heights[n] = heights[i] + 1;
+                        i = n;
+                    }
+                }
+            },
+            ObjectKind::Slider(_) => {
+                while n > start_index {
+                    n -= 1;
+
+                    let object_n = &objects[n];
+
+                    if is_spinner(object_n) {
+                        continue;
+                    }
+
+                    if objects[i].start_time - object_n.start_time > stack_threshold {
+                        break;
+                    }
+
+                    if distance(end_pos(object_n), pos(&objects[i])) < STACK_DISTANCE {
+                        heights[n] = heights[i] + 1;
+                        i = n;
+                    }
+                }
+            },
+            ObjectKind::Spinner(_) => {},
+        }
+    }
+
+    heights
+}
+
+/// Stacking used by beatmaps with format version below 6
+fn calc_stack_heights_legacy(objects: &[Object], stack_threshold: f64) -> Vec<i32> {
+    let mut heights = vec![0; objects.len()];
+
+    for (i, current) in objects.iter().enumerate() {
+        if heights[i] != 0 && !is_slider(current) {
+            continue;
+        }
+
+        let mut start_time = current.end_time();
+        let mut slider_stack = 0;
+
+        let current_end_pos = match &current.kind {
+            ObjectKind::Slider(slider) => slider.path_end_pos(),
+            _ => pos(current),
+        };
+
+        for (j, object_j) in objects.iter().enumerate().skip(i + 1) {
+            if object_j.start_time - stack_threshold > start_time {
+                break;
+            }
+
+            if distance(pos(object_j), pos(current)) < STACK_DISTANCE {
+                heights[i] += 1;
+                start_time = object_j.end_time();
+            } else if distance(pos(object_j), current_end_pos) < STACK_DISTANCE {
+                slider_stack += 1;
+                heights[j] -= slider_stack;
+                start_time = object_j.end_time();
+            }
+        }
+    }
+
+    heights
+}
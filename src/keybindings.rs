@@ -0,0 +1,293 @@
+use std::fmt;
+
+use thiserror::Error;
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+/// Keyboard key or mouse button
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BindingInput {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub input: BindingInput,
+    /// Control has to be held together with the input
+    pub ctrl: bool,
+}
+
+impl KeyBinding {
+    pub const fn key(code: KeyCode) -> Self {
+        Self {
+            input: BindingInput::Key(code),
+            ctrl: false,
+        }
+    }
+
+    pub const fn ctrl_key(code: KeyCode) -> Self {
+        Self {
+            input: BindingInput::Key(code),
+            ctrl: true,
+        }
+    }
+
+    pub const fn mouse(button: MouseButton) -> Self {
+        Self {
+            input: BindingInput::Mouse(button),
+            ctrl: false,
+        }
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl + ")?;
+        }
+
+        match self.input {
+            BindingInput::Key(code) => write!(f, "{code:?}"),
+            BindingInput::Mouse(button) => write!(f, "Mouse {button:?}"),
+        }
+    }
+}
+
+/// Part of the client where action is used. Same binding
+/// can be used by actions of different contexts
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ActionContext {
+    Gameplay,
+    SongSelection,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    K1,
+    K2,
+    QuitPlay,
+    Skip,
+    QuickRetry,
+    TogglePractice,
+    StartBeatmap,
+    RandomBeatmap,
+    NextBeatmap,
+    PreviousBeatmap,
+    ToggleSettings,
+    CloseSettings,
+    ToggleModSelect,
+}
+
+impl Action {
+    pub const ALL: [Action; 13] = [
+        Action::K1,
+        Action::K2,
+        Action::QuitPlay,
+        Action::Skip,
+        Action::QuickRetry,
+        Action::TogglePractice,
+        Action::StartBeatmap,
+        Action::RandomBeatmap,
+        Action::NextBeatmap,
+        Action::PreviousBeatmap,
+        Action::ToggleSettings,
+        Action::CloseSettings,
+        Action::ToggleModSelect,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::K1 => "Left click (K1)",
+            Action::K2 => "Right click (K2)",
+            Action::QuitPlay => "Quit play",
+            Action::Skip => "Skip",
+            Action::QuickRetry => "Quick retry (hold)",
+            Action::TogglePractice => "Toggle practice",
+            Action::StartBeatmap => "Start beatmap",
+            Action::RandomBeatmap => "Random beatmap",
+            Action::NextBeatmap => "Next beatmap",
+            Action::PreviousBeatmap => "Previous beatmap",
+            Action::ToggleSettings => "Toggle settings",
+            Action::CloseSettings => "Close settings",
+            Action::ToggleModSelect => "Toggle mod select",
+        }
+    }
+
+    pub fn context(&self) -> ActionContext {
+        match self {
+            Action::K1
+            | Action::K2
+            | Action::QuitPlay
+            | Action::Skip
+            | Action::QuickRetry
+            | Action::TogglePractice => ActionContext::Gameplay,
+            _ => ActionContext::SongSelection,
+        }
+    }
+
+    /// Mouse buttons are only passed to the gameplay
+    #[inline]
+    pub fn allows_mouse(&self) -> bool {
+        matches!(self, Action::K1 | Action::K2)
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum KeyBindingError {
+    #[error("`{0}` is already used by `{1}`")]
+    Conflict(KeyBinding, &'static str),
+    #[error("`{0}` can't be bound to a mouse button")]
+    MouseNotAllowed(&'static str),
+}
+
+/// Binding of every [`Action`]. Lives only in the [`crate::config::Config`]
+/// of the current session, defaults are used on every start
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    bindings: Vec<(Action, KeyBinding)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            bindings: vec![
+                (Action::K1, KeyBinding::key(KeyCode::KeyZ)),
+                (Action::K2, KeyBinding::key(KeyCode::KeyX)),
+                (Action::QuitPlay, KeyBinding::key(KeyCode::Escape)),
+                (Action::Skip, KeyBinding::key(KeyCode::Space)),
+                (Action::QuickRetry, KeyBinding::key(KeyCode::Backquote)),
+                (Action::TogglePractice, KeyBinding::ctrl_key(KeyCode::KeyP)),
+                (Action::StartBeatmap, KeyBinding::key(KeyCode::Enter)),
+                (Action::RandomBeatmap, KeyBinding::key(KeyCode::F2)),
+                (Action::NextBeatmap, KeyBinding::key(KeyCode::ArrowDown)),
+                (Action::PreviousBeatmap, KeyBinding::key(KeyCode::ArrowUp)),
+                (Action::ToggleSettings, KeyBinding::ctrl_key(KeyCode::KeyO)),
+                (Action::CloseSettings, KeyBinding::key(KeyCode::Escape)),
+                (Action::ToggleModSelect, KeyBinding::key(KeyCode::F1)),
+            ],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: Action) -> KeyBinding {
+        self.bindings.iter()
+            .find(|(x, _)| *x == action)
+            .map(|(_, binding)| *binding)
+            .expect("every action has a binding")
+    }
+
+    /// Action of the same context that already uses the `binding`
+    pub fn conflict(&self, action: Action, binding: KeyBinding) -> Option<Action> {
+        self.bindings.iter()
+            .find(|(x, other)| {
+                *x != action
+                    && x.context() == action.context()
+                    && *other == binding
+            })
+            .map(|(x, _)| *x)
+    }
+
+    pub fn set(&mut self, action: Action, binding: KeyBinding) -> Result<(), KeyBindingError> {
+        if matches!(binding.input, BindingInput::Mouse(_)) && !action.allows_mouse() {
+            return Err(KeyBindingError::MouseNotAllowed(action.name()));
+        }
+
+        if let Some(other) = self.conflict(action, binding) {
+            return Err(KeyBindingError::Conflict(binding, other.name()));
+        }
+
+        for (x, current) in self.bindings.iter_mut() {
+            if *x == action {
+                *current = binding;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Action triggered by the input. Bindings without control
+    /// are triggered even if it's held, unless there's
+    /// a binding of the same input with control
+    pub fn action(&self, context: ActionContext, input: BindingInput, ctrl: bool) -> Option<Action> {
+        let mut matching = self.bindings.iter()
+            .filter(|(x, binding)| x.context() == context && binding.input == input);
+
+        let exact = matching.clone().find(|(_, binding)| binding.ctrl == ctrl);
+        let fallback = matching.find(|(_, binding)| !binding.ctrl);
+
+        exact.or(fallback).map(|(x, _)| *x)
+    }
+
+    /// Input is bound to the `action`, control isn't taken into account,
+    /// used for releases, which are triggered in any order
+    #[inline]
+    pub fn is_bound(&self, action: Action, input: BindingInput) -> bool {
+        self.get(action).input == input
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use winit::{event::MouseButton, keyboard::KeyCode};
+
+    use super::{Action, ActionContext, BindingInput, KeyBinding, KeyBindingError, KeyBindings};
+
+    #[test]
+    fn test_default_bindings_have_no_conflicts() {
+        let bindings = KeyBindings::default();
+
+        for action in Action::ALL {
+            assert_eq!(bindings.conflict(action, bindings.get(action)), None, "{action:?}");
+        }
+    }
+
+    #[test]
+    fn test_conflict_is_rejected() {
+        let mut bindings = KeyBindings::default();
+
+        assert_eq!(
+            bindings.set(Action::K1, KeyBinding::key(KeyCode::KeyX)),
+            Err(KeyBindingError::Conflict(KeyBinding::key(KeyCode::KeyX), Action::K2.name())),
+        );
+        assert_eq!(bindings.get(Action::K1), KeyBinding::key(KeyCode::KeyZ));
+
+        // Different contexts can share keys
+        assert!(bindings.set(Action::K1, KeyBinding::key(KeyCode::Enter)).is_ok());
+        assert_eq!(
+            bindings.action(ActionContext::Gameplay, BindingInput::Key(KeyCode::Enter), false),
+            Some(Action::K1)
+        );
+    }
+
+    #[test]
+    fn test_mouse_bindings() {
+        let mut bindings = KeyBindings::default();
+
+        assert!(bindings.set(Action::K2, KeyBinding::mouse(MouseButton::Left)).is_ok());
+        assert_eq!(
+            bindings.set(Action::StartBeatmap, KeyBinding::mouse(MouseButton::Right)),
+            Err(KeyBindingError::MouseNotAllowed(Action::StartBeatmap.name())),
+        );
+        assert!(bindings.is_bound(Action::K2, BindingInput::Mouse(MouseButton::Left)));
+    }
+
+    #[test]
+    fn test_ctrl_bindings() {
+        let mut bindings = KeyBindings::default();
+        let key_o = BindingInput::Key(KeyCode::KeyO);
+
+        assert_eq!(bindings.action(ActionContext::SongSelection, key_o, false), None);
+        assert_eq!(bindings.action(ActionContext::SongSelection, key_o, true), Some(Action::ToggleSettings));
+
+        assert!(bindings.set(Action::RandomBeatmap, KeyBinding::key(KeyCode::KeyO)).is_ok());
+        assert_eq!(bindings.action(ActionContext::SongSelection, key_o, false), Some(Action::RandomBeatmap));
+        assert_eq!(bindings.action(ActionContext::SongSelection, key_o, true), Some(Action::ToggleSettings));
+
+        // Held control doesn't block other bindings
+        assert_eq!(
+            bindings.action(ActionContext::SongSelection, BindingInput::Key(KeyCode::Enter), true),
+            Some(Action::StartBeatmap)
+        );
+    }
+}
@@ -16,11 +16,16 @@ cfg_if::cfg_if! {
         pub mod skin_manager;
         pub mod vertex;
         pub mod config;
+        pub mod keybindings;
         pub mod hit_circle_instance;
         pub mod slider_instance;
         pub mod timer;
+        pub mod countdown;
+        pub mod practice;
         pub mod skin_ini;
         pub mod processor;
+        pub mod mods;
+        pub mod transforms;
 
         pub mod osu_input;
     } else {
@@ -36,11 +41,16 @@ cfg_if::cfg_if! {
         pub mod skin_manager;
         pub mod vertex;
         pub mod config;
+        pub mod keybindings;
         pub mod hit_circle_instance;
         pub mod slider_instance;
         pub mod timer;
+        pub mod countdown;
+        pub mod practice;
         pub mod skin_ini;
         pub mod processor;
+        pub mod mods;
+        pub mod transforms;
         pub mod egui_state;
         mod song_select_state;
         pub mod renderer;
@@ -48,6 +58,8 @@ cfg_if::cfg_if! {
         mod screen;
         pub mod osu_db;
         mod frameless_source;
+        mod time_stretch;
+        pub mod gameplay_thread;
         pub mod osu_state;
     }
 }
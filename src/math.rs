@@ -1,5 +1,7 @@
 use cgmath::Vector2;
 
+use crate::hit_objects::{HIDDEN_FADE_IN_MULTIPLIER, HIDDEN_FADE_OUT_MULTIPLIER};
+
 pub const OSU_COORDS_WIDTH: f32 = 512.0;
 pub const OSU_COORDS_HEIGHT: f32 = 384.0;
 
@@ -76,6 +78,8 @@ pub fn calc_opposite_direction_degree(p1: Vector2<f32>, p2: Vector2<f32>) -> f32
 }
 
 /// Return preempt and fadein based on AR
+/// Preempt and fade in are in beatmap time, with rate changing
+/// mods they're shorter in real time the same way as in stable
 pub fn calculate_preempt_fadein(ar: f32) -> (f32, f32) {
     if ar > 5.0 {
         (
@@ -118,6 +122,19 @@ pub fn calc_fade_alpha(
     }
 }
 
+/// Alpha of a hit circle with hidden, it fades in right after
+/// appearing and fades out long before it's start time
+pub fn calc_hidden_alpha(time: f64, start_time: f64, preempt: f64) -> f64 {
+    let fade_in_start = start_time - preempt;
+    let fade_in_end = fade_in_start + preempt * HIDDEN_FADE_IN_MULTIPLIER;
+    let fade_out_end = fade_in_end + preempt * HIDDEN_FADE_OUT_MULTIPLIER;
+
+    if time < fade_in_end {
+        calc_progress(time, fade_in_start, fade_in_end)
+    } else {
+        1.0 - calc_progress(time, fade_in_end, fade_out_end)
+    }.clamp(0.0, 1.0)
+}
 
 #[test]
 pub fn test_progress() {
@@ -131,3 +148,13 @@ pub fn test_directiondegrees() {
 
     assert_eq!(calc_opposite_direction_degree(p1, p2), 270.0)
 }
+
+#[test]
+pub fn test_hidden_alpha() {
+    // 1000ms preempt: fade in 0..400, fade out 400..700
+    assert_eq!(calc_hidden_alpha(-100.0, 1000.0, 1000.0), 0.0);
+    assert_eq!(calc_hidden_alpha(200.0, 1000.0, 1000.0), 0.5);
+    assert_eq!(calc_hidden_alpha(400.0, 1000.0, 1000.0), 1.0);
+    assert_eq!(calc_hidden_alpha(700.0, 1000.0, 1000.0), 0.0);
+    assert_eq!(calc_hidden_alpha(1000.0, 1000.0, 1000.0), 0.0);
+}
@@ -0,0 +1,230 @@
+use bitflags::bitflags;
+use rosu_map::{section::hit_objects::HitObjectKind, Beatmap};
+
+use crate::math::OSU_COORDS_HEIGHT;
+
+bitflags! {
+    /// Same bits as the `mods` field of `.osr` replays
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+    pub struct Mods: u32 {
+        const NO_FAIL = 1 << 0;
+        const EASY = 1 << 1;
+        const TOUCH_DEVICE = 1 << 2;
+        const HIDDEN = 1 << 3;
+        const HARD_ROCK = 1 << 4;
+        const SUDDEN_DEATH = 1 << 5;
+        const DOUBLE_TIME = 1 << 6;
+        const RELAX = 1 << 7;
+        const HALF_TIME = 1 << 8;
+        /// Always set together with [`Mods::DOUBLE_TIME`]
+        const NIGHTCORE = 1 << 9;
+        const FLASHLIGHT = 1 << 10;
+        const AUTOPLAY = 1 << 11;
+        const SPUN_OUT = 1 << 12;
+        const AUTOPILOT = 1 << 13;
+        /// Always set together with [`Mods::SUDDEN_DEATH`]
+        const PERFECT = 1 << 14;
+        const CINEMA = 1 << 22;
+        const SCORE_V2 = 1 << 29;
+        const MIRROR = 1 << 30;
+    }
+}
+
+/// Mods on the left can't be enabled together with the mods on the right
+const INCOMPATIBLE: [(Mods, Mods); 6] = [
+    (Mods::EASY, Mods::HARD_ROCK),
+    (Mods::DOUBLE_TIME.union(Mods::NIGHTCORE), Mods::HALF_TIME),
+    (Mods::NO_FAIL, Mods::SUDDEN_DEATH.union(Mods::PERFECT)),
+    (Mods::RELAX, Mods::AUTOPILOT),
+    (Mods::AUTOPLAY, Mods::RELAX.union(Mods::AUTOPILOT).union(Mods::SPUN_OUT)),
+    (Mods::SPUN_OUT, Mods::AUTOPILOT),
+];
+
+impl Mods {
+    /// Mods that can't be enabled together with any of `self`
+    pub fn incompatible(&self) -> Mods {
+        let mut result = Mods::empty();
+
+        for (left, right) in INCOMPATIBLE {
+            if self.intersects(left) {
+                result |= right;
+            }
+
+            if self.intersects(right) {
+                result |= left;
+            }
+        }
+
+        result
+    }
+
+    /// Turns `mods` on or off keeping the combination valid,
+    /// incompatible mods are turned off. Nightcore and perfect
+    /// can't be on without double time and sudden death
+    pub fn toggled(self, mods: Mods) -> Mods {
+        if self.contains(mods) {
+            let mut result = self - mods;
+
+            if mods.contains(Mods::DOUBLE_TIME) {
+                result -= Mods::NIGHTCORE;
+            }
+
+            if mods.contains(Mods::SUDDEN_DEATH) {
+                result -= Mods::PERFECT;
+            }
+
+            return result;
+        }
+
+        let mut mods = mods;
+
+        if mods.contains(Mods::NIGHTCORE) {
+            mods |= Mods::DOUBLE_TIME;
+        }
+
+        if mods.contains(Mods::PERFECT) {
+            mods |= Mods::SUDDEN_DEATH;
+        }
+
+        (self - mods.incompatible()) | mods
+    }
+
+    /// Speed of the gameplay clock
+    pub fn clock_rate(&self) -> f64 {
+        if self.intersects(Mods::DOUBLE_TIME | Mods::NIGHTCORE) {
+            1.5
+        } else if self.contains(Mods::HALF_TIME) {
+            0.75
+        } else {
+            1.0
+        }
+    }
+
+    /// Nightcore speeds up audio together with the pitch,
+    /// other rate mods are time stretching it
+    #[inline]
+    pub fn changes_pitch(&self) -> bool {
+        self.contains(Mods::NIGHTCORE)
+    }
+
+    /// ScoreV1 multiplier, same as stable
+    pub fn score_multiplier(&self) -> f64 {
+        let mut multiplier = 1.0;
+
+        if self.contains(Mods::NO_FAIL) {
+            multiplier *= 0.5;
+        }
+
+        if self.contains(Mods::EASY) {
+            multiplier *= 0.5;
+        }
+
+        if self.contains(Mods::HALF_TIME) {
+            multiplier *= 0.3;
+        }
+
+        if self.contains(Mods::HIDDEN) {
+            multiplier *= 1.06;
+        }
+
+        if self.contains(Mods::HARD_ROCK) {
+            multiplier *= 1.06;
+        }
+
+        if self.contains(Mods::DOUBLE_TIME) {
+            multiplier *= 1.12;
+        }
+
+        if self.contains(Mods::FLASHLIGHT) {
+            multiplier *= 1.12;
+        }
+
+        if self.contains(Mods::SPUN_OUT) {
+            multiplier *= 0.9;
+        }
+
+        if self.intersects(Mods::RELAX | Mods::AUTOPILOT | Mods::AUTOPLAY) {
+            multiplier = 0.0;
+        }
+
+        multiplier
+    }
+}
+
+/// Overrides of beatmap difficulty values, applied
+/// after every other mod. `None` keeps the value as is
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct DifficultyAdjust {
+    pub circle_size: Option<f32>,
+    pub approach_rate: Option<f32>,
+    pub overall_difficulty: Option<f32>,
+    pub hp_drain_rate: Option<f32>,
+}
+
+/// Applies difficulty changing mods to the beatmap.
+///
+/// Should be done before converting beatmap
+/// into [`crate::hit_objects::Object`]'s, so stacking, ticks and
+/// hit windows are calculated from already changed values
+pub fn apply_to_beatmap(map: &mut Beatmap, mods: Mods, adjust: &DifficultyAdjust) {
+    let _span = tracy_client::span!("mods::apply_to_beatmap");
+
+    apply_to_difficulty(map, mods, adjust);
+
+    if mods.contains(Mods::HARD_ROCK) {
+        flip_vertically(map);
+    }
+}
+
+pub fn apply_to_difficulty(map: &mut Beatmap, mods: Mods, adjust: &DifficultyAdjust) {
+    if mods.contains(Mods::HARD_ROCK) {
+        map.circle_size = (map.circle_size * 1.3).min(10.0);
+        map.approach_rate = (map.approach_rate * 1.4).min(10.0);
+        map.overall_difficulty = (map.overall_difficulty * 1.4).min(10.0);
+        map.hp_drain_rate = (map.hp_drain_rate * 1.4).min(10.0);
+    }
+
+    if mods.contains(Mods::EASY) {
+        map.circle_size *= 0.5;
+        map.approach_rate *= 0.5;
+        map.overall_difficulty *= 0.5;
+        map.hp_drain_rate *= 0.5;
+    }
+
+    if let Some(cs) = adjust.circle_size {
+        map.circle_size = cs;
+    }
+
+    if let Some(ar) = adjust.approach_rate {
+        map.approach_rate = ar;
+    }
+
+    if let Some(od) = adjust.overall_difficulty {
+        map.overall_difficulty = od;
+    }
+
+    if let Some(hp) = adjust.hp_drain_rate {
+        map.hp_drain_rate = hp;
+    }
+}
+
+/// Flips every object upside down. Slider control points are
+/// relative to the slider position, so their curve and
+/// ticks are recalculated from the flipped path
+pub fn flip_vertically(map: &mut Beatmap) {
+    for object in map.hit_objects.iter_mut() {
+        match &mut object.kind {
+            HitObjectKind::Circle(circle) => {
+                circle.pos.y = OSU_COORDS_HEIGHT - circle.pos.y;
+            },
+            HitObjectKind::Slider(slider) => {
+                slider.pos.y = OSU_COORDS_HEIGHT - slider.pos.y;
+
+                for control_point in slider.path.control_points_mut() {
+                    control_point.pos.y = -control_point.pos.y;
+                }
+            },
+            _ => {},
+        }
+    }
+}
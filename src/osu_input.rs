@@ -1,20 +1,71 @@
 use cgmath::Vector2;
 
+/// Gameplay keys and mouse buttons.
+///
+/// Mouse buttons are separate keys, so they can be pressed and
+/// held together with keyboard ones. Slider tracking treats
+/// `k1` and `m1` (and `k2` with `m2`) as the same button, same as stable
 #[derive(Debug, Copy, Clone)]
 pub struct KeyboardState {
     pub k1: bool,
     pub k2: bool,
+    pub m1: bool,
+    pub m2: bool,
 }
 
 impl KeyboardState {
     pub fn is_keys_hit(&self) -> bool {
-        self.k1 || self.k2
+        self.k1 || self.k2 || self.m1 || self.m2
+    }
+
+    /// Left button, either `k1` or `m1`
+    #[inline]
+    pub fn is_left(&self) -> bool {
+        self.k1 || self.m1
+    }
+
+    /// Right button, either `k2` or `m2`
+    #[inline]
+    pub fn is_right(&self) -> bool {
+        self.k2 || self.m2
+    }
+
+    /// Keys pressed in both states
+    pub fn intersection(&self, other: &KeyboardState) -> Self {
+        Self {
+            k1: self.k1 && other.k1,
+            k2: self.k2 && other.k2,
+            m1: self.m1 && other.m1,
+            m2: self.m2 && other.m2,
+        }
+    }
+
+    /// Keys pressed in any of the states
+    pub fn union(&self, other: &KeyboardState) -> Self {
+        Self {
+            k1: self.k1 || other.k1,
+            k2: self.k2 || other.k2,
+            m1: self.m1 || other.m1,
+            m2: self.m2 || other.m2,
+        }
+    }
+
+    /// Keys pressed in this state, but not in the `other`
+    pub fn difference(&self, other: &KeyboardState) -> Self {
+        Self {
+            k1: self.k1 && !other.k1,
+            k2: self.k2 && !other.k2,
+            m1: self.m1 && !other.m1,
+            m2: self.m2 && !other.m2,
+        }
     }
 
     pub fn empty() -> Self {
         Self {
             k1: false,
             k2: false,
+            m1: false,
+            m2: false,
         }
     }
 }
@@ -24,6 +75,8 @@ impl Default for KeyboardState {
         Self {
             k1: false,
             k2: false,
+            m1: false,
+            m2: false,
         }
     }
 }
@@ -46,22 +99,21 @@ pub struct OsuInput {
 impl OsuInput {
     /// Returns true if there's input which is not currently being held
     pub fn is_keys_hit_no_hold(&self) -> bool {
-        let k1 = self.keys.k1 && !self.hold.k1;
-        let k2 = self.keys.k2 && !self.hold.k2;
-
-        k1 || k2
+        self.keys.difference(&self.hold).is_keys_hit()
     }
     
     /// Returns true if there's any input that currently being held
     pub fn is_keys_hold(&self) -> bool {
-        (self.keys.k1 && self.hold.k1) || (self.keys.k2 && self.hold.k2)
+        self.keys.intersection(&self.hold).is_keys_hit()
     }
 
-    pub fn is_k1_hold(&self) -> bool {
-        self.keys.k1 && self.hold.k1
+    /// Left button is held, either with `k1` or `m1`
+    pub fn is_left_hold(&self) -> bool {
+        self.keys.intersection(&self.hold).is_left()
     }
 
-    pub fn is_k2_hold(&self) -> bool {
-        self.keys.k2 && self.hold.k2
+    /// Right button is held, either with `k2` or `m2`
+    pub fn is_right_hold(&self) -> bool {
+        self.keys.intersection(&self.hold).is_right()
     }
 }
@@ -9,10 +9,16 @@ use wgpu::{
 };
 use winit::dpi::PhysicalSize;
 use crate::{
-    camera::Camera, config::Config, graphics::Graphics, hit_circle_instance::{ApproachCircleInstance, HitCircleInstance}, hit_objects::{self, hit_window::HitWindow, slider::{SliderRender, SliderResultState}, Hit, Object, CIRCLE_FADEOUT_TIME, CIRCLE_SCALEOUT_MAX, JUDGMENTS_FADEOUT_TIME, REVERSE_ARROW_FADEIN, REVERSE_ARROW_FADEOUT, SLIDER_FADEOUT_TIME}, math::{calc_fade_alpha, calc_hitcircle_diameter, calc_playfield, calc_playfield_scale_factor, calc_progress, lerp}, quad_instance::QuadInstance, quad_renderer::QuadRenderer, rgb::Rgb, skin_manager::SkinManager, slider_instance::SliderInstance, texture::{AtlasTexture, DepthTexture, Texture}, vertex::Vertex
+    camera::Camera, config::Config, graphics::Graphics, hit_circle_instance::{ApproachCircleInstance, HitCircleInstance}, hit_objects::{self, hit_window::HitWindow, slider::{SliderPart, SliderRender, SliderResultState}, Hit, Object, CIRCLE_FADEOUT_TIME, CIRCLE_SCALEOUT_MAX, HIDDEN_FADE_IN_MULTIPLIER, JUDGMENTS_FADEOUT_TIME, REVERSE_ARROW_FADEIN, REVERSE_ARROW_FADEOUT, SLIDER_FADEOUT_TIME, SPINNER_FADEIN_TIME, SPINNER_FADEOUT_TIME}, math::{calc_fade_alpha, calc_hidden_alpha, calc_hitcircle_diameter, calc_playfield, calc_playfield_scale_factor, calc_progress, lerp}, mods::Mods, transforms::{self, TransformSettings}, quad_instance::QuadInstance, quad_renderer::QuadRenderer, rgb::Rgb, skin_manager::SkinManager, slider_instance::SliderInstance, texture::{AtlasTexture, DepthTexture, Texture}, vertex::Vertex
 };
 
 static SLIDER_SCALE: f32 = 2.0;
+
+// In osu!pixels
+static SPINNER_CIRCLE_SIZE: f32 = 360.0;
+static SPINNER_RPM_WIDTH: f32 = 140.0;
+static SPINNER_RPM_HEIGHT: f32 = 40.0;
+static SPINNER_RPM_Y: f32 = 400.0;
 pub const QUAD_INDECIES: &[u16] = &[0, 1, 2, 0, 2, 3];
 
 // TODO: Move it outta her
@@ -76,6 +82,10 @@ pub struct OsuRenderer<'or> {
     offsets: Vector2<f32>,
     hit_circle_diameter: f32,
 
+    /// Visual mods like hidden are applied during objects preparation
+    mods: Mods,
+    transforms: TransformSettings,
+
     // Quad verticies
     quad_verticies: [Vertex; 4],
 
@@ -142,6 +152,17 @@ pub struct OsuRenderer<'or> {
 
     slider_ticks_instance_data: Vec<QuadInstance>,
     slider_ticks_instance_buffer: wgpu::Buffer,
+
+    // Spinner
+    spinner_vertex_buffer: wgpu::Buffer,
+    spinner_quad: QuadRenderer<'or>,
+    spinner_rpm_quad: QuadRenderer<'or>,
+    spinner_instance_data: Vec<QuadInstance>,
+    spinner_instance_buffer: wgpu::Buffer,
+    spinner_approach_instance_data: Vec<HitCircleInstance>,
+    spinner_approach_instance_buffer: wgpu::Buffer,
+    spinner_rpm_instance_data: Vec<QuadInstance>,
+    spinner_rpm_instance_buffer: wgpu::Buffer,
     
     /// Queue of judgements that needs to be rendered
     /// Should be cleared after everything inside is rendered
@@ -686,6 +707,39 @@ impl<'or> OsuRenderer<'or> {
         let slider_reverse_arrow_quad = QuadRenderer::new(graphics.clone(), false);
         slider_reverse_arrow_quad.resize_vertex_centered(10.0, 10.0);
 
+        let spinner_vertex_buffer =
+            graphics
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("spinner vertex buffer"),
+                    contents: bytemuck::cast_slice(
+                        &Vertex::quad_centered(SPINNER_CIRCLE_SIZE, SPINNER_CIRCLE_SIZE)
+                    ),
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                });
+
+        let spinner_quad = QuadRenderer::new(graphics.clone(), false);
+        spinner_quad.resize_vertex_centered(SPINNER_CIRCLE_SIZE, SPINNER_CIRCLE_SIZE);
+
+        let spinner_rpm_quad = QuadRenderer::new(graphics.clone(), false);
+        spinner_rpm_quad.resize_vertex_centered(SPINNER_RPM_WIDTH, SPINNER_RPM_HEIGHT);
+
+        let spinner_instance_data = Vec::new();
+        let spinner_instance_buffer = spinner_quad.create_instance_buffer();
+
+        let spinner_approach_instance_data: Vec<HitCircleInstance> = Vec::new();
+        let spinner_approach_instance_buffer =
+            graphics
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("spinner approach instance buffer"),
+                    contents: bytemuck::cast_slice(&spinner_approach_instance_data),
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                });
+
+        let spinner_rpm_instance_data = Vec::new();
+        let spinner_rpm_instance_buffer = spinner_rpm_quad.create_instance_buffer();
+
         drop(config_lock);
 
         Self {
@@ -720,6 +774,8 @@ impl<'or> OsuRenderer<'or> {
             follow_points_instance_buffer,
             offsets: Vector2::new(0.0, 0.0),
             hit_circle_diameter: 1.0,
+            mods: Mods::empty(),
+            transforms: TransformSettings::default(),
             quad_colored_pipeline,
             slider_settings_buffer,
             slider_settings_bind_group,
@@ -732,11 +788,30 @@ impl<'or> OsuRenderer<'or> {
             slider_ticks_instance_buffer,
             slider_reverse_arrow_quad,
             slider_texture_camera,
+            spinner_vertex_buffer,
+            spinner_quad,
+            spinner_rpm_quad,
+            spinner_instance_data,
+            spinner_instance_buffer,
+            spinner_approach_instance_data,
+            spinner_approach_instance_buffer,
+            spinner_rpm_instance_data,
+            spinner_rpm_instance_buffer,
             config,
             skin_manager,
         }
     }
 
+    pub fn set_mods(&mut self, mods: Mods) {
+        self.mods = mods;
+    }
+
+    /// Only visual transforms are used, others
+    /// are already applied to the objects
+    pub fn set_transforms(&mut self, transforms: TransformSettings) {
+        self.transforms = transforms;
+    }
+
     pub fn prepare(
         &self
     ) {
@@ -795,65 +870,87 @@ impl<'or> OsuRenderer<'or> {
                         continue
                     };
 
-                    let head_range = RangeInclusive::new(
-                        hit_result.head.at - config.judgements.total_time() as f64,
-                        hit_result.head.at + config.judgements.total_time() as f64,
-                    );
+                    let total_time = config.judgements.total_time() as f64;
+
+                    // Only missed parts are shown, on their positions
+                    for part in hit_result.parts.iter().filter(|x| !x.hit) {
+                        // Missed end is not a break, slider
+                        // judgement is shown there anyway
+                        if part.part == SliderPart::End {
+                            continue;
+                        }
 
-                    let slider_end_time = slider.start_time + slider.duration;
+                        if !(part.at - total_time..=part.at + total_time).contains(&time) {
+                            continue;
+                        }
 
-                    let tail_range = RangeInclusive::new(
-                         slider_end_time - config.judgements.total_time() as f64,
-                         slider_end_time + config.judgements.total_time() as f64,
-                    );
-                    
-                    // Judgement for the head
-                    if head_range.contains(&time) {
-                        let head_alpha = calc_fade_alpha(
+                        let alpha = calc_fade_alpha(
                             time,
-                            hit_result.head.at,
+                            part.at,
                             config.judgements.fade_in_ms,
                             config.judgements.stay_on_screen_ms,
                             config.judgements.fade_out_ms,
                         );
 
-                        let entry = JudgementsEntry {
-                            pos: Vector2::new(slider.pos.x as f64, slider.pos.y as f64),
-                            alpha: head_alpha as f32,
-                            result: hit_result.head.result,
-                        };
-
-                        self.judgements_queue.push(entry);
+                        self.judgements_queue.push(JudgementsEntry {
+                            pos: Vector2::new(part.pos.x as f64, part.pos.y as f64),
+                            alpha: alpha as f32,
+                            result: Hit::MISS,
+                        });
                     }
-                    
-                    // Judgement for the tail
+
+                    // Final judgement at the end of the slider
                     if let SliderResultState::Passed(end_result) = hit_result.state {
-                        if !tail_range.contains(&time) {
+                        let slider_end_time = slider.end_time();
+
+                        if !(slider_end_time - total_time..=slider_end_time + total_time).contains(&time) {
                             continue
                         };
 
-                        let pos = slider.curve.position_at(1.0);
-
-                        let end_pos = (
-                            (slider.pos.x + pos.x) as f64,
-                            (slider.pos.y + pos.y) as f64
-                        );
+                        let end_pos = slider.end_pos();
 
                         let end_alpha = calc_fade_alpha(
                             time,
-                            slider.start_time + slider.duration,
+                            slider_end_time,
                             config.judgements.fade_in_ms,
                             config.judgements.stay_on_screen_ms,
                             config.judgements.fade_out_ms,
                         );
 
                         self.judgements_queue.push(JudgementsEntry {
-                            pos: end_pos.into(),
+                            pos: Vector2::new(end_pos.x as f64, end_pos.y as f64),
                             alpha: end_alpha as f32,
                             result: end_result,
                         });
+                    }
+                },
+                hit_objects::ObjectKind::Spinner(spinner) => {
+                    let Some(hit_result) = &spinner.hit_result else {
+                        continue
+                    };
 
+                    let range = RangeInclusive::new(
+                        hit_result.at - config.judgements.total_time() as f64,
+                        hit_result.at + config.judgements.total_time() as f64
+                    );
+
+                    if !range.contains(&time) {
+                        continue
                     }
+
+                    let alpha = calc_fade_alpha(
+                        time,
+                        hit_result.at,
+                        config.judgements.fade_in_ms,
+                        config.judgements.stay_on_screen_ms,
+                        config.judgements.fade_out_ms,
+                    );
+
+                    self.judgements_queue.push(JudgementsEntry {
+                        pos: Vector2::new(spinner.pos.x as f64, spinner.pos.y as f64),
+                        alpha: alpha as f32,
+                        result: hit_result.result,
+                    });
                 },
             }
         };
@@ -887,6 +984,10 @@ impl<'or> OsuRenderer<'or> {
         let config = self.config.read().expect("failed to acquire read lock");
         let skin = self.skin_manager.read().expect("failed to acquire read lock");
 
+        let hidden = self.mods.contains(Mods::HIDDEN);
+        let scale_in = self.transforms.scale_in;
+        let wiggle = self.transforms.wiggle;
+
         for current_index in queue.iter() {
             let object = &objects[*current_index];
 
@@ -913,6 +1014,9 @@ impl<'or> OsuRenderer<'or> {
                     hit_objects::ObjectKind::Slider(_) => {
                         *skin_color
                     },
+                    hit_objects::ObjectKind::Spinner(_) => {
+                        *skin_color
+                    },
                 }
             } else {
                 *skin_color
@@ -939,6 +1043,13 @@ impl<'or> OsuRenderer<'or> {
                     let mut hit_circle_scale = 1.0;
                     let mut render_approach = true;
 
+                    // Note lock shake and wiggle
+                    let mut offset = Vector2::new(object.shake_offset(time), 0.0);
+
+                    if wiggle {
+                        offset += transforms::wiggle_offset(time, object.start_time, preempt as f64);
+                    }
+
                     if let Some(hit_result) = &circle.hit_result {
                         self.quad_debug_instance_data.push(
                             QuadInstance::from_xy_pos(circle.pos.x, circle.pos.y)
@@ -949,7 +1060,10 @@ impl<'or> OsuRenderer<'or> {
                         let progress = calc_progress(time, hit_result.at, hit_result.at + CIRCLE_FADEOUT_TIME);
                         hit_circle_alpha = 1.0 - progress;
 
-                        hit_circle_scale = lerp(1.0, CIRCLE_SCALEOUT_MAX, progress);
+                        // Missed circles are just fading out
+                        if hit_result.result != Hit::MISS {
+                            hit_circle_scale = lerp(1.0, CIRCLE_SCALEOUT_MAX, progress);
+                        }
                         render_approach = false;
                     } else {
                         // In case if there are no hit result keep alpha at 1.0 until late x50 hit window point
@@ -963,12 +1077,21 @@ impl<'or> OsuRenderer<'or> {
                     if time >= object.start_time {
                         render_approach = false;
                     }
+
+                    if hidden {
+                        hit_circle_alpha *= calc_hidden_alpha(time, object.start_time, preempt as f64);
+                        render_approach = false;
+                    }
+
+                    if let Some(mode) = scale_in {
+                        hit_circle_scale *= transforms::scale_in(mode, time, object.start_time, preempt as f64);
+                    }
                     
                     if render_approach {
                         self.approach_circle_instance_data
                             .push(ApproachCircleInstance::new(
-                                circle.pos.x,
-                                circle.pos.y,
+                                circle.pos.x + offset.x,
+                                circle.pos.y + offset.y,
                                 0.0,
                                 alpha as f32,
                                 approach_scale as f32,
@@ -976,8 +1099,8 @@ impl<'or> OsuRenderer<'or> {
                     }
 
                     let hit_circle_instance = HitCircleInstance::new(
-                        circle.pos.x,
-                        circle.pos.y,
+                        circle.pos.x + offset.x,
+                        circle.pos.y + offset.y,
                         0.0,
                         hit_circle_alpha as f32,
                         hit_circle_scale as f32,
@@ -1100,12 +1223,28 @@ impl<'or> OsuRenderer<'or> {
                         body_alpha = (percentage / 100.0).clamp(0.0, 0.95);
                     }
 
+                    // Follow circle stays visible even if body is hidden
+                    let follow_circle_alpha = body_alpha;
+
+                    // Hidden body fades in faster and then slowly fades
+                    // out until the slider end
+                    if hidden {
+                        let fade_in_end = start_time + preempt as f64 * HIDDEN_FADE_IN_MULTIPLIER;
+                        let slider_end = object.start_time + slider.duration;
+
+                        body_alpha = if time < fade_in_end {
+                            calc_progress(time, start_time, fade_in_end)
+                        } else {
+                            1.0 - calc_progress(time, fade_in_end, slider_end)
+                        }.clamp(0.0, 0.95);
+                    }
+
                     // APPROACH
                     let approach_progress = (time - start_time) / (object.start_time - start_time);
 
                     let approach_scale = lerp(1.0, 3.95, 1.0 - approach_progress).clamp(1.0, 4.0);
 
-                    let approach_alpha = if time >= object.start_time {
+                    let approach_alpha = if time >= object.start_time || hidden {
                         0.0
                     } else {
                         body_alpha
@@ -1141,7 +1280,7 @@ impl<'or> OsuRenderer<'or> {
 
                         self.follow_points_instance_data.push(HitCircleInstance {
                             pos: [pos.x + slider.pos.x, pos.y + slider.pos.y, 0.0],
-                            alpha: body_alpha as f32,
+                            alpha: follow_circle_alpha as f32,
                             color: color.to_gpu_values(),
                             scale: 1.0
                         });
@@ -1157,10 +1296,17 @@ impl<'or> OsuRenderer<'or> {
                         slider_body: skin.ini.colours.slider_body.to_gpu_values(),
                     });
 
+                    // Note lock shake and wiggle, only head is moving
+                    let mut offset = Vector2::new(object.shake_offset(time), 0.0);
+
+                    if wiggle {
+                        offset += transforms::wiggle_offset(time, object.start_time, preempt as f64);
+                    }
+
                     self.approach_circle_instance_data
                         .push(ApproachCircleInstance::new(
-                            slider.pos.x,
-                            slider.pos.y,
+                            slider.pos.x + offset.x,
+                            slider.pos.y + offset.y,
                             0.0,
                             approach_alpha as f32,
                             approach_scale as f32,
@@ -1192,12 +1338,20 @@ impl<'or> OsuRenderer<'or> {
                             hit_circle_alpha = 1.0 - progress;
                         }
                     }
+
+                    if hidden {
+                        hit_circle_alpha *= calc_hidden_alpha(time, object.start_time, preempt as f64);
+                    }
+
+                    if let Some(mode) = scale_in {
+                        hit_circle_scale *= transforms::scale_in(mode, time, object.start_time, preempt as f64);
+                    }
                     
                     // HIT CIRCLE
                     self.hit_circle_instance_data
                         .push(HitCircleInstance::new(
-                            slider.pos.x,
-                            slider.pos.y,
+                            slider.pos.x + offset.x,
+                            slider.pos.y + offset.y,
                             0.0,
                             hit_circle_alpha as f32,
                             hit_circle_scale as f32,
@@ -1216,8 +1370,15 @@ impl<'or> OsuRenderer<'or> {
                             // TODO: McOsu doesnt bother with slider ticks fadeout and fadein animations.
                             // and i spend too much time trying to get it right
                             // so i'm also gonna give up on it for now....
+                            // With hidden every tick fades as it's own hit circle
+                            let tick_alpha = if hidden {
+                                calc_hidden_alpha(time, tick.time, preempt as f64)
+                            } else {
+                                body_alpha
+                            };
+
                             self.slider_ticks_instance_data.push(
-                                QuadInstance::from_xy_pos_alpha(tick.pos.x, tick.pos.y, body_alpha as f32)
+                                QuadInstance::from_xy_pos_alpha(tick.pos.x, tick.pos.y, tick_alpha as f32)
                             );
 
                             if time >= tick.time {
@@ -1244,6 +1405,57 @@ impl<'or> OsuRenderer<'or> {
                         panic!("Texture and quad should be present");
                     };
                 }
+                hit_objects::ObjectKind::Spinner(spinner) => {
+                    let _span = tracy_client::span!("osu_renderer prepare_objects2::spinner");
+
+                    let fade_in_start = spinner.start_time - SPINNER_FADEIN_TIME;
+                    let end_time = spinner.end_time();
+
+                    let alpha = if time >= end_time {
+                        1.0 - calc_progress(time, end_time, end_time + SPINNER_FADEOUT_TIME)
+                    } else {
+                        calc_progress(time, fade_in_start, spinner.start_time)
+                    }.clamp(0.0, 1.0) as f32;
+
+                    self.spinner_instance_data.push(
+                        QuadInstance::from_xy_pos_alpha_degree(
+                            spinner.pos.x,
+                            spinner.pos.y,
+                            alpha,
+                            spinner.state.angle.to_degrees() as f32,
+                        )
+                    );
+
+                    // Approach circle shrinks from full size
+                    // to nothing during spinner duration
+                    let approach_scale = (1.0 - calc_progress(time, spinner.start_time, end_time))
+                        .clamp(0.0, 1.0);
+
+                    let approach_alpha = if time >= spinner.start_time && time < end_time {
+                        alpha
+                    } else {
+                        0.0
+                    };
+
+                    self.spinner_approach_instance_data.push(
+                        HitCircleInstance::new(
+                            spinner.pos.x,
+                            spinner.pos.y,
+                            0.0,
+                            approach_alpha,
+                            approach_scale as f32,
+                            &Rgb::new(255, 255, 255),
+                        )
+                    );
+
+                    self.spinner_rpm_instance_data.push(
+                        QuadInstance::from_xy_pos_alpha(
+                            spinner.pos.x,
+                            SPINNER_RPM_Y,
+                            alpha,
+                        )
+                    );
+                }
             }
         }
     }
@@ -1524,6 +1736,12 @@ impl<'or> OsuRenderer<'or> {
         self.slider_reverse_arrow_quad.resize_camera(new_size);
         self.slider_reverse_arrow_quad.transform_camera(self.scale, self.offsets);
 
+        self.spinner_quad.resize_camera(new_size);
+        self.spinner_quad.transform_camera(self.scale, self.offsets);
+
+        self.spinner_rpm_quad.resize_camera(new_size);
+        self.spinner_rpm_quad.transform_camera(self.scale, self.offsets);
+
         // Slider to screen
         self.slider_to_screen_verticies = Vertex::quad_positional(
             0.0,
@@ -1545,12 +1763,16 @@ impl<'or> OsuRenderer<'or> {
         self.camera.zoom(zoom_factor, zoom_center);
         self.quad_debug.zoom_camera(zoom_factor, zoom_center);
         self.slider_reverse_arrow_quad.zoom_camera(zoom_factor, zoom_center);
+        self.spinner_quad.zoom_camera(zoom_factor, zoom_center);
+        self.spinner_rpm_quad.zoom_camera(zoom_factor, zoom_center);
     }
 
     pub fn move_camera(&mut self, delta: Vector2<f32>) {
         self.camera.move_camera(delta);
         self.quad_debug.move_camera(delta);
         self.slider_reverse_arrow_quad.move_camera(delta);
+        self.spinner_quad.move_camera(delta);
+        self.spinner_rpm_quad.move_camera(delta);
     }
 
     pub fn write_camera_buffers(&mut self) {
@@ -1561,6 +1783,8 @@ impl<'or> OsuRenderer<'or> {
 
         self.quad_debug.write_camera_buffer();
         self.slider_reverse_arrow_quad.write_camera_buffer();
+        self.spinner_quad.write_camera_buffer();
+        self.spinner_rpm_quad.write_camera_buffer();
     }
 
     pub fn write_buffers(&mut self) {
@@ -1621,6 +1845,30 @@ impl<'or> OsuRenderer<'or> {
             &self.slider_ticks_instance_data,
             QuadInstance
         );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.spinner_instance_buffer,
+            &self.spinner_instance_data,
+            QuadInstance
+        );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.spinner_approach_instance_buffer,
+            &self.spinner_approach_instance_data,
+            HitCircleInstance
+        );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.spinner_rpm_instance_buffer,
+            &self.spinner_rpm_instance_data,
+            QuadInstance
+        );
     }
 
     /// Clears internal buffers
@@ -1635,6 +1883,9 @@ impl<'or> OsuRenderer<'or> {
         self.quad_debug_instance_data2.clear();
         self.judgements_queue.clear();
         self.slider_ticks_instance_data.clear();
+        self.spinner_instance_data.clear();
+        self.spinner_approach_instance_data.clear();
+        self.spinner_rpm_instance_data.clear();
         self.quad_debug.clear_atlas_buffers();
     }
     
@@ -1715,6 +1966,7 @@ impl<'or> OsuRenderer<'or> {
             // hitobjects order
             let mut current_circle = 0;
             let mut current_slider = 0;
+            let mut current_spinner = 0;
 
             for current_index in queue.iter() {
                 let object = &objects[*current_index];
@@ -1846,6 +2098,42 @@ impl<'or> OsuRenderer<'or> {
                         current_slider += 1;
                         current_circle += 1;
                     },
+                    hit_objects::ObjectKind::Spinner(_) => {
+                        let instance = current_spinner..current_spinner + 1;
+
+                        // Rotating spinner circle
+                        self.spinner_quad.render_on_view_instanced(
+                            view,
+                            &skin.spinner_circle.bind_group,
+                            &self.spinner_instance_buffer,
+                            instance.clone(),
+                        );
+
+                        self.spinner_rpm_quad.render_on_view_instanced(
+                            view,
+                            &skin.spinner_rpm.bind_group,
+                            &self.spinner_rpm_instance_buffer,
+                            instance.clone(),
+                        );
+
+                        // Shrinking approach circle
+                        render_pass.set_pipeline(&self.quad_colored_pipeline);
+                        render_pass.set_bind_group(0, &skin.spinner_approach_circle.bind_group, &[]);
+                        render_pass.set_bind_group(1, self.camera.bind_group(), &[]);
+                        render_pass.set_vertex_buffer(0, self.spinner_vertex_buffer.slice(..));
+                        render_pass.set_vertex_buffer(1, self.spinner_approach_instance_buffer.slice(..));
+                        render_pass.set_index_buffer(
+                            self.hit_circle_index_buffer.slice(..),
+                            wgpu::IndexFormat::Uint16,
+                        );
+                        render_pass.draw_indexed(
+                            0..QUAD_INDECIES.len() as u32,
+                            0,
+                            instance,
+                        );
+
+                        current_spinner += 1;
+                    },
                 }
             }
 
@@ -1,19 +1,42 @@
-use std::{fs::File, io::BufReader, path::{Path, PathBuf}, sync::{mpsc::{channel, Receiver, Sender, TryRecvError}, Arc, RwLock}, time::Duration};
+use std::{fs::File, io::{BufReader, Cursor}, path::{Path, PathBuf}, sync::{mpsc::{channel, Receiver, Sender, TryRecvError}, Arc, RwLock}, time::{Duration, Instant}};
 
 use cgmath::Vector2;
-use egui::{RawInput, Slider};
 use rodio::{source::UniformSourceIterator, Decoder, Sink, Source};
 use rosu_map::Beatmap;
 use wgpu::TextureView;
-use winit::{dpi::{PhysicalPosition, PhysicalSize}, keyboard::KeyCode, window::Window};
+use winit::{dpi::{PhysicalPosition, PhysicalSize}, event::MouseButton, keyboard::KeyCode, window::Window};
 
 use crate::{
-    config::Config, egui_state::EguiState, frameless_source::FramelessSource, graphics::Graphics, hit_objects::{hit_window::HitWindow, Object, ObjectKind}, math::{calc_playfield, calculate_preempt_fadein, calc_hitcircle_diameter}, renderer::cursor::CursorRenderer, osu_db::BeatmapEntry, osu_input::KeyboardState, osu_renderer::OsuRenderer, skin_manager::SkinManager, song_select_state::SongSelectionState, timer::Timer
+    config::Config, countdown::Countdown, gameplay_thread::{GameplaySettings, GameplayThread}, keybindings::{Action, ActionContext, BindingInput}, egui_state::EguiState, frameless_source::FramelessSource, graphics::Graphics, hit_objects::{break_period::{BreakPeriod, SectionResult}, hit_window::HitWindow, ruleset::Ruleset, Object, ObjectKind}, math::{calc_playfield, calculate_preempt_fadein, calc_hitcircle_diameter}, mods::{self, DifficultyAdjust, Mods}, transforms::TransformSettings, renderer::{background::BackgroundRenderer, break_overlay::BreakOverlayRenderer, countdown::CountdownRenderer, cursor::CursorRenderer, flashlight::FlashlightRenderer, health_bar::HealthBarRenderer, pause::{PauseButton, PauseRenderer}, skip::SkipRenderer}, osu_db::BeatmapEntry, osu_input::{KeyboardState, OsuInput}, osu_renderer::OsuRenderer, practice::{LoopResult, Practice, PracticeSection}, screen::{practice::PracticePanel, results::ResultsScreen}, skin_manager::SkinManager, song_select_state::SongSelectionState, time_stretch::TimeStretch, timer::{ClockSnapshot, Timer}
 };
-use crate::processor::OsuProcessor;
+use crate::processor::{autoplay, health_processor::HealthProcessor, judgement::Judgement, results::PlayResults, score_processor::ScoreProcessor};
+
+/// Duration of the slowdown after running out of HP, in ms
+const FAIL_ANIMATION_TIME: f64 = 2000.0;
+/// Audio is paused once the slowdown is over,
+/// so it never has to be played at zero speed
+const FAIL_MIN_AUDIO_SPEED: f32 = 0.05;
+
+/// Intro is only skippable if there's at least that much of it left
+const MIN_INTRO_SKIP: f64 = 1000.0;
+
+/// Quick retry key has to be held that long, ms
+const QUICK_RETRY_HOLD_TIME: f64 = 500.0;
+
+/// Results are shown that long after the end of the last object
+const RESULTS_DELAY: f64 = 1000.0;
 
 pub enum OsuStates {
     Playing,
+    /// Clock and audio are stopped, pause menu is shown
+    Paused,
+    /// Continue was chosen, waiting for the click
+    /// at the position cursor was paused at
+    Resuming,
+    /// Ran out of HP, gameplay slows down until it stops
+    Failed,
+    /// Every object is finished
+    Results,
     SongSelection,
 }
 
@@ -21,10 +44,29 @@ pub enum OsuStateEvent {
     ToSongSelection,
     SetCursorSize(f32),
     ChangeSkin(PathBuf),
+    /// Mods of the next play
+    SetMods(Mods, DifficultyAdjust, TransformSettings),
+    /// Judgement rules of the next play
+    SetRuleset(Ruleset),
     StartBeatmap(BeatmapEntry),
     PlaySound(i32, Box<dyn Source<Item = f32> + Send + Sync>),
+    Retry,
+    /// Plays the last finished play again from it's inputs
+    WatchReplay,
+    /// Plays the section from it's start, rate multiplies mods clock rate
+    StartPractice(PracticeSection, f64),
+    /// Leaves practice mode and restarts the play
+    StopPractice,
 }
 
+/// Inputs of the finished play together with
+/// everything needed to watch it the same way
+#[derive(Debug, Clone)]
+struct RecordedPlay {
+    inputs: Vec<OsuInput>,
+    /// Objects are placed the same only with the same seed
+    random_seed: Option<u64>,
+}
 
 pub struct OsuState<'s> {
     pub window: Arc<Window>,
@@ -33,20 +75,43 @@ pub struct OsuState<'s> {
     pub event_sender: Sender<OsuStateEvent>,
 
     pub sink: Sink,
+    /// Skin sounds, played on top of the beatmap audio
+    pub effects_sink: Sink,
+    /// Beatmap audio is started once the clock reaches zero
+    audio_started: bool,
+    /// Kept to reload the audio on retry, once it has ended
+    audio_file: Option<PathBuf>,
 
     pub current_state: OsuStates,
     pub song_select: SongSelectionState<'s>,
+    results_screen: ResultsScreen,
+    practice_panel: PracticePanel,
 
     skin_manager: Arc<RwLock<SkinManager>>,
+    config: Arc<RwLock<Config>>,
 
     osu_renderer: OsuRenderer<'s>,
 
+    /// Beatmap as it was parsed, without any mods applied
     current_beatmap: Option<Beatmap>,
+    mods: Mods,
+    difficulty_adjust: DifficultyAdjust,
+    /// Random seed is kept here, so every retry
+    /// of the play has the same positions
+    transforms: TransformSettings,
+    /// Rules the play is judged with
+    ruleset: Ruleset,
     preempt: f32,
     fadein: f32,
     current_hit_window: HitWindow,
 
     hit_objects: Vec<Object>,
+    breaks: Vec<BreakPeriod>,
+    /// Pass or fail of every break, decided at the middle of it
+    section_results: Vec<Option<SectionResult>>,
+    countdown: Option<Countdown>,
+    /// Countdown steps that already had their sound played
+    countdown_played: usize,
 
     objects_render_queue: Vec<usize>,
     objects_judgments_render_queue: Vec<usize>,
@@ -54,15 +119,50 @@ pub struct OsuState<'s> {
     osu_clock: Timer,
     
     cursor_renderer: CursorRenderer<'s>,
-
-    input_processor: OsuProcessor,
+    health_bar_renderer: HealthBarRenderer<'s>,
+    flashlight_renderer: FlashlightRenderer<'s>,
+    background_renderer: BackgroundRenderer<'s>,
+    break_overlay_renderer: BreakOverlayRenderer<'s>,
+    countdown_renderer: CountdownRenderer<'s>,
+    skip_renderer: SkipRenderer<'s>,
+    pause_renderer: PauseRenderer<'s>,
+
+    /// Processes inputs of the current play, objects
+    /// above only mirror the states it publishes
+    gameplay: Option<GameplayThread>,
+    /// Last cursor position known to the gameplay in osu!pixels
+    gameplay_cursor_pos: Vector2<f64>,
+    score_processor: ScoreProcessor,
+    health_processor: HealthProcessor,
+
+    /// Inputs of the last finished play
+    last_replay: Option<RecordedPlay>,
+    /// Replay that is being watched, it's inputs
+    /// are passed to the gameplay instead of the player's
+    replay_playback: Option<RecordedPlay>,
+
+    /// Section that is played over and over,
+    /// objects before it are never judged
+    practice: Option<Practice>,
+
+    /// Game time at which HP reached zero
+    failed_at: f64,
+    fail_clock: Timer,
+
+    /// Screen position of the cursor when the play was paused
+    resume_pos: PhysicalPosition<f64>,
+    resume_clock: Timer,
+    /// Quick retry key is held since then
+    quick_retry_held: Option<Instant>,
 
     current_screen_size: Vector2<f32>,
     current_hit_circle_diameter: f32,
+    /// Position of the real cursor in screen pixels, used by buttons
+    cursor_screen_pos: PhysicalPosition<f64>,
 }
 
 impl<'s> OsuState<'s> {
-    pub fn new(window: Arc<Window>, graphics: Graphics<'s>, sink: Sink) -> Self {
+    pub fn new(window: Arc<Window>, graphics: Graphics<'s>, sink: Sink, effects_sink: Sink) -> Self {
         let egui = EguiState::new(&graphics, &window);
         let skin_manager = Arc::new(RwLock::new(
             SkinManager::from_path("skin", &graphics)
@@ -85,27 +185,60 @@ impl<'s> OsuState<'s> {
         window.set_cursor_visible(false);
 
         Self {
+            results_screen: ResultsScreen::new(event_sender.clone()),
+            practice_panel: PracticePanel::new(event_sender.clone()),
             cursor_renderer: CursorRenderer::new(graphics.clone(), skin_manager.clone()),
+            health_bar_renderer: HealthBarRenderer::new(graphics.clone(), skin_manager.clone()),
+            flashlight_renderer: FlashlightRenderer::new(graphics.clone()),
+            background_renderer: BackgroundRenderer::new(graphics.clone()),
+            break_overlay_renderer: BreakOverlayRenderer::new(graphics.clone(), skin_manager.clone()),
+            countdown_renderer: CountdownRenderer::new(graphics.clone(), skin_manager.clone()),
+            skip_renderer: SkipRenderer::new(graphics.clone(), skin_manager.clone()),
+            pause_renderer: PauseRenderer::new(graphics.clone(), skin_manager.clone()),
             event_receiver,
             preempt: 0.0,
             fadein: 0.0,
             osu_renderer,
             window,
             current_beatmap: None,
+            mods: Mods::default(),
+            difficulty_adjust: DifficultyAdjust::default(),
+            transforms: TransformSettings::default(),
+            ruleset: Ruleset::default(),
             egui,
             sink,
+            effects_sink,
+            audio_started: false,
+            audio_file: None,
             osu_clock: Timer::new(),
             objects_render_queue: Vec::with_capacity(20),
             hit_objects: Vec::new(),
+            breaks: Vec::new(),
+            section_results: Vec::new(),
+            countdown: None,
+            countdown_played: 0,
             skin_manager,
+            config,
             current_state: OsuStates::SongSelection,
             song_select,
             event_sender,
-            input_processor: OsuProcessor::default(),
+            gameplay: None,
+            gameplay_cursor_pos: Vector2::new(0.0, 0.0),
+            score_processor: ScoreProcessor::default(),
+            health_processor: HealthProcessor::default(),
+            last_replay: None,
+            replay_playback: None,
+            practice: None,
+            failed_at: 0.0,
+            fail_clock: Timer::new(),
+            resume_pos: PhysicalPosition::new(0.0, 0.0),
+            resume_clock: Timer::new(),
+            quick_retry_held: None,
             current_hit_window: Default::default(),
             current_screen_size: Vector2::new(1.0, 1.0),
             current_hit_circle_diameter: 1.0,
             objects_judgments_render_queue: Vec::new(),
+            cursor_screen_pos: PhysicalPosition::new(0.0, 0.0),
         }
     }
 
@@ -120,8 +253,6 @@ impl<'s> OsuState<'s> {
 
     pub fn open_beatmap(&mut self, path: impl AsRef<Path>) {
         let _span = tracy_client::span!("osu_state::open_beatmap");
-        self.osu_clock.reset_time();
-        self.osu_clock.unpause();
 
         let map = match Beatmap::from_path(path.as_ref()) {
             Ok(m) => m,
@@ -132,37 +263,310 @@ impl<'s> OsuState<'s> {
             }
         };
 
+        let beatmap_dir = path.as_ref().parent().expect("failed to get beatmap dir");
+
+        self.audio_file = Some(beatmap_dir.join(&map.audio_file));
+        self.load_audio();
+
+        let background_file = beatmap_dir.join(&map.background_file);
+
+        let background = if background_file.is_file() {
+            image::open(&background_file)
+                .inspect_err(|e| tracing::error!("Failed to load background: {e}"))
+                .ok()
+        } else {
+            None
+        };
+
+        self.background_renderer.set_image(background);
+
+        self.current_beatmap = Some(map);
+        self.last_replay = None;
+
+        // Every start of the beatmap gets new random
+        // positions, retries are keeping them
+        if self.transforms.random_seed.is_some() {
+            self.transforms.random_seed = Some(rand::random());
+        }
+
+        self.apply_beatmap_transformations();
+        self.start_play();
+    }
+
+    /// Replaces audio of the sink with the beatmap audio, paused
+    fn load_audio(&self) {
+        let _span = tracy_client::span!("osu_state::load_audio");
+
         self.sink.clear();
+        self.sink.set_speed(self.audio_speed());
 
-        let beatmap_dir = path.as_ref().parent().expect("failed to get beatmap dir");
-        let audio_file = beatmap_dir.join(&map.audio_file);
-        
         // We have to acknowlage the fact that there might be beatmaps
         // without any audio files
-        if audio_file.is_file() {
-            let file = BufReader::new(File::open(audio_file).unwrap());
-            let source = FramelessSource::new(Decoder::new(file).expect("Failed to load audio file source"));
-            let source = UniformSourceIterator::new(source, 2, 44100);
-            self.set_audio(source);
-            tracing::info!("Initialized a new audio file!");
+        let Some(audio_file) = self.audio_file.as_ref().filter(|x| x.is_file()) else {
+            return;
+        };
+
+        let file = BufReader::new(File::open(audio_file).unwrap());
+        let source = FramelessSource::new(Decoder::new(file).expect("Failed to load audio file source"));
+        let source = UniformSourceIterator::new(source, 2, 44100);
+
+        // Nightcore is handled by the sink speed
+        let stretch_rate = if self.mods.changes_pitch() {
+            1.0
+        } else {
+            self.clock_rate()
+        };
+
+        self.set_audio(TimeStretch::new(source, stretch_rate));
+        self.sink.pause();
+        tracing::info!("Initialized a new audio file!");
+    }
+
+    /// Starts the current beatmap from the beginning,
+    /// objects and processors have to be fresh already
+    fn start_play(&mut self) {
+        // Start time depends on the preempt with mods applied
+        let start_time = self.start_time();
+
+        self.osu_clock.reset_to(start_time);
+        self.osu_clock.set_rate(self.clock_rate());
+        self.osu_clock.unpause();
+        self.sync_gameplay_clock();
+
+        self.sink.pause();
+        self.audio_started = false;
+        self.start_audio_if_needed(start_time);
+
+        self.quick_retry_held = None;
+        self.current_state = OsuStates::Playing;
+    }
+
+    /// Restarts the play without parsing the beatmap again
+    fn retry(&mut self) {
+        let _span = tracy_client::span!("osu_state::retry");
+
+        if self.current_beatmap.is_none() {
+            return;
         }
 
-        let (preempt, fadein) = calculate_preempt_fadein(map.approach_rate);
-        let hit_window = HitWindow::from_od(map.overall_difficulty);
+        self.apply_beatmap_transformations();
 
-        self.preempt = preempt;
-        self.fadein = fadein;
-        self.current_hit_window = hit_window;
+        // Audio is gone once it has played till the end
+        if self.sink.empty() {
+            self.load_audio();
+        } else {
+            // Fail animation slows it down
+            self.sink.set_speed(self.audio_speed());
+        }
 
-        // Convert rosu_map to our objects
-        let out_objects = Object::from_rosu(&map);
+        // Practice only plays the section again
+        if self.practice.is_some() {
+            self.restart_practice_loop();
+        } else {
+            self.start_play();
+        }
+    }
 
-        self.hit_objects = out_objects;
+    /// Starts looping the `section`. Audio and objects are rebuilt,
+    /// since practice `rate` changes the clock rate of the play
+    fn start_practice(&mut self, section: PracticeSection, rate: f64) {
+        let _span = tracy_client::span!("osu_state::start_practice");
+
+        if self.current_beatmap.is_none() {
+            return;
+        }
+
+        self.practice = Some(Practice::new(section, rate));
+        self.replay_playback = None;
 
-        self.current_beatmap = Some(map);
         self.apply_beatmap_transformations();
+        self.load_audio();
+        self.restart_practice_loop();
+    }
+
+    fn stop_practice(&mut self) {
+        if self.practice.take().is_none() {
+            return;
+        }
+
+        self.practice_panel.close();
+        self.load_audio();
+        self.retry();
+    }
+
+    /// Plays the practiced section again from the lead-in,
+    /// objects of the section and everything after it are reset
+    fn restart_practice_loop(&mut self) {
+        let _span = tracy_client::span!("osu_state::restart_practice_loop");
+
+        let Some(practice) = &self.practice else {
+            return;
+        };
+
+        let first = practice.section.objects(&self.hit_objects).start;
+        let start = practice.section.loop_start(self.preempt as f64);
+
+        for object in self.hit_objects.iter_mut().skip(first) {
+            object.reset_state();
+        }
+
+        // Every loop is scored on it's own
+        self.score_processor = ScoreProcessor {
+            difficulty_multiplier: self.score_processor.difficulty_multiplier,
+            mod_multiplier: self.score_processor.mod_multiplier,
+            ..Default::default()
+        };
+
+        self.health_processor.reset();
+        self.health_bar_renderer.set_health(self.health_processor.health());
+
+        for (result, period) in self.section_results.iter_mut().zip(&self.breaks) {
+            if period.end_time > start {
+                *result = None;
+            }
+        }
+
+        if let Some(countdown) = &self.countdown {
+            self.countdown_played = countdown.steps_until(start).count();
+        }
+
+        self.osu_clock.reset_to(start);
+        self.osu_clock.set_rate(self.clock_rate());
+        self.osu_clock.unpause();
+
+        let clock = self.osu_clock.snapshot();
+
+        if let Some(gameplay) = &mut self.gameplay {
+            gameplay.restart(first, clock);
+        }
+
+        // Audio is gone once it has played till the end
+        if self.sink.empty() {
+            self.load_audio();
+        }
+
+        self.sink.pause();
+        self.audio_started = false;
+        self.start_audio_if_needed(start);
+
+        self.quick_retry_held = None;
+        self.current_state = OsuStates::Playing;
+    }
+
+    /// Time the practiced section starts over at
+    fn practice_loop_end(&self) -> Option<f64> {
+        self.practice.as_ref()?.section.loop_end(&self.hit_objects)
+    }
+
+    fn finish_practice_loop(&mut self) {
+        if let Some(practice) = &mut self.practice {
+            let objects = practice.section.objects(&self.hit_objects);
+            practice.loops.push(LoopResult::from_objects(&self.hit_objects[objects]));
+        }
+
+        self.restart_practice_loop();
+    }
+
+    fn pause(&mut self) {
+        let _span = tracy_client::span!("osu_state::pause");
+
+        self.osu_clock.update();
+        self.osu_clock.pause();
+        self.sink.pause();
+
+        // Keys released during the pause would never reach the gameplay
+        if let Some(gameplay) = &self.gameplay {
+            gameplay.released(KeyboardState {
+                k1: true,
+                k2: true,
+                m1: true,
+                m2: true,
+            });
+        }
+
+        self.sync_gameplay_clock();
+
+        self.resume_pos = self.cursor_screen_pos;
+        self.current_state = OsuStates::Paused;
+    }
+
+    /// Player has to click at the position the cursor was paused at,
+    /// unless the cursor isn't controlled by the player
+    fn continue_play(&mut self) {
+        if self.is_cursor_assisted() {
+            self.resume();
+            return;
+        }
+
+        self.resume_clock.reset_time();
+        self.resume_clock.unpause();
+        self.current_state = OsuStates::Resuming;
+    }
 
+    fn resume(&mut self) {
+        let _span = tracy_client::span!("osu_state::resume");
+
+        self.osu_clock.unpause();
+        self.sync_gameplay_clock();
+
+        if self.audio_started {
+            self.seek_audio(self.osu_clock.get_time());
+            self.sink.play();
+        }
+
+        self.current_state = OsuStates::Playing;
+    }
+
+    #[inline]
+    fn is_over_resume_marker(&self) -> bool {
+        self.pause_renderer.is_over_marker(self.cursor_screen_pos, self.resume_pos)
+    }
+
+    /// Progress of the held quick retry, retries once it's complete
+    fn update_quick_retry(&mut self) -> f32 {
+        let Some(held) = self.quick_retry_held else {
+            return 0.0;
+        };
+
+        let progress = held.elapsed().as_secs_f64() * 1000.0 / QUICK_RETRY_HOLD_TIME;
+
+        if progress >= 1.0 {
+            self.retry();
+            return 0.0;
+        }
+
+        progress as f32
+    }
+
+    /// Gameplay starts early enough for the lead-in, countdown and
+    /// the first object to fade in. Never after the audio start
+    fn start_time(&self) -> f64 {
+        let Some(map) = &self.current_beatmap else {
+            return 0.0;
+        };
+
+        let first_object = self.hit_objects.first()
+            .map_or(0.0, |x| x.start_time - self.preempt as f64);
+
+        let countdown = self.countdown.as_ref()
+            .map_or(0.0, |x| x.start_time());
+
+        0.0_f64
+            .min(-map.audio_lead_in)
+            .min(first_object)
+            .min(countdown)
+    }
+
+    /// Audio can't be played at negative time,
+    /// so it's started once the clock reaches zero
+    fn start_audio_if_needed(&mut self, time: f64) {
+        if self.audio_started || time < 0.0 {
+            return;
+        }
+
+        self.seek_audio(time);
         self.sink.play();
+        self.audio_started = true;
     }
 
     pub fn set_audio<I>(&self, audio: I) 
@@ -172,15 +576,293 @@ impl<'s> OsuState<'s> {
         self.sink.append(audio);
     }
 
+    /// Base speed of the sink, only pitch changing mods are using it
+    fn audio_speed(&self) -> f32 {
+        if self.mods.changes_pitch() {
+            self.clock_rate() as f32
+        } else {
+            1.0
+        }
+    }
+
+    /// Practice rate is applied on top of the mods
+    fn clock_rate(&self) -> f64 {
+        let practice_rate = self.practice.as_ref().map_or(1.0, |x| x.rate);
+
+        self.mods.clock_rate() * practice_rate
+    }
+
+    /// Mods are applied on the next [`Self::apply_beatmap_transformations`]
+    pub fn set_mods(
+        &mut self,
+        mods: Mods,
+        difficulty_adjust: DifficultyAdjust,
+        transforms: TransformSettings,
+    ) {
+        self.mods = mods;
+        self.difficulty_adjust = difficulty_adjust;
+        self.transforms = transforms;
+    }
+
+    /// Ruleset is used starting from the next play,
+    /// see [`Self::apply_beatmap_transformations`]
+    pub fn set_ruleset(&mut self, ruleset: Ruleset) {
+        self.ruleset = ruleset;
+    }
+
+    /// Applies current mods to the beatmap and rebuilds
+    /// everything that depends on it
     pub fn apply_beatmap_transformations(&mut self) {
         let _span = tracy_client::span!("osu_state::apply_beatmap_transformations");
-        let cs = match &self.current_beatmap {
-            Some(beatmap) => beatmap.circle_size,
-            None => 4.0,
+
+        let Some(original) = &self.current_beatmap else {
+            self.countdown = None;
+            self.osu_renderer.on_cs_change(4.0);
+            self.current_hit_circle_diameter = calc_hitcircle_diameter(4.0);
+            return;
+        };
+
+        let mut map = original.clone();
+        mods::apply_to_beatmap(&mut map, self.mods, &self.difficulty_adjust);
+
+        let (preempt, fadein) = calculate_preempt_fadein(map.approach_rate);
+
+        self.preempt = preempt;
+        self.fadein = fadein;
+        self.current_hit_window = HitWindow::from_od(map.overall_difficulty);
+
+        // Replay is watched with the positions it was played on
+        if let Some(replay) = &self.replay_playback {
+            self.transforms.random_seed = replay.random_seed;
+        }
+
+        // Convert rosu_map to our objects
+        let pipeline = self.transforms.pipeline(self.mods);
+        self.hit_objects = Object::from_rosu_transformed(&map, &pipeline);
+        self.breaks = BreakPeriod::from_rosu(&map);
+        self.section_results = vec![None; self.breaks.len()];
+        self.countdown = Countdown::from_rosu(&map);
+        self.countdown_played = 0;
+
+        // Stable calculates difficulty multiplier from the values without mods
+        self.score_processor = ScoreProcessor::new(original, &self.hit_objects);
+        self.score_processor.mod_multiplier = self.mods.score_multiplier();
+
+        self.health_processor = HealthProcessor::new(&map, &self.hit_objects);
+        self.health_bar_renderer.set_health(self.health_processor.health());
+
+        self.osu_renderer.set_mods(self.mods);
+        self.osu_renderer.set_transforms(self.transforms);
+        self.osu_renderer.on_cs_change(map.circle_size);
+        self.current_hit_circle_diameter = calc_hitcircle_diameter(map.circle_size);
+
+        let autoplay_inputs = if let Some(replay) = &self.replay_playback {
+            replay.inputs.clone()
+        } else if self.mods.contains(Mods::AUTOPLAY) {
+            autoplay::generate(&self.hit_objects, self.clock_rate())
+        } else {
+            Vec::new()
+        };
+
+        // Objects can't be shared with the renderer, so gameplay thread
+        // gets it's own copy. Conversion is deterministic, random
+        // transform included, so both copies are the same
+        let settings = GameplaySettings {
+            hit_window: self.current_hit_window.clone(),
+            circle_diameter: self.current_hit_circle_diameter,
+            rate: self.clock_rate(),
+            ruleset: self.ruleset,
+            assists: if self.replay_playback.is_some() {
+                Mods::empty()
+            } else {
+                self.mods
+            },
+            autoplay_inputs,
+        };
+
+        // Clock can still be running at the time of the previous
+        // play, thread waits until the new play syncs it
+        self.gameplay = Some(GameplayThread::spawn(
+            Object::from_rosu_transformed(&map, &pipeline),
+            settings,
+            ClockSnapshot::paused_at(self.start_time()),
+        ));
+        self.gameplay_cursor_pos = Vector2::new(0.0, 0.0);
+    }
+
+    /// Has to be called after every change of the gameplay clock
+    fn sync_gameplay_clock(&self) {
+        if let Some(gameplay) = &self.gameplay {
+            gameplay.sync_clock(self.osu_clock.snapshot());
+        }
+    }
+
+    /// Applies everything published by the gameplay
+    /// thread and returns new judgements
+    fn receive_gameplay_updates(&mut self) -> Vec<Judgement> {
+        let _span = tracy_client::span!("osu_state::receive_gameplay_updates");
+
+        let mut judgements = Vec::new();
+
+        let Some(gameplay) = &self.gameplay else {
+            return judgements;
+        };
+
+        for update in gameplay.updates() {
+            for (i, state) in update.objects {
+                self.hit_objects[i].set_state(state);
+            }
+
+            judgements.extend(update.judgements);
+            self.gameplay_cursor_pos = update.cursor_pos;
+
+            // Watching a replay or practicing shouldn't replace it
+            if let Some(inputs) = update.replay.filter(|_| self.replay_playback.is_none() && self.practice.is_none()) {
+                self.last_replay = Some(RecordedPlay {
+                    inputs,
+                    random_seed: self.transforms.random_seed,
+                });
+            }
+        }
+
+        judgements
+    }
+
+    /// Seeks audio to the gameplay `time`
+    fn seek_audio(&self, time: f64) {
+        let pos = Duration::from_millis(time.max(0.0).round() as u64);
+
+        if let Err(e) = self.sink.try_seek(pos) {
+            tracing::error!("Failed to seek audio: {e}");
+        }
+    }
+
+    /// Time skip jumps to from `time`, either right before
+    /// the first object fades in or close to the end of a break
+    fn skip_target(&self, time: f64) -> Option<f64> {
+        if let Some(first) = self.hit_objects.first() {
+            let target = first.start_time - self.preempt as f64;
+
+            if target - time >= MIN_INTRO_SKIP {
+                return Some(target);
+            }
+        }
+
+        BreakPeriod::at(&self.breaks, time)
+            .filter(|(_, period)| period.is_skippable(time))
+            .map(|(_, period)| period.skip_target())
+    }
+
+    /// Skip button fades together with the break
+    fn skip_alpha(&self, time: f64) -> f32 {
+        if self.skip_target(time).is_none() {
+            return 0.0;
+        }
+
+        BreakPeriod::at(&self.breaks, time)
+            .map_or(1.0, |(_, period)| period.visibility(time) as f32)
+    }
+
+    /// Moves both clock and audio to the skip target
+    fn skip(&mut self) {
+        let _span = tracy_client::span!("osu_state::skip");
+
+        let time = self.osu_clock.update();
+
+        let Some(target) = self.skip_target(time) else {
+            return;
+        };
+
+        self.osu_clock.set_time(target);
+        self.sync_gameplay_clock();
+
+        // Skipped steps shouldn't be heard all at once
+        if let Some(countdown) = &self.countdown {
+            self.countdown_played = countdown.steps_until(target).count();
+        }
+
+        if self.audio_started {
+            self.seek_audio(target);
+        }
+
+        self.start_audio_if_needed(target);
+    }
+
+    /// Plays sound of the countdown step that has just started
+    fn update_countdown(&mut self, time: f64) {
+        let Some(countdown) = &self.countdown else {
+            return;
+        };
+
+        let due = countdown.steps_until(time).count();
+
+        if due <= self.countdown_played {
+            return;
+        }
+
+        // Only the latest one if several were passed during a single frame
+        let step = countdown.steps_until(time).last();
+        self.countdown_played = due;
+
+        let sound = step.and_then(|step| {
+            self.skin_manager.read()
+                .expect("failed to acquire skin lock")
+                .countdown_sound(step)
+                .cloned()
+        });
+
+        if let Some(sound) = sound {
+            self.play_effect(sound);
+        }
+    }
+
+    fn play_effect(&self, sound: Arc<[u8]>) {
+        match Decoder::new(Cursor::new(sound)) {
+            Ok(source) => self.effects_sink.append(source),
+            Err(e) => tracing::error!("Failed to decode sound: {e}"),
+        }
+    }
+
+    fn render_countdown(&mut self, view: &TextureView, time: f64) {
+        if let Some(countdown) = &self.countdown {
+            self.countdown_renderer.render_on_view(view, time, countdown);
+        }
+
+        let alpha = self.skip_alpha(time);
+        self.skip_renderer.render_on_view(view, alpha);
+    }
+
+    /// Decides pass or fail of the current break once it reaches the middle
+    fn update_breaks(&mut self, time: f64) {
+        let Some((i, period)) = BreakPeriod::at(&self.breaks, time) else {
+            return;
         };
 
-        self.osu_renderer.on_cs_change(cs);
-        self.current_hit_circle_diameter = calc_hitcircle_diameter(cs);
+        if self.section_results[i].is_none()
+            && period.has_section_result()
+            && time >= period.middle() {
+            self.section_results[i] = Some(SectionResult::from_health(self.health_processor.health()));
+        }
+    }
+
+    /// Background is fully visible in the middle of breaks
+    fn background_dim(&self, time: f64) -> f32 {
+        let dim = self.config.read()
+            .expect("failed to acquire read lock")
+            .background_dim;
+
+        let visibility = BreakPeriod::at(&self.breaks, time)
+            .map_or(0.0, |(_, period)| period.visibility(time));
+
+        dim * (1.0 - visibility as f32)
+    }
+
+    fn render_break_overlay(&mut self, view: &TextureView, time: f64) {
+        let Some((i, period)) = BreakPeriod::at(&self.breaks, time) else {
+            return;
+        };
+
+        self.break_overlay_renderer.render_on_view(view, time, period, self.section_results[i]);
     }
 
     pub fn resize(&mut self, new_size: &PhysicalSize<u32>) {
@@ -189,6 +871,13 @@ impl<'s> OsuState<'s> {
         self.current_screen_size.y = new_size.height as f32;
 
         self.cursor_renderer.on_resize(new_size);
+        self.health_bar_renderer.on_resize(new_size);
+        self.flashlight_renderer.on_resize(new_size);
+        self.background_renderer.on_resize(new_size);
+        self.break_overlay_renderer.on_resize(new_size);
+        self.countdown_renderer.on_resize(new_size);
+        self.skip_renderer.on_resize(new_size);
+        self.pause_renderer.on_resize(new_size);
         self.osu_renderer.on_resize(new_size);
         self.song_select.on_resize(new_size);
     }
@@ -199,31 +888,68 @@ impl<'s> OsuState<'s> {
         is_cntrl_pressed: bool
     ) {
         let _span = tracy_client::span!("osu_state::on_pressed_down");
+
+        let action = self.config.read()
+            .expect("failed to acquire read lock")
+            .keybindings
+            .action(ActionContext::Gameplay, BindingInput::Key(key_code), is_cntrl_pressed);
+
+        let is_in_play = matches!(
+            self.current_state,
+            OsuStates::Playing | OsuStates::Paused | OsuStates::Resuming | OsuStates::Failed
+        );
+
+        // Key repeat keeps sending presses while it's held
+        if is_in_play && action == Some(Action::QuickRetry) && self.quick_retry_held.is_none() {
+            self.quick_retry_held = Some(Instant::now());
+        }
+
         match self.current_state {
             OsuStates::Playing => {
-                if key_code == KeyCode::Escape {
-                    self.event_sender.send(OsuStateEvent::ToSongSelection)
-                        .expect("Failed to send ToSongSelection event to the OsuState");
+                if action == Some(Action::QuitPlay) {
+                    self.pause();
+                    return;
+                }
+
+                if action == Some(Action::Skip) {
+                    self.skip();
                 }
-                
-                let ts = self.osu_clock.since_start();
 
-                if key_code == KeyCode::KeyZ {
-                    let state = KeyboardState {
-                        k1: true,
-                        k2: false,
-                    };
+                if action == Some(Action::TogglePractice) {
+                    self.practice_panel.toggle(self.osu_clock.get_time(), self.practice.as_ref());
+                    return;
+                }
 
-                    self.input_processor.store_keyboard_pressed(ts, state);
+                // Autoplay presses keys by itself
+                if self.is_autoplay() {
+                    return;
                 }
 
-                if key_code == KeyCode::KeyX {
-                    let state = KeyboardState {
-                        k1: false,
-                        k2: true,
-                    };
+                let state = Self::gameplay_keys(action);
+
+                if let (Some(state), Some(gameplay)) = (state, &self.gameplay) {
+                    gameplay.pressed(state);
+                }
+            },
+            OsuStates::Paused => {
+                if action == Some(Action::QuitPlay) {
+                    self.continue_play();
+                }
+            },
+            OsuStates::Resuming => {
+                if action == Some(Action::QuitPlay) {
+                    self.current_state = OsuStates::Paused;
+                    return;
+                }
 
-                    self.input_processor.store_keyboard_pressed(ts, state);
+                if Self::gameplay_keys(action).is_some() && self.is_over_resume_marker() {
+                    self.resume();
+                }
+            },
+            OsuStates::Failed | OsuStates::Results => {
+                if action == Some(Action::QuitPlay) {
+                    self.event_sender.send(OsuStateEvent::ToSongSelection)
+                        .expect("Failed to send ToSongSelection event to the OsuState");
                 }
             },
             OsuStates::SongSelection => {
@@ -234,40 +960,168 @@ impl<'s> OsuState<'s> {
 
     pub fn on_pressed_release(&mut self, key_code: KeyCode) {
         let _span = tracy_client::span!("osu_state::on_pressed_release");
-        match self.current_state {
-            OsuStates::Playing => {
 
-                let ts = self.osu_clock.since_start();
-                if key_code == KeyCode::KeyZ {
-                    let state = KeyboardState {
-                        k1: true,
-                        k2: false,
-                    };
+        let is_quick_retry = self.config.read()
+            .expect("failed to acquire read lock")
+            .keybindings
+            .is_bound(Action::QuickRetry, BindingInput::Key(key_code));
 
-                    self.input_processor.store_keyboard_released(ts, state);
+        if is_quick_retry {
+            self.quick_retry_held = None;
+        }
+
+        match self.current_state {
+            OsuStates::Playing => {
+                if self.is_autoplay() {
+                    return;
                 }
 
-                if key_code == KeyCode::KeyX {
-                    let state = KeyboardState {
-                        k1: false,
-                        k2: true,
-                    };
+                let state = self.bound_keys(BindingInput::Key(key_code));
 
-                    self.input_processor.store_keyboard_released(ts, state);
+                if let (Some(state), Some(gameplay)) = (state, &self.gameplay) {
+                    gameplay.released(state);
                 }
             }
             _ => {}
         };
     }
 
+    fn gameplay_keys(action: Option<Action>) -> Option<KeyboardState> {
+        match action {
+            Some(Action::K1) => Some(KeyboardState {
+                k1: true,
+                ..KeyboardState::empty()
+            }),
+            Some(Action::K2) => Some(KeyboardState {
+                k2: true,
+                ..KeyboardState::empty()
+            }),
+            _ => None,
+        }
+    }
+
+    /// Gameplay keys bound to the input. Control isn't taken
+    /// into account, since it can be released before the key itself
+    fn bound_keys(&self, input: BindingInput) -> Option<KeyboardState> {
+        let config = self.config.read().expect("failed to acquire read lock");
+
+        let state = KeyboardState {
+            k1: config.keybindings.is_bound(Action::K1, input),
+            k2: config.keybindings.is_bound(Action::K2, input),
+            ..KeyboardState::empty()
+        };
+
+        state.is_keys_hit().then_some(state)
+    }
+
+    /// Mouse buttons bound to K1 or K2 are pressing them,
+    /// otherwise left and right buttons are M1 and M2
+    fn mouse_button_state(&self, button: MouseButton) -> Option<KeyboardState> {
+        if let Some(state) = self.bound_keys(BindingInput::Mouse(button)) {
+            return Some(state);
+        }
+
+        match button {
+            MouseButton::Left => Some(KeyboardState {
+                m1: true,
+                ..KeyboardState::empty()
+            }),
+            MouseButton::Right => Some(KeyboardState {
+                m2: true,
+                ..KeyboardState::empty()
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn on_mouse_pressed(&mut self, button: MouseButton) {
+        let _span = tracy_client::span!("osu_state::on_mouse_pressed");
+
+        match self.current_state {
+            OsuStates::Paused if button == MouseButton::Left => {
+                match self.pause_renderer.button_at(self.cursor_screen_pos) {
+                    Some(PauseButton::Continue) => self.continue_play(),
+                    Some(PauseButton::Retry) => self.retry(),
+                    Some(PauseButton::Back) => {
+                        self.event_sender.send(OsuStateEvent::ToSongSelection)
+                            .expect("Failed to send ToSongSelection event to the OsuState");
+                    },
+                    None => {},
+                }
+
+                return;
+            },
+            OsuStates::Resuming => {
+                if self.mouse_button_state(button).is_some() && self.is_over_resume_marker() {
+                    self.resume();
+                }
+
+                return;
+            },
+            OsuStates::Playing => {},
+            _ => return,
+        }
+
+        // Neither are clicks on the practice panel
+        if self.practice_panel.is_open() && self.egui.state.egui_ctx().is_pointer_over_area() {
+            return;
+        }
+
+        // Click on the skip button isn't passed to the gameplay
+        if button == MouseButton::Left
+            && self.skip_target(self.osu_clock.get_time()).is_some()
+            && self.skip_renderer.contains(self.cursor_screen_pos) {
+            self.skip();
+            return;
+        }
+
+        if self.is_autoplay() {
+            return;
+        }
+
+        let Some(state) = self.mouse_button_state(button) else {
+            return;
+        };
+
+        if let Some(gameplay) = &self.gameplay {
+            gameplay.pressed(state);
+        }
+    }
+
+    pub fn on_mouse_released(&mut self, button: MouseButton) {
+        let _span = tracy_client::span!("osu_state::on_mouse_released");
+
+        if !matches!(self.current_state, OsuStates::Playing) || self.is_autoplay() {
+            return;
+        }
+
+        let Some(state) = self.mouse_button_state(button) else {
+            return;
+        };
+
+        if let Some(gameplay) = &self.gameplay {
+            gameplay.released(state);
+        }
+    }
+
     pub fn on_cursor_moved(&mut self, position: PhysicalPosition<f64>) {
         let _span = tracy_client::span!("osu_state::on_cursor_moved");
-        self.cursor_renderer.on_cursor_moved(position);
+
+        self.cursor_screen_pos = position;
+
+        let is_playing = matches!(self.current_state, OsuStates::Playing | OsuStates::Failed);
+
+        // Cursor is moved by autoplay or autopilot instead
+        if !(is_playing && self.is_cursor_assisted()) {
+            self.cursor_renderer.on_cursor_moved(position);
+        }
+
+        if is_playing && self.is_autoplay() {
+            return;
+        }
 
         match self.current_state {
             OsuStates::Playing => {
-                let ts = self.osu_clock.since_start();
-
                 let mut recv_pos = Vector2::new(position.x as f32, position.y as f32);
                 let (scale, offsets) = calc_playfield(self.current_screen_size.x, self.current_screen_size.y);
 
@@ -276,59 +1130,34 @@ impl<'s> OsuState<'s> {
                 
                 let pos = Vector2::new(recv_pos.x as f64, recv_pos.y as f64);
 
-                self.input_processor.store_cursor_moved(ts, pos);
+                if let Some(gameplay) = &self.gameplay {
+                    gameplay.cursor_moved(pos);
+                }
             },
             _ => {},
         }
     }
 
-    pub fn update_egui(&mut self, input: RawInput) {
-        let _span = tracy_client::span!("osu_state::update_egui");
-
-        self.egui.state.egui_ctx().begin_pass(input);
-
-        egui::Window::new("Debug Gameplay Window")
-            .resizable(false)
-            .show(&self.egui.state.egui_ctx(), |ui| {
-
-            if let Some(beatmap) = &self.current_beatmap {
-                ui.add(egui::Label::new(format!("{}", self.osu_clock.get_time())));
-
-                if ui.add(
-                    Slider::new(
-                        &mut self.osu_clock.last_time,
-                        1.0..=(beatmap.hit_objects.last().unwrap().start_time),
-                    )
-                    .step_by(1.0),
-                ).changed() {
-                    self.osu_clock.pause();
-                    self.sink.try_seek(Duration::from_millis(self.osu_clock.get_time().round() as u64)).unwrap();
-                    self.osu_clock.unpause();
-                };
-
-                if !self.osu_clock.is_paused() {
-                    if ui.add(egui::Button::new("pause")).clicked() {
-                        self.osu_clock.pause();
-                        self.sink.pause();
-                    }
-                } else {
-                    if ui.add(egui::Button::new("unpause")).clicked() {
-                        self.sink.try_seek(Duration::from_millis(self.osu_clock.get_time().round() as u64)).unwrap();
-                        self.osu_clock.unpause();
-                        self.sink.play();
-                    }
-                }
-            }
-        });
+    /// Inputs aren't coming from the player
+    #[inline]
+    fn is_autoplay(&self) -> bool {
+        self.mods.contains(Mods::AUTOPLAY) || self.replay_playback.is_some()
+    }
 
-        let output = self.egui.state.egui_ctx().end_pass();
+    /// Cursor isn't controlled by the player
+    #[inline]
+    fn is_cursor_assisted(&self) -> bool {
+        self.is_autoplay() || self.mods.contains(Mods::AUTOPILOT)
+    }
 
-        self.egui.state.handle_platform_output(
-            &self.window,
-            output.platform_output.to_owned(),
-        );
+    /// Moves rendered cursor to the position in osu!pixels
+    fn move_cursor_to(&mut self, pos: Vector2<f64>) {
+        let (scale, offsets) = calc_playfield(self.current_screen_size.x, self.current_screen_size.y);
 
-        self.egui.output = Some(output);
+        self.cursor_renderer.on_cursor_moved(PhysicalPosition::new(
+            pos.x * scale as f64 + offsets.x as f64,
+            pos.y * scale as f64 + offsets.y as f64,
+        ));
     }
 
     pub fn process_inputs(&mut self, _process_time: f64) {
@@ -341,7 +1170,9 @@ impl<'s> OsuState<'s> {
         let _span = tracy_client::span!("osu_state::prepare_objects_for_renderer");
 
         for (i, obj) in self.hit_objects.iter_mut().enumerate().rev() {
-            self.objects_judgments_render_queue.push(i);
+            if obj.is_judgements_visible(time, self.preempt) {
+                self.objects_judgments_render_queue.push(i);
+            }
 
             if !obj.is_visible(time, self.preempt, &self.current_hit_window) {
                 continue;
@@ -391,6 +1222,12 @@ impl<'s> OsuState<'s> {
                     OsuStateEvent::SetCursorSize(new_size) => {
                         self.cursor_renderer.set_size(new_size);
                     },
+                    OsuStateEvent::SetMods(mods, difficulty_adjust, transforms) => {
+                        self.set_mods(mods, difficulty_adjust, transforms);
+                    },
+                    OsuStateEvent::SetRuleset(ruleset) => {
+                        self.set_ruleset(ruleset);
+                    },
                     OsuStateEvent::ChangeSkin(path) => {
                         let _span = tracy_client::span!("osu_state::update::event::change_skin");
                         self.open_skin(path)
@@ -403,8 +1240,34 @@ impl<'s> OsuState<'s> {
                     OsuStateEvent::ToSongSelection => {
                         let _span = tracy_client::span!("osu_state::update::event::to_song_selection");
                         self.osu_clock.reset_time();
+                        self.osu_clock.set_rate(1.0);
+                        self.sink.set_speed(1.0);
+                        self.gameplay = None;
+                        self.quick_retry_held = None;
+                        self.replay_playback = None;
+                        self.practice = None;
+                        self.practice_panel.close();
                         self.current_state = OsuStates::SongSelection;
                     },
+                    OsuStateEvent::Retry => {
+                        self.replay_playback = None;
+                        self.retry();
+                    },
+                    OsuStateEvent::WatchReplay => {
+                        // Replay is always of the whole beatmap
+                        if self.practice.take().is_some() {
+                            self.load_audio();
+                        }
+
+                        self.replay_playback = self.last_replay.clone();
+                        self.retry();
+                    },
+                    OsuStateEvent::StartPractice(section, rate) => {
+                        self.start_practice(section, rate);
+                    },
+                    OsuStateEvent::StopPractice => {
+                        self.stop_practice();
+                    },
                     OsuStateEvent::PlaySound(start_at, audio_source) => {
                         let span = tracy_client::span!("osu_state::update::event::play_sound");
                         self.sink.clear();
@@ -426,7 +1289,11 @@ impl<'s> OsuState<'s> {
         //let input = self.egui.state.take_egui_input(&self.window);
 
         match self.current_state {
-            OsuStates::Playing => {},
+            OsuStates::Playing
+            | OsuStates::Paused
+            | OsuStates::Resuming
+            | OsuStates::Failed
+            | OsuStates::Results => {},
             OsuStates::SongSelection => {
                 self.song_select.update();
             },
@@ -434,6 +1301,128 @@ impl<'s> OsuState<'s> {
 
     }
 
+    /// Last object is judged and had some time to fade out
+    /// Last object isn't always the one that ends last,
+    /// e.g. circle placed during a long spinner
+    fn is_play_finished(&self, time: f64) -> bool {
+        if self.hit_objects.is_empty()
+            || !self.hit_objects.iter().all(|x| x.is_finished()) {
+            return false;
+        }
+
+        let end_time = self.hit_objects.iter()
+            .map(|x| x.end_time())
+            .fold(f64::MIN, f64::max);
+
+        time >= end_time + RESULTS_DELAY
+    }
+
+    fn show_results(&mut self) {
+        let _span = tracy_client::span!("osu_state::show_results");
+
+        let results = PlayResults::new(
+            &self.score_processor,
+            &self.hit_objects,
+            &self.current_hit_window,
+            self.clock_rate(),
+        );
+
+        let title = self.current_beatmap.as_ref()
+            .map(|x| format!("{} - {} [{}]", x.artist, x.title, x.version))
+            .unwrap_or_default();
+
+        self.results_screen.set_results(results, title, self.last_replay.is_some());
+
+        // Nothing can be judged anymore
+        self.gameplay = None;
+        self.osu_clock.pause();
+        self.current_state = OsuStates::Results;
+    }
+
+    fn start_fail_animation(&mut self) {
+        let _span = tracy_client::span!("osu_state::start_fail_animation");
+
+        self.osu_clock.pause();
+        self.failed_at = self.osu_clock.get_time();
+
+        // Nothing can be judged after failing
+        self.gameplay = None;
+
+        self.fail_clock.reset_time();
+        self.fail_clock.unpause();
+
+        self.current_state = OsuStates::Failed;
+    }
+
+    /// Slows down both gameplay and audio until they fully stop
+    fn update_fail_animation(&mut self) {
+        let elapsed = self.fail_clock.update().min(FAIL_ANIMATION_TIME);
+        let progress = elapsed / FAIL_ANIMATION_TIME;
+
+        // Speed goes linearly from 1 to 0, so passed
+        // game time is the integral of it
+        let passed = elapsed - elapsed * elapsed / (2.0 * FAIL_ANIMATION_TIME);
+        self.osu_clock.set_time(self.failed_at + passed * self.osu_clock.rate());
+
+        if progress >= 1.0 {
+            self.sink.pause();
+        } else {
+            // Changing speed of the sink drops the pitch as well
+            let speed = (1.0 - progress as f32).max(FAIL_MIN_AUDIO_SPEED);
+            self.sink.set_speed(self.audio_speed() * speed);
+        }
+    }
+
+    /// Flashlight hides objects, but not the HUD, so it
+    /// should be rendered before it and before queues are cleared
+    fn render_flashlight(&mut self, view: &TextureView) {
+        if !self.mods.contains(Mods::FLASHLIGHT) {
+            return;
+        }
+
+        let time = self.osu_clock.get_time();
+
+        let is_holding_slider = self.objects_render_queue.iter()
+            .any(|i| match &self.hit_objects[*i].kind {
+                ObjectKind::Slider(slider) => slider.is_tracking(time),
+                _ => false,
+            });
+
+        self.flashlight_renderer.update(
+            self.gameplay_cursor_pos,
+            self.score_processor.combo,
+            is_holding_slider,
+        );
+
+        self.flashlight_renderer.render_on_view(view);
+    }
+
+    /// Gameplay frame without any processing, while
+    /// it's paused or slowing down after the fail
+    fn render_stopped_gameplay(&mut self, view: &TextureView) -> Result<(), wgpu::SurfaceError> {
+        let time = self.osu_clock.get_time();
+
+        let dim = self.background_dim(time);
+        self.background_renderer.render_on_view(view, dim);
+
+        self.prepare_objects_for_renderer(time);
+
+        self.osu_renderer.render_objects(
+            view,
+            &self.objects_render_queue,
+            &self.hit_objects
+        )?;
+
+        self.render_flashlight(view);
+
+        self.objects_render_queue.clear();
+        self.objects_judgments_render_queue.clear();
+
+        self.health_bar_renderer.render_on_view(view);
+
+        Ok(())
+    }
+
     pub fn render_egui(&mut self, view: &TextureView) -> Result<(), wgpu::SurfaceError> {
         let _span = tracy_client::span!("osu_state::render_egui");
 
@@ -461,32 +1450,92 @@ impl<'s> OsuState<'s> {
 
         match self.current_state {
             OsuStates::Playing => {
+                let dim = self.background_dim(self.osu_clock.get_time());
+                self.background_renderer.render_on_view(&view, dim);
 
                 self.prepare_objects_for_renderer(self.osu_clock.get_time());
 
-                // TODO THIS SHOULN'T BE HERE, fix when dicided what to
-                // do with egui_input thing
-                //self.update_egui(egui_input);
-
                 self.osu_renderer.render_objects(
                     &view,
                     &self.objects_render_queue, 
                     &self.hit_objects
                 )?;
 
+                self.render_flashlight(&view);
+
                 // Clearing objects queue only after they successfully rendered
                 self.objects_render_queue.clear();
                 self.objects_judgments_render_queue.clear();
-                //self.render_egui(&view)?;
 
-                //self.render_playing(&view);
+                let time = self.osu_clock.update();
+
+                self.start_audio_if_needed(time);
+                self.update_countdown(time);
 
-                self.osu_clock.update();
-                self.input_processor.process_all(
-                    &mut self.hit_objects,
-                    &self.current_hit_window,
-                    self.current_hit_circle_diameter
+                let judgements = self.receive_gameplay_updates();
+
+                if self.is_cursor_assisted() {
+                    self.move_cursor_to(self.gameplay_cursor_pos);
+                }
+
+                self.score_processor.apply_all(&judgements);
+
+                self.health_processor.update(self.osu_clock.get_time());
+                self.health_processor.apply_all(&judgements);
+                self.health_bar_renderer.set_health(self.health_processor.health());
+                self.health_bar_renderer.render_on_view(&view);
+
+                self.update_breaks(self.osu_clock.get_time());
+                self.render_break_overlay(&view, self.osu_clock.get_time());
+                self.render_countdown(&view, self.osu_clock.get_time());
+
+                if self.practice_panel.is_open() {
+                    let length = self.hit_objects.last().map_or(0.0, |x| x.end_time());
+
+                    let egui_output = self.practice_panel.render(
+                        egui_input,
+                        self.egui.state.egui_ctx(),
+                        time,
+                        length,
+                        self.practice.as_ref(),
+                    );
+                    self.render_egui(&view)?;
+                    self.egui.output = Some(egui_output)
+                }
+
+                // Practice never fails, the section is played again instead
+                if self.practice_loop_end().is_some_and(|end| time >= end) {
+                    self.finish_practice_loop();
+                } else if self.health_processor.is_failed() && self.practice.is_none() {
+                    self.start_fail_animation();
+                } else if self.is_play_finished(time) {
+                    self.show_results();
+                }
+            },
+            OsuStates::Paused => {
+                self.render_stopped_gameplay(&view)?;
+                self.pause_renderer.render_on_view(&view);
+            },
+            OsuStates::Resuming => {
+                self.render_stopped_gameplay(&view)?;
+
+                let elapsed = self.resume_clock.update();
+                self.pause_renderer.render_marker_on_view(&view, self.resume_pos, elapsed);
+            },
+            OsuStates::Failed => {
+                self.update_fail_animation();
+                self.render_stopped_gameplay(&view)?;
+            },
+            OsuStates::Results => {
+                let dim = self.background_dim(self.osu_clock.get_time());
+                self.background_renderer.render_on_view(&view, dim);
+
+                let egui_output = self.results_screen.render(
+                    egui_input,
+                    self.egui.state.egui_ctx(),
                 );
+                self.render_egui(&view)?;
+                self.egui.output = Some(egui_output)
             },
             OsuStates::SongSelection => {
                 let egui_output = self.song_select.render(
@@ -499,6 +1548,11 @@ impl<'s> OsuState<'s> {
             },
         }
 
+        if !matches!(self.current_state, OsuStates::SongSelection | OsuStates::Results) {
+            let progress = self.update_quick_retry();
+            self.pause_renderer.render_overlay_on_view(&view, progress);
+        }
+
         self.cursor_renderer.render_on_view(
             &view
         );
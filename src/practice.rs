@@ -0,0 +1,179 @@
+use std::ops::Range;
+
+use crate::hit_objects::{Hit, Object, ObjectKind};
+
+/// Time given to get ready before the section starts, ms
+pub const PRACTICE_LEAD_IN: f64 = 2000.0;
+/// Section keeps playing that long after it's last object
+/// ends, so the object has time to be judged and fade out
+pub const PRACTICE_LOOP_TAIL: f64 = 500.0;
+
+pub const MIN_PRACTICE_RATE: f64 = 0.5;
+pub const MAX_PRACTICE_RATE: f64 = 2.0;
+
+/// Part of the beatmap that is practiced. Played from `start`
+/// until the end of the beatmap, or looped if there's an `end`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PracticeSection {
+    pub start: f64,
+    pub end: Option<f64>,
+}
+
+impl PracticeSection {
+    pub fn new(start: f64, end: Option<f64>) -> Self {
+        Self {
+            start,
+            end: end.filter(|x| *x > start),
+        }
+    }
+
+    /// Indices of the objects starting inside of the section
+    pub fn objects(&self, objects: &[Object]) -> Range<usize> {
+        let first = objects.partition_point(|x| x.start_time < self.start);
+
+        let last = match self.end {
+            Some(end) => objects.partition_point(|x| x.start_time < end),
+            None => objects.len(),
+        };
+
+        first..last.max(first)
+    }
+
+    /// Every loop starts early enough to get ready
+    /// and for the first object to fade in
+    #[inline]
+    pub fn loop_start(&self, preempt: f64) -> f64 {
+        self.start - PRACTICE_LEAD_IN.max(preempt)
+    }
+
+    /// Time the section starts over at, `None` if it isn't looped.
+    /// Objects starting inside of the section are always played till the end
+    pub fn loop_end(&self, objects: &[Object]) -> Option<f64> {
+        let end = self.end?;
+
+        let last_end = objects[self.objects(objects)].iter()
+            .map(|x| x.end_time())
+            .fold(end, f64::max);
+
+        Some(last_end + PRACTICE_LOOP_TAIL)
+    }
+}
+
+/// Outcome of a single loop, only objects of the section are counted
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct LoopResult {
+    pub x300: u32,
+    pub x100: u32,
+    pub x50: u32,
+    pub xmiss: u32,
+    /// Missed slider ticks and repeats
+    pub slider_breaks: u32,
+}
+
+impl LoopResult {
+    /// Unfinished objects are skipped
+    pub fn from_objects(objects: &[Object]) -> Self {
+        let mut result = Self::default();
+
+        for object in objects {
+            match object.final_hit() {
+                Some(Hit::X300) => result.x300 += 1,
+                Some(Hit::X100) => result.x100 += 1,
+                Some(Hit::X50) => result.x50 += 1,
+                Some(Hit::MISS) => result.xmiss += 1,
+                None => {},
+            }
+
+            if let ObjectKind::Slider(slider) = &object.kind {
+                let breaks = slider.hit_result.iter()
+                    .flat_map(|x| x.parts.iter())
+                    .filter(|x| x.is_slider_break())
+                    .count();
+
+                result.slider_breaks += breaks as u32;
+            }
+        }
+
+        result
+    }
+
+    /// Same as [`crate::processor::score_processor::ScoreProcessor::accuracy`]
+    pub fn accuracy(&self) -> f64 {
+        let total = self.x300 + self.x100 + self.x50 + self.xmiss;
+
+        if total == 0 {
+            return 1.0;
+        }
+
+        let points = self.x300 * 300 + self.x100 * 100 + self.x50 * 50;
+
+        points as f64 / (total * 300) as f64
+    }
+}
+
+/// Practice mode of the current play
+#[derive(Debug, Clone)]
+pub struct Practice {
+    pub section: PracticeSection,
+    /// Multiplies clock rate of the mods
+    pub rate: f64,
+    /// Results of every finished loop, in order
+    pub loops: Vec<LoopResult>,
+}
+
+impl Practice {
+    pub fn new(section: PracticeSection, rate: f64) -> Self {
+        Self {
+            section,
+            rate: rate.clamp(MIN_PRACTICE_RATE, MAX_PRACTICE_RATE),
+            loops: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::case;
+
+    use super::{LoopResult, Practice, PracticeSection};
+
+    #[case(10000.0, 450.0, 8000.0; "lead-in")]
+    #[case(10000.0, 2400.0, 7600.0; "long preempt")]
+    fn test_loop_start(start: f64, preempt: f64, expected: f64) {
+        let section = PracticeSection::new(start, None);
+
+        assert_eq!(section.loop_start(preempt), expected);
+    }
+
+    #[test]
+    fn test_end_before_start_isnt_looped() {
+        assert_eq!(PracticeSection::new(5000.0, Some(4000.0)).end, None);
+        assert_eq!(PracticeSection::new(5000.0, Some(5000.0)).end, None);
+        assert_eq!(PracticeSection::new(5000.0, Some(6000.0)).end, Some(6000.0));
+
+        // Nothing to loop without an end
+        assert_eq!(PracticeSection::new(5000.0, None).loop_end(&[]), None);
+    }
+
+    #[test]
+    fn test_loop_accuracy() {
+        let result = LoopResult {
+            x300: 2,
+            x100: 1,
+            xmiss: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(result.accuracy(), 700.0 / 1200.0);
+        assert_eq!(LoopResult::default().accuracy(), 1.0);
+    }
+
+    #[test]
+    fn test_rate_is_clamped() {
+        let section = PracticeSection::new(0.0, None);
+
+        assert_eq!(Practice::new(section, 0.1).rate, 0.5);
+        assert_eq!(Practice::new(section, 1.25).rate, 1.25);
+        assert_eq!(Practice::new(section, 3.0).rate, 2.0);
+    }
+}
@@ -0,0 +1,100 @@
+use cgmath::Vector2;
+use rosu_map::util::Pos;
+
+use crate::{
+    hit_objects::{hit_window::HitWindow, ruleset::Ruleset, Object, ObjectKind},
+    osu_input::KeyboardState,
+};
+
+fn is_inside(pos: Vector2<f64>, center: &Pos, circle_diameter: f32) -> bool {
+    let dx = pos.x - center.x as f64;
+    let dy = pos.y - center.y as f64;
+
+    (dx * dx + dy * dy).sqrt() <= (circle_diameter / 2.0) as f64
+}
+
+/// Keys relax presses for the cursor at `pos`.
+///
+/// Circles and slider heads are hit as soon as their time
+/// comes and the cursor is inside them, keys are kept down
+/// during sliders and spinners. Hits are alternated between
+/// keys, so every one of them is a fresh press
+pub fn relax_keys(
+    ts: f64,
+    pos: Vector2<f64>,
+    last_keys: KeyboardState,
+    objects: &[Object],
+    hit_window: &HitWindow,
+    circle_diameter: f32,
+    ruleset: Ruleset,
+) -> KeyboardState {
+    let mut hit = false;
+    let mut hold = false;
+
+    for object in objects {
+        if object.start_time > ts {
+            break;
+        }
+
+        match &object.kind {
+            ObjectKind::Circle(circle) => {
+                if circle.is_hittable(ts, hit_window, ruleset)
+                    && is_inside(pos, &circle.pos, circle_diameter) {
+                    hit = true;
+                }
+            },
+            ObjectKind::Slider(slider) => {
+                if slider.is_head_hittable(ts, hit_window, ruleset)
+                    && is_inside(pos, &slider.pos, circle_diameter) {
+                    hit = true;
+                }
+
+                if ts <= slider.end_time() {
+                    hold = true;
+                }
+            },
+            ObjectKind::Spinner(spinner) => {
+                if ts <= spinner.end_time() {
+                    hold = true;
+                }
+            },
+        }
+    }
+
+    if hit {
+        return if !last_keys.k1 {
+            KeyboardState {
+                k1: true,
+                k2: hold && last_keys.k2,
+                ..KeyboardState::empty()
+            }
+        } else if !last_keys.k2 {
+            KeyboardState {
+                k1: hold,
+                k2: true,
+                ..KeyboardState::empty()
+            }
+        } else {
+            // Both keys are down, one of them has
+            // to be released to be pressed again
+            KeyboardState {
+                k1: false,
+                k2: hold,
+                ..KeyboardState::empty()
+            }
+        };
+    }
+
+    if hold {
+        if last_keys.is_keys_hit() {
+            return last_keys;
+        }
+
+        return KeyboardState {
+            k1: true,
+            ..KeyboardState::empty()
+        };
+    }
+
+    KeyboardState::empty()
+}
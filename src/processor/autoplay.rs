@@ -0,0 +1,245 @@
+use cgmath::Vector2;
+
+use crate::{
+    hit_objects::{spinner::{SPINNER_CENTER, SPINNER_MAX_RADIANS_PER_MS}, Object, ObjectKind},
+    osu_input::{KeyboardState, OsuInput},
+};
+
+/// Time between generated cursor frames, ~60 fps
+const FRAME_TIME: f64 = 1000.0 / 60.0;
+/// Keys are released this long after the object end
+const KEY_UP_DELAY: f64 = 50.0;
+/// Objects closer than this to the previous one are hit with the other key
+const ALTERNATE_THRESHOLD: f64 = 200.0;
+/// Cursor doesn't start moving to the next object earlier than this
+const MAX_MOVE_TIME: f64 = 500.0;
+/// Distance from the spinner center while spinning, in osu!pixels
+const SPIN_RADIUS: f64 = 50.0;
+
+/// Single key press of the generated play
+struct KeyPress {
+    press: f64,
+    release: f64,
+    k1: bool,
+}
+
+/// Generates a perfect play for the provided objects.
+///
+/// Cursor is moved to every object on time, follows slider balls
+/// and spins spinners as fast as allowed at the provided `rate`,
+/// streams are alternated between both keys. Inputs are sorted
+/// and can be passed to [`super::OsuProcessor`] the same way as replay frames
+pub fn generate(objects: &[Object], rate: f64) -> Vec<OsuInput> {
+    let _span = tracy_client::span!("autoplay::generate");
+
+    let mut frames: Vec<(f64, Vector2<f64>)> = Vec::new();
+    let mut presses: Vec<KeyPress> = Vec::new();
+
+    let mut last_time = objects.first().map_or(0.0, |x| x.start_time) - MAX_MOVE_TIME;
+    let mut last_pos = Vector2::new(SPINNER_CENTER.x as f64, SPINNER_CENTER.y as f64);
+    let mut last_k1 = false;
+
+    for (i, object) in objects.iter().enumerate() {
+        let start_pos = start_position(object);
+
+        move_to(&mut frames, last_time, last_pos, object.start_time, start_pos);
+
+        match &object.kind {
+            ObjectKind::Circle(_) => {
+                frames.push((object.start_time, start_pos));
+            },
+            ObjectKind::Slider(slider) => {
+                let mut times = frame_times(slider.start_time, slider.end_time());
+
+                // Only one checkpoint is passed per input,
+                // so every checkpoint gets it's own frame
+                for checkpoint in &slider.checkpoints {
+                    times.push((checkpoint.time + 1.0).min(slider.end_time()));
+                }
+
+                times.sort_by(|a, b| a.partial_cmp(b).expect("failed to compare"));
+                times.dedup();
+
+                for time in times {
+                    let progress = slider.get_slider_progress(time);
+                    let pos = slider.curve.position_at(progress);
+
+                    frames.push((
+                        time,
+                        Vector2::new(
+                            slider.pos.x as f64 + pos.x as f64,
+                            slider.pos.y as f64 + pos.y as f64,
+                        ),
+                    ));
+                }
+            },
+            ObjectKind::Spinner(spinner) => {
+                let speed = SPINNER_MAX_RADIANS_PER_MS / rate;
+
+                for time in frame_times(spinner.start_time, spinner.end_time()) {
+                    let angle = (time - spinner.start_time) * speed;
+
+                    frames.push((
+                        time,
+                        Vector2::new(
+                            spinner.pos.x as f64 + angle.cos() * SPIN_RADIUS,
+                            spinner.pos.y as f64 + angle.sin() * SPIN_RADIUS,
+                        ),
+                    ));
+                }
+            },
+        }
+
+        let end_time = object.end_time();
+
+        let k1 = if object.start_time - last_time < ALTERNATE_THRESHOLD {
+            !last_k1
+        } else {
+            true
+        };
+
+        let release = match objects.get(i + 1) {
+            Some(next) => (end_time + KEY_UP_DELAY).min(next.start_time),
+            None => end_time + KEY_UP_DELAY,
+        };
+
+        presses.push(KeyPress {
+            press: object.start_time,
+            release,
+            k1,
+        });
+
+        last_time = end_time;
+        last_pos = frames.last().map_or(start_pos, |x| x.1);
+        last_k1 = k1;
+    }
+
+    // Key releases need their own frames
+    // to be noticed by the processor
+    let release_frames: Vec<_> = presses.iter()
+        .map(|x| (x.release, position_at(&frames, x.release)))
+        .collect();
+
+    frames.extend(release_frames);
+
+    frames.sort_by(|a, b| a.0.partial_cmp(&b.0).expect("failed to compare"));
+    frames.dedup_by(|a, b| a.0 == b.0);
+
+    let mut inputs = Vec::with_capacity(frames.len());
+    let mut last_keys = KeyboardState::empty();
+
+    for (ts, pos) in frames {
+        let mut keys = KeyboardState::empty();
+
+        for press in &presses {
+            if (press.press..press.release).contains(&ts) {
+                if press.k1 {
+                    keys.k1 = true;
+                } else {
+                    keys.k2 = true;
+                }
+            }
+        }
+
+        inputs.push(OsuInput {
+            ts,
+            pos,
+            keys,
+            hold: keys.intersection(&last_keys),
+        });
+
+        last_keys = keys;
+    }
+
+    inputs
+}
+
+fn start_position(object: &Object) -> Vector2<f64> {
+    match &object.kind {
+        ObjectKind::Circle(circle) => Vector2::new(circle.pos.x as f64, circle.pos.y as f64),
+        ObjectKind::Slider(slider) => Vector2::new(slider.pos.x as f64, slider.pos.y as f64),
+        ObjectKind::Spinner(spinner) => Vector2::new(
+            spinner.pos.x as f64 + SPIN_RADIUS,
+            spinner.pos.y as f64,
+        ),
+    }
+}
+
+/// Frames every [`FRAME_TIME`] from `start` up to `end`, both included
+fn frame_times(start: f64, end: f64) -> Vec<f64> {
+    let mut times = Vec::new();
+    let mut time = start;
+
+    while time < end {
+        times.push(time);
+        time += FRAME_TIME;
+    }
+
+    times.push(end);
+
+    times
+}
+
+/// Linear movement between two objects, frames are
+/// placed before `to_time`, so the object itself gets an exact frame
+fn move_to(
+    frames: &mut Vec<(f64, Vector2<f64>)>,
+    from_time: f64,
+    from: Vector2<f64>,
+    to_time: f64,
+    to: Vector2<f64>,
+) {
+    let start = from_time.max(to_time - MAX_MOVE_TIME);
+
+    if to_time <= start {
+        return;
+    }
+
+    let mut time = start;
+
+    while time < to_time {
+        let progress = (time - start) / (to_time - start);
+        frames.push((time, from + (to - from) * progress));
+        time += FRAME_TIME;
+    }
+}
+
+/// Cursor position at any time, interpolated between frames
+fn position_at(frames: &[(f64, Vector2<f64>)], time: f64) -> Vector2<f64> {
+    let next = frames.iter().position(|x| x.0 >= time);
+
+    match next {
+        Some(0) => frames[0].1,
+        Some(i) => interpolate(frames[i - 1], frames[i], time),
+        None => frames.last().map_or(Vector2::new(0.0, 0.0), |x| x.1),
+    }
+}
+
+/// Cursor position of the generated play at any time,
+/// used by autopilot to move the cursor for the player
+pub fn cursor_position_at(inputs: &[OsuInput], time: f64) -> Vector2<f64> {
+    let next = inputs.partition_point(|x| x.ts < time);
+
+    match next {
+        0 => inputs.first().map_or(Vector2::new(0.0, 0.0), |x| x.pos),
+        i if i == inputs.len() => inputs[i - 1].pos,
+        i => interpolate(
+            (inputs[i - 1].ts, inputs[i - 1].pos),
+            (inputs[i].ts, inputs[i].pos),
+            time,
+        ),
+    }
+}
+
+fn interpolate(
+    (prev_time, prev): (f64, Vector2<f64>),
+    (next_time, next): (f64, Vector2<f64>),
+    time: f64,
+) -> Vector2<f64> {
+    if next_time == prev_time {
+        return next;
+    }
+
+    let progress = (time - prev_time) / (next_time - prev_time);
+    prev + (next - prev) * progress
+}
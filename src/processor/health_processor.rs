@@ -0,0 +1,403 @@
+use rosu_map::Beatmap;
+
+use crate::{hit_objects::{break_period::BreakPeriod, ComboResult, Hit, Object, ObjectKind}, math::diff_rate};
+
+use super::judgement::{Judgement, JudgementKind};
+
+/// Stable keeps HP in 0..=200 range
+pub const MAX_HP: f64 = 200.0;
+
+pub const HP_300: f64 = 6.0;
+pub const HP_100: f64 = 2.2;
+pub const HP_50: f64 = 0.4;
+pub const HP_GEKI: f64 = 14.0;
+pub const HP_KATU: f64 = 10.0;
+pub const HP_SLIDER_REPEAT: f64 = 4.0;
+pub const HP_SLIDER_TICK: f64 = 3.0;
+pub const HP_SPINNER_SPIN: f64 = 1.7;
+pub const HP_SPINNER_BONUS: f64 = 2.0;
+
+/// Drain rate stable starts searching from, HP per ms
+const INITIAL_DRAIN_RATE: f64 = 0.05;
+
+/// Tracks HP based on judgements produced by [`super::OsuProcessor`].
+///
+/// Drain rate and gain multipliers are calculated the same way
+/// stable does, by simulating a perfect play and lowering
+/// the drain until HP never goes below map specific limits
+#[derive(Debug, Clone)]
+pub struct HealthProcessor {
+    pub hp: f64,
+
+    /// HP drained per millisecond
+    pub drain_rate: f64,
+    hp_multiplier_normal: f64,
+    hp_multiplier_combo_end: f64,
+    miss_penalty: f64,
+
+    drain_start: f64,
+    drain_end: f64,
+    breaks: Vec<BreakPeriod>,
+
+    last_update: Option<f64>,
+    failed: bool,
+}
+
+impl Default for HealthProcessor {
+    fn default() -> Self {
+        Self {
+            hp: MAX_HP,
+            drain_rate: 0.0,
+            hp_multiplier_normal: 1.0,
+            hp_multiplier_combo_end: 1.0,
+            miss_penalty: 0.0,
+            drain_start: 0.0,
+            drain_end: 0.0,
+            breaks: Vec::new(),
+            last_update: None,
+            failed: false,
+        }
+    }
+}
+
+/// Perfect play state used to find the drain rate
+struct Simulation {
+    hp: f64,
+    hp_uncapped: f64,
+}
+
+impl Simulation {
+    fn new() -> Self {
+        Self {
+            hp: MAX_HP,
+            hp_uncapped: MAX_HP,
+        }
+    }
+
+    fn reduce(&mut self, amount: f64) {
+        self.hp = (self.hp - amount).max(0.0);
+        self.hp_uncapped = (self.hp_uncapped - amount).max(0.0);
+    }
+
+    fn increase(&mut self, amount: f64) {
+        self.hp = (self.hp + amount).min(MAX_HP);
+        self.hp_uncapped += amount;
+    }
+}
+
+impl HealthProcessor {
+    pub fn new(map: &Beatmap, objects: &[Object]) -> Self {
+        let hp = map.hp_drain_rate;
+
+        let mut processor = Self {
+            miss_penalty: diff_rate(hp, -6.0, -25.0, -40.0) as f64,
+            drain_start: objects.first().map_or(0.0, |x| x.start_time),
+            drain_end: objects.last().map_or(0.0, |x| x.end_time()),
+            breaks: BreakPeriod::from_rosu(map),
+            ..Default::default()
+        };
+
+        processor.calc_drain_rate(hp, objects);
+
+        processor
+    }
+
+    /// Same search stable does, starts with a high drain and
+    /// lowers it every time the simulated play drops
+    /// below the allowed HP
+    fn calc_drain_rate(&mut self, hp: f32, objects: &[Object]) {
+        let _span = tracy_client::span!("health_processor::calc_drain_rate");
+
+        if objects.is_empty() {
+            return;
+        }
+
+        let lowest_hp_ever = diff_rate(hp, 195.0, 160.0, 60.0) as f64;
+        let lowest_hp_combo_end = diff_rate(hp, 198.0, 170.0, 80.0) as f64;
+        let lowest_hp_end = diff_rate(hp, 198.0, 180.0, 80.0) as f64;
+
+        let mut drain = INITIAL_DRAIN_RATE;
+        let mut multiplier_normal = 1.0;
+        let mut multiplier_combo_end = 1.0;
+
+        'search: loop {
+            let mut sim = Simulation::new();
+            let mut last_time = self.drain_start;
+            let mut combo_too_low = 0;
+
+            for (i, object) in objects.iter().enumerate() {
+                let break_time = self.break_time_between(last_time, object.start_time);
+
+                sim.reduce(drain * (object.start_time - last_time - break_time));
+                last_time = object.end_time();
+
+                if sim.hp <= lowest_hp_ever {
+                    drain *= 0.96;
+                    continue 'search;
+                }
+
+                let reduction = drain * (object.end_time() - object.start_time);
+                let overkill = (reduction - sim.hp).max(0.0);
+
+                sim.reduce(reduction);
+
+                match &object.kind {
+                    ObjectKind::Slider(slider) => {
+                        // Head, repeats and the end
+                        for _ in 0..slider.repeats + 1 {
+                            sim.increase(multiplier_normal * HP_SLIDER_REPEAT);
+                        }
+
+                        for _ in &slider.ticks {
+                            sim.increase(multiplier_normal * HP_SLIDER_TICK);
+                        }
+                    },
+                    ObjectKind::Spinner(spinner) => {
+                        for _ in 0..spinner.required_spins as u32 {
+                            sim.increase(multiplier_normal * HP_SPINNER_SPIN);
+                        }
+                    },
+                    ObjectKind::Circle(_) => {},
+                }
+
+                if overkill > 0.0 && sim.hp - overkill <= lowest_hp_ever {
+                    drain *= 0.96;
+                    continue 'search;
+                }
+
+                let is_combo_end = match objects.get(i + 1) {
+                    Some(next) => next.new_combo,
+                    None => true,
+                };
+
+                if is_combo_end {
+                    sim.increase(multiplier_combo_end * HP_GEKI + multiplier_normal * HP_300);
+
+                    if sim.hp_uncapped < lowest_hp_combo_end {
+                        combo_too_low += 1;
+
+                        if combo_too_low > 2 {
+                            multiplier_combo_end *= 1.07;
+                            multiplier_normal *= 1.03;
+                            continue 'search;
+                        }
+                    }
+                } else {
+                    sim.increase(multiplier_normal * HP_300);
+                }
+            }
+
+            if sim.hp < lowest_hp_end {
+                drain *= 0.94;
+                multiplier_combo_end *= 1.01;
+                multiplier_normal *= 1.01;
+                continue;
+            }
+
+            break;
+        }
+
+        self.drain_rate = drain;
+        self.hp_multiplier_normal = multiplier_normal;
+        self.hp_multiplier_combo_end = multiplier_combo_end;
+    }
+
+    /// Total duration of breaks fully contained in `start..end`
+    fn break_time_between(&self, start: f64, end: f64) -> f64 {
+        self.breaks.iter()
+            .filter(|x| x.start_time >= start && x.end_time <= end)
+            .map(|x| x.duration())
+            .sum()
+    }
+
+    /// Time inside of `start..end` during which HP is drained,
+    /// nothing is drained before the first object,
+    /// after the last one and during breaks
+    fn drain_time_between(&self, start: f64, end: f64) -> f64 {
+        let start = start.max(self.drain_start);
+        let end = end.min(self.drain_end);
+
+        if end <= start {
+            return 0.0;
+        }
+
+        let break_time: f64 = self.breaks.iter()
+            .map(|x| (end.min(x.end_time) - start.max(x.start_time)).max(0.0))
+            .sum();
+
+        end - start - break_time
+    }
+
+    /// Drains HP up to `time`
+    pub fn update(&mut self, time: f64) {
+        let _span = tracy_client::span!("health_processor::update");
+
+        let last_update = self.last_update.unwrap_or(time);
+        self.last_update = Some(time);
+
+        if self.failed || time <= last_update {
+            return;
+        }
+
+        let drained = self.drain_rate * self.drain_time_between(last_update, time);
+        self.set_hp(self.hp - drained);
+    }
+
+    pub fn apply(&mut self, judgement: &Judgement) {
+        let _span = tracy_client::span!("health_processor::apply");
+
+        if self.failed {
+            return;
+        }
+
+        let normal = self.hp_multiplier_normal;
+        let combo_end = self.hp_multiplier_combo_end;
+
+        let change = match judgement.kind {
+            JudgementKind::Circle(hit)
+            | JudgementKind::Slider(hit)
+            | JudgementKind::Spinner(hit) => match hit {
+                Hit::X300 => normal * HP_300,
+                Hit::X100 => normal * HP_100,
+                Hit::X50 => normal * HP_50,
+                Hit::MISS => self.miss_penalty,
+            },
+            JudgementKind::SliderHead(hit) => {
+                if hit == Hit::MISS {
+                    0.0
+                } else {
+                    normal * HP_SLIDER_REPEAT
+                }
+            },
+            JudgementKind::SliderRepeat { hit } | JudgementKind::SliderEnd { hit } => {
+                if hit { normal * HP_SLIDER_REPEAT } else { 0.0 }
+            },
+            JudgementKind::SliderTick { hit } => {
+                if hit { normal * HP_SLIDER_TICK } else { 0.0 }
+            },
+            JudgementKind::SpinnerSpins { spins, bonus_spins } => {
+                let regular_spins = spins.saturating_sub(bonus_spins);
+
+                normal * (regular_spins as f64 * HP_SPINNER_SPIN
+                    + bonus_spins as f64 * HP_SPINNER_BONUS)
+            },
+            JudgementKind::ComboEnd(ComboResult::Geki) => combo_end * HP_GEKI,
+            JudgementKind::ComboEnd(ComboResult::Katu) => combo_end * HP_KATU,
+        };
+
+        self.set_hp(self.hp + change);
+    }
+
+    pub fn apply_all<'a>(&mut self, judgements: impl IntoIterator<Item = &'a Judgement>) {
+        for judgement in judgements {
+            self.apply(judgement);
+        }
+    }
+
+    fn set_hp(&mut self, hp: f64) {
+        self.hp = hp.clamp(0.0, MAX_HP);
+
+        if self.hp <= 0.0 {
+            self.failed = true;
+        }
+    }
+
+    /// 0.0 >= Return value <= 1.0
+    #[inline]
+    pub fn health(&self) -> f64 {
+        self.hp / MAX_HP
+    }
+
+    #[inline]
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Back to full HP, drain rate and multipliers are kept
+    pub fn reset(&mut self) {
+        self.hp = MAX_HP;
+        self.last_update = None;
+        self.failed = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cgmath::Vector2;
+
+    use crate::hit_objects::{break_period::BreakPeriod, ComboResult, Hit};
+    use crate::processor::judgement::{Judgement, JudgementKind};
+
+    use super::{HealthProcessor, HP_300, MAX_HP};
+
+    fn judgement(kind: JudgementKind) -> Judgement {
+        Judgement {
+            at: 0.0,
+            pos: Vector2::new(0.0, 0.0),
+            object: 0,
+            kind,
+        }
+    }
+
+    fn processor() -> HealthProcessor {
+        HealthProcessor {
+            drain_rate: 0.01,
+            miss_penalty: -25.0,
+            drain_start: 1000.0,
+            drain_end: 10000.0,
+            breaks: vec![BreakPeriod::new(3000.0, 5000.0)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut health = processor();
+
+        // Nothing is drained before the first object
+        health.update(0.0);
+        health.update(1000.0);
+        assert_eq!(health.hp, MAX_HP);
+
+        health.update(2000.0);
+        assert_eq!(health.hp, MAX_HP - 10.0);
+
+        // Break is skipped
+        health.update(6000.0);
+        assert_eq!(health.hp, MAX_HP - 30.0);
+
+        // And everything after the last object
+        health.update(20000.0);
+        assert_eq!(health.hp, MAX_HP - 70.0);
+    }
+
+    #[test]
+    fn test_gains() {
+        let mut health = processor();
+        health.hp = 100.0;
+
+        health.apply(&judgement(JudgementKind::Circle(Hit::X300)));
+        assert_eq!(health.hp, 100.0 + HP_300);
+
+        health.apply(&judgement(JudgementKind::Circle(Hit::MISS)));
+        assert_eq!(health.hp, 100.0 + HP_300 - 25.0);
+
+        health.hp = MAX_HP - 1.0;
+        health.apply(&judgement(JudgementKind::ComboEnd(ComboResult::Geki)));
+        assert_eq!(health.hp, MAX_HP);
+    }
+
+    #[test]
+    fn test_fail() {
+        let mut health = processor();
+        health.hp = 20.0;
+
+        health.apply(&judgement(JudgementKind::Circle(Hit::MISS)));
+        assert!(health.is_failed());
+        assert_eq!(health.health(), 0.0);
+
+        // Failed play can't be recovered
+        health.apply(&judgement(JudgementKind::Circle(Hit::X300)));
+        assert_eq!(health.health(), 0.0);
+    }
+}
@@ -0,0 +1,172 @@
+use cgmath::Vector2;
+
+use crate::hit_objects::{slider::{Slider, SliderPart, SliderResultState}, spinner::Spinner, ComboResult, Hit, Object, ObjectKind};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum JudgementKind {
+    Circle(Hit),
+
+    /// Slider head is either `X300` or `MISS`
+    SliderHead(Hit),
+    SliderTick { hit: bool },
+    SliderRepeat { hit: bool },
+    SliderEnd { hit: bool },
+    /// Final judgement of the whole slider
+    Slider(Hit),
+
+    /// Full spins done during spinner, reported
+    /// right before the final spinner judgement
+    SpinnerSpins { spins: u32, bonus_spins: u32 },
+    Spinner(Hit),
+
+    /// Reported right after the final judgement
+    /// of the last object in combo
+    ComboEnd(ComboResult),
+}
+
+/// Single scoring event produced by [`super::OsuProcessor`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Judgement {
+    pub at: f64,
+    /// Where it happened in osu!pixels, e.g. position
+    /// of the slider tick for tick judgements
+    pub pos: Vector2<f32>,
+    /// Index of the object inside of the beatmap objects
+    pub object: usize,
+    pub kind: JudgementKind,
+}
+
+impl Judgement {
+    /// Final judgement of the object, all the
+    /// intermediate ones (like slider ticks) are `None`
+    pub fn final_hit(&self) -> Option<Hit> {
+        match self.kind {
+            JudgementKind::Circle(hit)
+            | JudgementKind::Slider(hit)
+            | JudgementKind::Spinner(hit) => Some(hit),
+            _ => None,
+        }
+    }
+
+    /// Missed slider tick or repeat, combo is reset
+    /// but the slider itself can still be hit
+    #[inline]
+    pub fn is_slider_break(&self) -> bool {
+        matches!(
+            self.kind,
+            JudgementKind::SliderTick { hit: false } | JudgementKind::SliderRepeat { hit: false }
+        )
+    }
+
+    /// Position of the last judged point of the object
+    pub fn end_pos(object: &Object) -> Vector2<f32> {
+        match &object.kind {
+            ObjectKind::Circle(circle) => Vector2::new(circle.pos.x, circle.pos.y),
+            ObjectKind::Slider(slider) => {
+                let pos = slider.end_pos();
+                Vector2::new(pos.x, pos.y)
+            },
+            ObjectKind::Spinner(spinner) => Vector2::new(spinner.pos.x, spinner.pos.y),
+        }
+    }
+
+    /// Collects final judgements of the finished object
+    pub fn from_object(index: usize, object: &Object, out: &mut Vec<Judgement>) {
+        match &object.kind {
+            ObjectKind::Circle(circle) => {
+                let Some(hit_result) = &circle.hit_result else {
+                    return;
+                };
+
+                out.push(Judgement {
+                    at: hit_result.at,
+                    pos: Vector2::new(circle.pos.x, circle.pos.y),
+                    object: index,
+                    kind: JudgementKind::Circle(hit_result.result),
+                });
+            },
+            ObjectKind::Slider(slider) => Self::from_slider(index, slider, out),
+            ObjectKind::Spinner(spinner) => Self::from_spinner(index, spinner, out),
+        }
+    }
+
+    /// Collects slider parts judged since the last call, `reported` is
+    /// the amount of already collected parts and is updated.
+    ///
+    /// Parts are reported as soon as they're judged, so slider
+    /// breaks are reaching score and HUD before the slider ends
+    pub fn from_slider_parts(
+        index: usize,
+        slider: &Slider,
+        reported: &mut usize,
+        out: &mut Vec<Judgement>,
+    ) {
+        let Some(hit_result) = &slider.hit_result else {
+            return;
+        };
+
+        for part in hit_result.parts.iter().skip(*reported) {
+            let kind = match part.part {
+                SliderPart::Head => JudgementKind::SliderHead(hit_result.head.result),
+                SliderPart::Tick => JudgementKind::SliderTick { hit: part.hit },
+                SliderPart::Repeat => JudgementKind::SliderRepeat { hit: part.hit },
+                SliderPart::End => JudgementKind::SliderEnd { hit: part.hit },
+            };
+
+            out.push(Judgement {
+                at: part.at,
+                pos: part.pos,
+                object: index,
+                kind,
+            });
+        }
+
+        *reported = hit_result.parts.len();
+    }
+
+    /// Only the final judgement, parts are
+    /// collected by [`Self::from_slider_parts`]
+    fn from_slider(index: usize, slider: &Slider, out: &mut Vec<Judgement>) {
+        let Some(hit_result) = &slider.hit_result else {
+            return;
+        };
+
+        let SliderResultState::Passed(final_hit) = hit_result.state else {
+            return;
+        };
+
+        let end_pos = slider.end_pos();
+
+        out.push(Judgement {
+            at: slider.end_time(),
+            pos: Vector2::new(end_pos.x, end_pos.y),
+            object: index,
+            kind: JudgementKind::Slider(final_hit),
+        });
+    }
+
+    fn from_spinner(index: usize, spinner: &Spinner, out: &mut Vec<Judgement>) {
+        let Some(hit_result) = &spinner.hit_result else {
+            return;
+        };
+
+        let pos = Vector2::new(spinner.pos.x, spinner.pos.y);
+
+        out.push(Judgement {
+            at: hit_result.at,
+            pos,
+            object: index,
+            kind: JudgementKind::SpinnerSpins {
+                spins: hit_result.spins.floor() as u32,
+                bonus_spins: hit_result.bonus_spins,
+            },
+        });
+
+        out.push(Judgement {
+            at: hit_result.at,
+            pos,
+            object: index,
+            kind: JudgementKind::Spinner(hit_result.result),
+        });
+    }
+}
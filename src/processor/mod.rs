@@ -1,19 +1,54 @@
 use cgmath::Vector2;
-use osu_replay_parser::replay::Replay;
+use osu_replay_parser::replay::{replay_data::Keys, Replay};
+use judgement::{Judgement, JudgementKind};
 use replay_log::ReplayLog;
 
-use crate::{hit_objects::{circle::CircleHitResult, hit_window::HitWindow, slider::SliderResult, Object}, osu_input::{KeyboardState, OsuInput}};
+use crate::{hit_objects::{circle::CircleHitResult, hit_window::HitWindow, ruleset::Ruleset, slider::SliderResult, ComboResult, Object, ObjectKind}, mods::Mods, osu_input::{KeyboardState, OsuInput}};
 
 pub mod replay_log;
+pub mod judgement;
+pub mod score_processor;
+pub mod health_processor;
+pub mod autoplay;
+pub mod assist;
+pub mod results;
 
 /// Responsible for 
 /// 1. Handling inputs
 /// 2. Assigning hit results based on recorded inputs
 pub struct OsuProcessor {
     replay_log: ReplayLog,
+    /// Inputs as they were judged, with assists applied
+    judged_log: ReplayLog,
     queue: Vec<OsuInput>,
 
+    /// Index of the first object that isn't finished yet
+    active_object: usize,
+
+    /// Judgements that weren't taken yet
+    judgements: Vec<Judgement>,
+    /// Objects which judgements were already reported
+    reported: Vec<bool>,
+    /// Amount of already reported slider parts for every object
+    reported_parts: Vec<usize>,
+
     last_cursor_pos: Vector2<f64>,
+
+    /// Clock rate of the gameplay. Inputs and objects are both
+    /// in beatmap time, but some limits are applied in real time
+    rate: f64,
+
+    /// Rules inputs are judged with
+    ruleset: Ruleset,
+
+    /// Relax and autopilot applied to the live inputs. Replays already
+    /// have keys pressed by relax and cursor moved by autopilot
+    /// in their frames, so they're processed as is, same as stable
+    assists: Mods,
+    /// Keys of the last input processed with relax
+    relax_last_keys: KeyboardState,
+    /// Cursor path autopilot follows, generated on the first processing
+    autopilot_path: Vec<OsuInput>,
 }
 
 impl Default for OsuProcessor {
@@ -21,12 +56,96 @@ impl Default for OsuProcessor {
         Self {
             last_cursor_pos: Vector2::new(0.0, 0.0),
             replay_log: Default::default(),
+            judged_log: Default::default(),
             queue: Vec::new(),
+            active_object: 0,
+            judgements: Vec::new(),
+            reported: Vec::new(),
+            reported_parts: Vec::new(),
+            rate: 1.0,
+            ruleset: Ruleset::default(),
+            assists: Mods::empty(),
+            relax_last_keys: KeyboardState::empty(),
+            autopilot_path: Vec::new(),
         }
     }
 }
 
 impl OsuProcessor {
+    #[inline]
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub fn set_rate(&mut self, rate: f64) {
+        self.rate = rate;
+    }
+
+    #[inline]
+    pub fn ruleset(&self) -> Ruleset {
+        self.ruleset
+    }
+
+    /// Has to be set before processing any input,
+    /// replays are judged with stable rules by default
+    pub fn set_ruleset(&mut self, ruleset: Ruleset) {
+        self.ruleset = ruleset;
+    }
+
+    /// Only [`Mods::RELAX`] and [`Mods::AUTOPILOT`] are kept
+    pub fn set_assists(&mut self, mods: Mods) {
+        self.assists = mods & (Mods::RELAX | Mods::AUTOPILOT);
+        self.autopilot_path.clear();
+    }
+
+    #[inline]
+    pub fn assists(&self) -> Mods {
+        self.assists
+    }
+
+    /// Index of the first object that isn't finished yet,
+    /// objects before it are never changed by processing
+    #[inline]
+    pub fn active_object(&self) -> usize {
+        self.active_object
+    }
+
+    /// Starts processing over from the object at `first`. Objects
+    /// before it are never visited again, so they aren't missed,
+    /// objects after it have to be reset by the caller
+    pub fn restart_from(&mut self, first: usize) {
+        self.replay_log = ReplayLog::default();
+        self.judged_log = ReplayLog::default();
+        self.queue.clear();
+        self.judgements.clear();
+
+        self.active_object = first;
+
+        for reported in self.reported.iter_mut().skip(first) {
+            *reported = false;
+        }
+
+        for parts in self.reported_parts.iter_mut().skip(first) {
+            *parts = 0;
+        }
+
+        self.relax_last_keys = KeyboardState::empty();
+    }
+
+    /// Every input processed so far, in order. Keys pressed by relax
+    /// and cursor moved by autopilot are already in the frames,
+    /// so they're played back without assists
+    #[inline]
+    pub fn replay_frames(&self) -> &[OsuInput] {
+        self.judged_log.frames()
+    }
+
+    /// Last known cursor position in osu!pixels
+    #[inline]
+    pub fn cursor_pos(&self) -> Vector2<f64> {
+        self.last_cursor_pos
+    }
+
     pub fn set_cursor_pos(&mut self, pos: Vector2<f64>) {
         self.last_cursor_pos = pos;
     }
@@ -42,10 +161,7 @@ impl OsuProcessor {
                 ts,
                 pos,
                 keys: last.keys,
-                hold: KeyboardState {
-                    k1: last.keys.k1,
-                    k2: last.keys.k2,
-                },
+                hold: last.keys,
             });
         } else {
             self.store_input(OsuInput {
@@ -66,54 +182,400 @@ impl OsuProcessor {
     ) {
         let _span = tracy_client::span!("processor::process_all");
 
-        'input_loop: for input in &self.queue {
-            for object in objects.iter_mut() {
-                match &mut object.kind {
-                    crate::hit_objects::ObjectKind::Circle(circle) => {
-                        let res = circle.update(
-                            input,
-                            hit_window,
-                            circle_diameter
-                        );
+        let mut queue = std::mem::take(&mut self.queue);
+
+        for input in &mut queue {
+            self.apply_assists(input, objects, hit_window, circle_diameter);
+            self.judged_log.store_input(input.clone());
+            Self::process_input(input, objects, hit_window, circle_diameter, self.rate, self.ruleset);
+        }
+
+        // Keeping allocation
+        queue.clear();
+        self.queue = queue;
+
+        self.collect_judgements(objects, 0, f64::MAX);
+    }
+
+    /// Processes queued inputs only against objects that
+    /// can still be judged, and assigns misses up to `ts`.
+    ///
+    /// Objects before the first unfinished one are never
+    /// visited again, so it's cheap enough to call every frame
+    pub fn process(
+        &mut self,
+        ts: f64,
+        objects: &mut [Object],
+        hit_window: &HitWindow,
+        circle_diameter: f32,
+    ) {
+        let _span = tracy_client::span!("processor::process");
+
+        self.advance_active_object(objects);
+
+        // Assists have to act even if the cursor
+        // isn't moving and nothing is pressed
+        let has_frame = match self.queue.last() {
+            Some(last) => last.ts >= ts,
+            None => false,
+        };
+
+        if !self.assists.is_empty() && !has_frame {
+            self.store_cursor_moved(ts, self.last_cursor_pos);
+        }
+
+        let mut queue = std::mem::take(&mut self.queue);
+
+        for input in &mut queue {
+            self.apply_assists(input, objects, hit_window, circle_diameter);
+            self.judged_log.store_input(input.clone());
+
+            Self::process_input(
+                input,
+                &mut objects[self.active_object..],
+                hit_window,
+                circle_diameter,
+                self.rate,
+                self.ruleset,
+            );
+        }
+
+        queue.clear();
+        self.queue = queue;
+
+        Self::apply_misses(&mut objects[self.active_object..], ts, hit_window, self.ruleset);
+
+        let from = self.active_object;
+        self.advance_active_object(objects);
+
+        // Objects the active one moved past are never visited again,
+        // so they're reported even if finished ahead of `ts`, e.g. hit early
+        let passed = objects[..self.active_object].last()
+            .map_or(f64::MIN, |x| x.start_time);
+
+        self.collect_judgements(
+            objects,
+            from,
+            (ts + hit_window.xmiss.round()).max(passed)
+        );
+    }
+
+    /// Replaces keys pressed by relax and cursor position
+    /// moved by autopilot
+    fn apply_assists(
+        &mut self,
+        input: &mut OsuInput,
+        objects: &[Object],
+        hit_window: &HitWindow,
+        circle_diameter: f32,
+    ) {
+        if self.assists.contains(Mods::AUTOPILOT) {
+            if self.autopilot_path.is_empty() {
+                self.autopilot_path = autoplay::generate(objects, self.rate);
+            }
+
+            input.pos = autoplay::cursor_position_at(&self.autopilot_path, input.ts);
+            self.last_cursor_pos = input.pos;
+        }
+
+        if self.assists.contains(Mods::RELAX) {
+            let keys = assist::relax_keys(
+                input.ts,
+                input.pos,
+                self.relax_last_keys,
+                &objects[self.active_object..],
+                hit_window,
+                circle_diameter,
+                self.ruleset,
+            );
+
+            input.keys = keys;
+            input.hold = keys.intersection(&self.relax_last_keys);
+
+            self.relax_last_keys = keys;
+        }
+    }
+
+    /// Returns all judgements produced since the last call
+    /// ordered by time
+    pub fn take_judgements(&mut self) -> Vec<Judgement> {
+        std::mem::take(&mut self.judgements)
+    }
+
+    /// Collects judgements of finished objects
+    /// starting before `until`
+    fn collect_judgements(&mut self, objects: &mut [Object], from: usize, until: f64) {
+        if self.reported.len() != objects.len() {
+            self.reported = vec![false; objects.len()];
+            self.reported_parts = vec![0; objects.len()];
+        }
+
+        let start = self.judgements.len();
+
+        for i in from..objects.len() {
+            if objects[i].start_time > until {
+                break;
+            }
+
+            if self.reported[i] {
+                continue;
+            }
+
+            if let ObjectKind::Slider(slider) = &objects[i].kind {
+                Judgement::from_slider_parts(
+                    i,
+                    slider,
+                    &mut self.reported_parts[i],
+                    &mut self.judgements
+                );
+            }
+
+            if !objects[i].is_finished() {
+                continue;
+            }
+
+            self.reported[i] = true;
+            Judgement::from_object(i, &objects[i], &mut self.judgements);
+
+            self.collect_combo_end(objects, i);
+        }
+
+        // Objects are not always finished in order,
+        // e.g. circles placed during slider body
+        self.judgements[start..]
+            .sort_by(|a, b| a.at.partial_cmp(&b.at).expect("failed to compare"));
+    }
+
+    /// Reports geki or katu of the combo `i` belongs to, only once
+    /// all objects of the combo are reported. Objects are not always
+    /// finished in order, so it doesn't have to be the last one
+    fn collect_combo_end(&mut self, objects: &mut [Object], i: usize) {
+        let combo_start = objects[..=i].iter()
+            .rposition(|x| x.new_combo)
+            .unwrap_or(0);
+
+        let combo_end = objects[i + 1..].iter()
+            .position(|x| x.new_combo)
+            .map_or(objects.len() - 1, |x| i + x);
+
+        if !self.reported[combo_start..=combo_end].iter().all(|x| *x) {
+            return;
+        }
 
-                        if res {
-                            continue 'input_loop;
+        let combo_result = ComboResult::from_hits(
+            objects[combo_start..=combo_end].iter().filter_map(|x| x.final_hit())
+        );
+
+        objects[combo_end].combo_result = combo_result;
+
+        if let Some(combo_result) = combo_result {
+            self.judgements.push(Judgement {
+                at: objects[combo_end].end_time(),
+                pos: Judgement::end_pos(&objects[combo_end]),
+                object: combo_end,
+                kind: JudgementKind::ComboEnd(combo_result),
+            });
+        }
+    }
+
+    /// Moves cursor past all finished objects
+    fn advance_active_object(&mut self, objects: &[Object]) {
+        while self.active_object < objects.len()
+            && objects[self.active_object].is_finished() {
+            self.active_object += 1;
+        }
+    }
+
+    /// Applies single input to the objects
+    ///
+    /// Follows note lock rules of the `ruleset`. In stable press on the
+    /// object is ignored while any earlier circle or slider head can
+    /// still be hit. In lazer it's ignored only before the start time
+    /// of the previous unjudged circle or slider head, and hit misses
+    /// every earlier head that wasn't judged yet.
+    /// Ignored press marks object as shaking
+    fn process_input(
+        input: &OsuInput,
+        objects: &mut [Object],
+        hit_window: &HitWindow,
+        circle_diameter: f32,
+        rate: f64,
+        ruleset: Ruleset,
+    ) {
+        // Earlier object is still waiting to be hit
+        let mut locked = false;
+
+        for i in 0..objects.len() {
+            let (before, rest) = objects.split_at_mut(i);
+            let object = &mut rest[0];
+            let start_time = object.start_time;
+
+            // Objects are sorted by start time, so input
+            // can't affect anything after that
+            if object.start_time - input.ts >= hit_window.xmiss.round() {
+                break;
+            }
+
+            let is_locked = match ruleset {
+                Ruleset::Stable => locked,
+                Ruleset::Lazer => Self::is_lazer_locked(before, object.start_time, input.ts),
+            };
+
+            match &mut object.kind {
+                crate::hit_objects::ObjectKind::Circle(circle) => {
+                    // Hit window is already passed
+                    if circle.update_miss(input.ts, hit_window, ruleset) {
+                        continue;
+                    }
+
+                    if is_locked {
+                        if circle.check_hit(input, hit_window, circle_diameter, ruleset).is_some() {
+                            object.shake_at = Some(input.ts);
+                            return;
                         }
 
-                    },
-                    crate::hit_objects::ObjectKind::Slider(slider) => {
+                        continue;
+                    }
+
+                    let res = circle.update(
+                        input,
+                        hit_window,
+                        circle_diameter,
+                        ruleset,
+                    );
+
+                    if res {
+                        Self::miss_skipped(before, start_time, input.ts, ruleset);
+                        return;
+                    }
+
+                    if circle.is_hittable(input.ts, hit_window, ruleset) {
+                        locked = true;
+                    }
+                },
+                crate::hit_objects::ObjectKind::Slider(slider) => {
+                    if is_locked {
+                        if slider.check_head_hit(input, hit_window, circle_diameter, ruleset).is_some() {
+                            object.shake_at = Some(input.ts);
+                            return;
+                        }
+                    } else {
                         if slider.update(
                             input,
                             hit_window,
-                            circle_diameter
+                            circle_diameter,
+                            ruleset,
                         ).is_some() {
-                            continue 'input_loop;
+                            Self::miss_skipped(before, start_time, input.ts, ruleset);
+                            return;
                         };
 
-                        slider.update_post(
-                            input,
-                            hit_window,
-                            circle_diameter
-                        );
+                        if slider.is_head_hittable(input.ts, hit_window, ruleset) {
+                            locked = true;
+                        }
+                    }
 
-                        continue;
-                    },
-                }
+                    slider.update_post(
+                        input,
+                        hit_window,
+                        circle_diameter,
+                        ruleset,
+                    );
+
+                    continue;
+                },
+                crate::hit_objects::ObjectKind::Spinner(spinner) => {
+                    // Spinners never consume inputs, so objects
+                    // after it can be still hit during spinning
+                    spinner.update(input, rate);
+                },
             }
         }
+    }
 
-        self.queue.clear();
+    /// Lazer note lock: the last circle or slider head starting
+    /// before `start_time` has to be judged or it's start time passed
+    fn is_lazer_locked(before: &[Object], start_time: f64, time: f64) -> bool {
+        let blocking = before.iter().rev()
+            .filter(|x| x.start_time < start_time)
+            .find(|x| !matches!(x.kind, ObjectKind::Spinner(_)));
+
+        match blocking.map(|x| &x.kind) {
+            Some(ObjectKind::Circle(circle)) => circle.hit_result.is_none() && time < circle.start_time,
+            Some(ObjectKind::Slider(slider)) => slider.hit_result.is_none() && time < slider.start_time,
+            _ => false,
+        }
     }
-    
-    pub fn process(&mut self, _ts: f64, _objects: &mut [Object]) {
-        todo!();
+
+    /// Lazer misses every circle and slider head starting before
+    /// `start_time` that wasn't judged when later object is hit.
+    /// Objects at the same time are left to be hit separately
+    fn miss_skipped(before: &mut [Object], start_time: f64, time: f64, ruleset: Ruleset) {
+        if ruleset != Ruleset::Lazer {
+            return;
+        }
+
+        for object in before.iter_mut().filter(|x| x.start_time < start_time) {
+            match &mut object.kind {
+                ObjectKind::Circle(circle) if circle.hit_result.is_none() => circle.miss(time),
+                ObjectKind::Slider(slider) => slider.miss_head(time),
+                _ => {},
+            }
+        }
     }
 
+    /// Assigns misses to the objects which were never
+    /// hit until provided time.
+    ///
+    /// Unlike [`OsuProcessor::process_all`] doesn't depend on inputs.
+    /// [`OsuProcessor::process`] calls it every frame with current time
+    pub fn process_misses(
+        &mut self,
+        objects: &mut [Object],
+        time: f64,
+        hit_window: &HitWindow,
+    ) {
+        let _span = tracy_client::span!("processor::process_misses");
+
+        Self::apply_misses(objects, time, hit_window, self.ruleset);
+        self.collect_judgements(objects, 0, time);
+    }
+
+    fn apply_misses(
+        objects: &mut [Object],
+        time: f64,
+        hit_window: &HitWindow,
+        ruleset: Ruleset,
+    ) {
+        for object in objects.iter_mut() {
+            if object.start_time > time {
+                break;
+            }
+
+            match &mut object.kind {
+                crate::hit_objects::ObjectKind::Circle(circle) => {
+                    circle.update_miss(time, hit_window, ruleset);
+                },
+                crate::hit_objects::ObjectKind::Slider(slider) => {
+                    slider.update_miss(time, hit_window, ruleset);
+                },
+                crate::hit_objects::ObjectKind::Spinner(spinner) => {
+                    spinner.update_miss(time);
+                },
+            }
+        }
+    }
+    
     /// This function treats KeyboardState with reversed meaning
     /// `true` means that particular key is released
     pub fn store_keyboard_released(&mut self, ts: f64, state: KeyboardState) {
         let _span = tracy_client::span!("processor::store_keyboard_released");
 
+        // Relax presses keys by itself
+        if self.assists.contains(Mods::RELAX) {
+            return;
+        }
+
         let last = self.replay_log.last_input();
 
         if let Some(last) = last {
@@ -127,14 +589,8 @@ impl OsuProcessor {
             self.store_input(OsuInput {
                 ts,
                 pos: self.last_cursor_pos,
-                keys: KeyboardState {
-                    k1: if state.k1 { false } else { last_keys.k1 },
-                    k2: if state.k2 { false } else { last_keys.k2 },
-                },
-                hold: KeyboardState {
-                    k1: if last.hold.k1 && state.k1 { false } else { last.hold.k1 },
-                    k2: if last.hold.k2 && state.k2 { false } else { last.hold.k2 },
-                },
+                keys: last_keys.difference(&state),
+                hold: last.hold.difference(&state),
             });
         } else {
             tracing::warn!("Trying to store release without previous input")
@@ -144,36 +600,38 @@ impl OsuProcessor {
     pub fn store_keyboard_pressed(&mut self, ts: f64, state: KeyboardState) {
         let _span = tracy_client::span!("processor::store_keyboard_pressed");
 
+        if self.assists.contains(Mods::RELAX) {
+            return;
+        }
+
         let last = self.replay_log.last_input();
 
         if let Some(last) = last {
             let last = last.keys;
 
+            // Keys that are already down stay pressed,
+            // e.g. keyboard key while holding a mouse button
             self.store_input(OsuInput {
                 ts,
                 pos: self.last_cursor_pos,
-                keys: KeyboardState {
-                    k1: state.k1,
-                    k2: state.k2,
-                },
-                hold: KeyboardState {
-                    k1: last.k1 && state.k1,
-                    k2: last.k2 && state.k2,
-                },
+                keys: last.union(&state),
+                hold: last,
             });
         } else {
             self.store_input(OsuInput {
                 ts,
                 pos: self.last_cursor_pos,
                 keys: state,
-                hold: KeyboardState {
-                    k1: false,
-                    k2: false,
-                },
+                hold: KeyboardState::empty(),
             });
         }
     }
 
+    /// Inputs that are waiting to be processed
+    pub fn queued_inputs(&self) -> &[OsuInput] {
+        &self.queue
+    }
+
     pub fn store_input(&mut self, input: OsuInput) {
         let _span = tracy_client::span!("processor::store_input");
         self.queue.push(input.clone());
@@ -181,8 +639,27 @@ impl OsuProcessor {
     }
 }
 
+/// Keys of the replay frame. Stable sets mouse bit together with
+/// the keyboard one, so mouse buttons are pressed only if
+/// the matching keyboard key isn't
+pub fn replay_keys(keys: Keys) -> KeyboardState {
+    let k1 = keys.contains(Keys::K1);
+    let k2 = keys.contains(Keys::K2);
+
+    KeyboardState {
+        k1,
+        k2,
+        m1: keys.contains(Keys::M1) && !k1,
+        m2: keys.contains(Keys::M2) && !k2,
+    }
+}
+
 impl From<Replay> for OsuProcessor {
     fn from(value: Replay) -> Self {
+        // Frames are stored in beatmap time, but
+        // processor still needs to know the rate
+        let mods = Mods::from_bits_truncate(value.mods as u32);
+
         let mut ts = 0;
         let mut inputs = Vec::new();
 
@@ -192,10 +669,7 @@ impl From<Replay> for OsuProcessor {
             let input = OsuInput {
                 ts: ts as f64,
                 pos: Vector2::new(frame.x as f64, frame.y as f64),
-                keys: KeyboardState {
-                    k1: frame.z.contains(osu_replay_parser::replay::replay_data::Keys::K1),
-                    k2: frame.z.contains(osu_replay_parser::replay::replay_data::Keys::K2),
-                },
+                keys: replay_keys(frame.z),
                 hold: KeyboardState::default(),
             };
 
@@ -241,20 +715,26 @@ impl From<Replay> for OsuProcessor {
         let mut last = KeyboardState::default();
         for input in &mut new_inputs {
 
-            input.hold = KeyboardState {
-                k1: input.keys.k1 && last.k1,
-                k2: input.keys.k2 && last.k2,
-                //m1: input.keys.m1 && last.m1,
-                //m2: input.keys.m2 && last.m2,
-            };
+            input.hold = input.keys.intersection(&last);
 
             last = input.keys.clone();
         }
         
         Self {
             replay_log: ReplayLog::default(),
+            judged_log: ReplayLog::default(),
             queue: new_inputs,
+            active_object: 0,
+            judgements: Vec::new(),
+            reported: Vec::new(),
+            reported_parts: Vec::new(),
             last_cursor_pos: Vector2::new(0.0, 0.0),
+            rate: mods.clock_rate(),
+            ruleset: Ruleset::default(),
+            // Relax and autopilot are already baked into frames
+            assists: Mods::empty(),
+            relax_last_keys: KeyboardState::empty(),
+            autopilot_path: Vec::new(),
         }
     }
 }
@@ -268,8 +748,8 @@ fn test_input_released() {
         KeyboardState {
             k1: true,
             k2: false,
-            //m1: false,
-            //m2: false,
+            m1: false,
+            m2: false,
         }
     );
 
@@ -278,8 +758,8 @@ fn test_input_released() {
         KeyboardState {
             k1: true,
             k2: false,
-            //m1: false,
-            //m2: false,
+            m1: false,
+            m2: false,
         }
     );
 
@@ -288,8 +768,8 @@ fn test_input_released() {
         KeyboardState {
             k1: true,
             k2: false,
-            //m1: false,
-            //m2: false,
+            m1: false,
+            m2: false,
         }
     );
 
@@ -302,8 +782,8 @@ fn test_input_released() {
         KeyboardState {
             k1: true,
             k2: false,
-            //m1: false,
-            //m2: false,
+            m1: false,
+            m2: false,
         }
     );
 
@@ -311,3 +791,34 @@ fn test_input_released() {
     assert_eq!(last_input.keys.is_keys_hit(), false);
     assert_eq!(last_input.ts, 150.0);
 }
+
+#[test]
+fn test_replay_mouse_buttons() {
+    let keyboard = replay_keys(Keys::M1 | Keys::K1);
+    assert!(keyboard.k1 && !keyboard.m1);
+
+    let mouse = replay_keys(Keys::M1);
+    assert!(mouse.m1 && !mouse.k1);
+    assert!(mouse.is_left());
+
+    let both = replay_keys(Keys::M1 | Keys::M2 | Keys::K2);
+    assert!(both.m1 && both.k2 && !both.m2 && !both.k1);
+}
+
+#[test]
+fn test_mouse_and_keyboard_held_together() {
+    let mut processor = OsuProcessor::default();
+
+    processor.store_keyboard_pressed(100.0, KeyboardState { m1: true, ..KeyboardState::empty() });
+    processor.store_keyboard_pressed(150.0, KeyboardState { k2: true, ..KeyboardState::empty() });
+
+    let last_input = processor.replay_log.last_input().unwrap();
+    assert!(last_input.keys.m1 && last_input.keys.k2);
+    assert!(last_input.hold.m1 && !last_input.hold.k2);
+    assert!(last_input.is_keys_hit_no_hold());
+
+    processor.store_keyboard_released(200.0, KeyboardState { m1: true, ..KeyboardState::empty() });
+
+    let last_input = processor.replay_log.last_input().unwrap();
+    assert!(!last_input.keys.m1 && last_input.keys.k2);
+}
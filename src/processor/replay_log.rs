@@ -10,6 +10,10 @@ impl ReplayLog {
         self.frames.push(input);
     }
 
+    pub fn frames(&self) -> &[OsuInput] {
+        &self.frames
+    }
+
     pub fn last_input(&self) -> Option<OsuInput> {
         self.frames.last().cloned() // TODO remove unwrap lol
     }
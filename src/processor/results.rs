@@ -0,0 +1,199 @@
+use crate::hit_objects::{hit_window::HitWindow, Hit, Object, ObjectKind};
+
+use super::score_processor::{Grade, ScoreProcessor};
+
+/// Everything shown on the results screen once the play is finished
+#[derive(Debug, Clone)]
+pub struct PlayResults {
+    pub score: u64,
+    pub accuracy: f64,
+    pub max_combo: u32,
+    pub grade: Grade,
+
+    pub x300: u32,
+    pub x100: u32,
+    pub x50: u32,
+    pub xmiss: u32,
+    pub xgeki: u32,
+    pub xkatu: u32,
+    pub slider_breaks: u32,
+
+    /// Hit errors of circles and slider heads in real
+    /// time, negative ones are early
+    pub hit_errors: Vec<f64>,
+    /// Hit windows in real time, same as the errors
+    pub hit_window: HitWindow,
+
+    /// Beatmap times of missed objects
+    pub miss_times: Vec<f64>,
+    /// Beatmap times of missed slider ticks and repeats
+    pub slider_break_times: Vec<f64>,
+    /// Start of the first object and end of the last one
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+impl PlayResults {
+    /// `hit_window` is in beatmap time, errors and windows are
+    /// divided by the `rate` so they match what player felt
+    pub fn new(
+        score: &ScoreProcessor,
+        objects: &[Object],
+        hit_window: &HitWindow,
+        rate: f64,
+    ) -> Self {
+        let mut hit_errors = Vec::new();
+        let mut miss_times = Vec::new();
+        let mut slider_break_times = Vec::new();
+
+        for object in objects {
+            if object.final_hit() == Some(Hit::MISS) {
+                miss_times.push(object.start_time);
+            }
+
+            let head = match &object.kind {
+                ObjectKind::Circle(circle) => circle.hit_result.as_ref(),
+                ObjectKind::Slider(slider) => {
+                    let Some(hit_result) = &slider.hit_result else {
+                        continue;
+                    };
+
+                    slider_break_times.extend(
+                        hit_result.parts.iter()
+                            .filter(|x| x.is_slider_break())
+                            .map(|x| x.at)
+                    );
+
+                    Some(&hit_result.head)
+                },
+                ObjectKind::Spinner(_) => None,
+            };
+
+            if let Some(head) = head.filter(|x| x.result != Hit::MISS) {
+                hit_errors.push((head.at - object.start_time) / rate);
+            }
+        }
+
+        Self {
+            score: score.score,
+            accuracy: score.accuracy(),
+            max_combo: score.max_combo,
+            grade: score.grade(),
+            x300: score.x300,
+            x100: score.x100,
+            x50: score.x50,
+            xmiss: score.xmiss,
+            xgeki: score.xgeki,
+            xkatu: score.xkatu,
+            slider_breaks: score.slider_breaks,
+            hit_errors,
+            hit_window: HitWindow {
+                x300: hit_window.x300 / rate,
+                x100: hit_window.x100 / rate,
+                x50: hit_window.x50 / rate,
+                xmiss: hit_window.xmiss / rate,
+            },
+            miss_times,
+            slider_break_times,
+            start_time: objects.first().map_or(0.0, |x| x.start_time),
+            end_time: objects.last().map_or(0.0, |x| x.end_time()),
+        }
+    }
+
+    /// Average hit error, negative if player is mostly early
+    pub fn mean_error(&self) -> f64 {
+        if self.hit_errors.is_empty() {
+            return 0.0;
+        }
+
+        self.hit_errors.iter().sum::<f64>() / self.hit_errors.len() as f64
+    }
+
+    /// Standard deviation of the hit errors times 10, same as stable
+    pub fn unstable_rate(&self) -> f64 {
+        if self.hit_errors.is_empty() {
+            return 0.0;
+        }
+
+        let mean = self.mean_error();
+
+        let variance = self.hit_errors.iter()
+            .map(|x| (x - mean).powi(2))
+            .sum::<f64>() / self.hit_errors.len() as f64;
+
+        variance.sqrt() * 10.0
+    }
+
+    /// Amount of hit errors in each of `bins` equal parts of
+    /// the x50 window, from the earliest to the latest
+    pub fn histogram(&self, bins: usize) -> Vec<u32> {
+        let mut histogram = vec![0; bins];
+
+        if bins == 0 {
+            return histogram;
+        }
+
+        let range = self.hit_window.x50;
+        let bin_size = range * 2.0 / bins as f64;
+
+        for error in &self.hit_errors {
+            let bin = ((error + range) / bin_size).floor().max(0.0) as usize;
+            histogram[bin.min(bins - 1)] += 1;
+        }
+
+        histogram
+    }
+
+    /// Position of `time` on the timeline, in 0.0..=1.0
+    pub fn timeline_progress(&self, time: f64) -> f64 {
+        let duration = self.end_time - self.start_time;
+
+        if duration <= 0.0 {
+            return 0.0;
+        }
+
+        ((time - self.start_time) / duration).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{hit_objects::hit_window::HitWindow, processor::score_processor::ScoreProcessor};
+
+    use super::PlayResults;
+
+    fn with_errors(hit_errors: Vec<f64>) -> PlayResults {
+        PlayResults {
+            hit_errors,
+            ..PlayResults::new(&ScoreProcessor::default(), &[], &HitWindow::from_od(5.0), 1.0)
+        }
+    }
+
+    #[test]
+    fn test_unstable_rate() {
+        let results = with_errors(vec![-10.0, 10.0, -10.0, 10.0]);
+
+        assert_relative_eq!(results.mean_error(), 0.0);
+        assert_relative_eq!(results.unstable_rate(), 100.0);
+
+        let results = with_errors(Vec::new());
+        assert_relative_eq!(results.unstable_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_histogram() {
+        // OD 5: x50 is 150
+        let results = with_errors(vec![-150.0, -1.0, 0.0, 20.0, 149.0, 500.0]);
+
+        assert_eq!(results.histogram(6), vec![1, 0, 1, 2, 0, 2]);
+    }
+
+    #[test]
+    fn test_rate_shortens_windows() {
+        let results = PlayResults::new(&ScoreProcessor::default(), &[], &HitWindow::from_od(5.0), 1.5);
+
+        assert_relative_eq!(results.hit_window.x50, 100.0);
+    }
+}
@@ -0,0 +1,347 @@
+use rosu_map::Beatmap;
+
+use crate::hit_objects::{break_period::BreakPeriod, ComboResult, Hit, Object};
+
+use super::judgement::{Judgement, JudgementKind};
+
+pub const SLIDER_TICK_POINTS: u64 = 10;
+pub const SLIDER_REPEAT_POINTS: u64 = 30;
+pub const SLIDER_END_POINTS: u64 = 30;
+pub const SLIDER_HEAD_POINTS: u64 = 30;
+pub const SPINNER_SPIN_POINTS: u64 = 100;
+pub const SPINNER_BONUS_POINTS: u64 = 1000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Grade {
+    SS,
+    S,
+    A,
+    B,
+    C,
+    D,
+}
+
+/// Tracks score, combo and accuracy (ScoreV1)
+/// based on judgements produced by [`super::OsuProcessor`]
+#[derive(Debug, Clone)]
+pub struct ScoreProcessor {
+    pub score: u64,
+    pub combo: u32,
+    pub max_combo: u32,
+
+    pub x300: u32,
+    pub x100: u32,
+    pub x50: u32,
+    pub xmiss: u32,
+    pub xgeki: u32,
+    pub xkatu: u32,
+    /// Missed slider ticks and repeats
+    pub slider_breaks: u32,
+
+    /// Stable's "difficulty points", depends on HP, CS, OD
+    /// and objects density
+    pub difficulty_multiplier: f64,
+    pub mod_multiplier: f64,
+}
+
+impl Default for ScoreProcessor {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            combo: 0,
+            max_combo: 0,
+            x300: 0,
+            x100: 0,
+            x50: 0,
+            xmiss: 0,
+            xgeki: 0,
+            xkatu: 0,
+            slider_breaks: 0,
+            difficulty_multiplier: 1.0,
+            mod_multiplier: 1.0,
+        }
+    }
+}
+
+impl ScoreProcessor {
+    pub fn new(map: &Beatmap, objects: &[Object]) -> Self {
+        Self {
+            difficulty_multiplier: Self::calc_difficulty_multiplier(map, objects),
+            ..Default::default()
+        }
+    }
+
+    /// Same as stable, `round((HP + CS + OD + density) / 38 * 5)`
+    /// where density is clamped amount of objects per drain second.
+    ///
+    /// Drain length is whole seconds between rounded object times
+    /// without breaks, maps without it get the highest density
+    pub fn calc_difficulty_multiplier(map: &Beatmap, objects: &[Object]) -> f64 {
+        let drain_seconds = match (objects.first(), objects.last()) {
+            (Some(first), Some(last)) => {
+                let break_time: i64 = BreakPeriod::from_rosu(map).iter()
+                    .map(|x| x.end_time.round() as i64 - x.start_time.round() as i64)
+                    .sum();
+
+                let total = last.end_time().round() as i64
+                    - first.start_time.round() as i64
+                    - break_time;
+
+                total / 1000
+            },
+            _ => 0,
+        };
+
+        let density = if drain_seconds > 0 {
+            (objects.len() as f32 / drain_seconds as f32 * 8.0).clamp(0.0, 16.0)
+        } else {
+            16.0
+        };
+
+        ((map.hp_drain_rate as f64
+            + map.circle_size as f64
+            + map.overall_difficulty as f64
+            + density as f64) / 38.0 * 5.0).round()
+    }
+
+    pub fn apply(&mut self, judgement: &Judgement) {
+        let _span = tracy_client::span!("score_processor::apply");
+
+        if judgement.is_slider_break() {
+            self.slider_breaks += 1;
+        }
+
+        match judgement.kind {
+            JudgementKind::Circle(hit) | JudgementKind::Spinner(hit) => {
+                self.add_hit(hit);
+                self.increase_combo(hit != Hit::MISS);
+            },
+            JudgementKind::SliderHead(hit) => {
+                if hit != Hit::MISS {
+                    self.score += SLIDER_HEAD_POINTS;
+                }
+
+                self.increase_combo(hit != Hit::MISS);
+            },
+            JudgementKind::SliderTick { hit } => {
+                if hit {
+                    self.score += SLIDER_TICK_POINTS;
+                }
+
+                self.increase_combo(hit);
+            },
+            JudgementKind::SliderRepeat { hit } => {
+                if hit {
+                    self.score += SLIDER_REPEAT_POINTS;
+                }
+
+                self.increase_combo(hit);
+            },
+            JudgementKind::SliderEnd { hit } => {
+                // Missing slider end doesn't break combo
+                if hit {
+                    self.score += SLIDER_END_POINTS;
+                    self.increase_combo(true);
+                }
+            },
+            JudgementKind::Slider(hit) => {
+                // Slider parts already gave combo
+                self.add_hit(hit);
+
+                if hit == Hit::MISS {
+                    self.combo = 0;
+                }
+            },
+            JudgementKind::SpinnerSpins { spins, bonus_spins } => {
+                let regular_spins = spins.saturating_sub(bonus_spins);
+
+                self.score += regular_spins as u64 * SPINNER_SPIN_POINTS;
+                self.score += bonus_spins as u64 * SPINNER_BONUS_POINTS;
+            },
+            JudgementKind::ComboEnd(ComboResult::Geki) => self.xgeki += 1,
+            JudgementKind::ComboEnd(ComboResult::Katu) => self.xkatu += 1,
+        }
+    }
+
+    pub fn apply_all<'a>(&mut self, judgements: impl IntoIterator<Item = &'a Judgement>) {
+        for judgement in judgements {
+            self.apply(judgement);
+        }
+    }
+
+    fn add_hit(&mut self, hit: Hit) {
+        let value = match hit {
+            Hit::X300 => {
+                self.x300 += 1;
+                300
+            },
+            Hit::X100 => {
+                self.x100 += 1;
+                100
+            },
+            Hit::X50 => {
+                self.x50 += 1;
+                50
+            },
+            Hit::MISS => {
+                self.xmiss += 1;
+                0
+            },
+        };
+
+        let combo_multiplier = self.combo.saturating_sub(1) as f64;
+        let bonus = value as f64 * combo_multiplier
+            * self.difficulty_multiplier
+            * self.mod_multiplier / 25.0;
+
+        self.score += value + bonus as u64;
+    }
+
+    fn increase_combo(&mut self, hit: bool) {
+        if hit {
+            self.combo += 1;
+            self.max_combo = self.max_combo.max(self.combo);
+        } else {
+            self.combo = 0;
+        }
+    }
+
+    #[inline]
+    pub fn total_hits(&self) -> u32 {
+        self.x300 + self.x100 + self.x50 + self.xmiss
+    }
+
+    /// 0.0 >= Return value <= 1.0
+    pub fn accuracy(&self) -> f64 {
+        let total = self.total_hits();
+
+        if total == 0 {
+            return 1.0;
+        }
+
+        let points = self.x300 * 300 + self.x100 * 100 + self.x50 * 50;
+
+        points as f64 / (total * 300) as f64
+    }
+
+    pub fn grade(&self) -> Grade {
+        let total = self.total_hits();
+
+        if total == 0 || self.x300 == total {
+            return Grade::SS;
+        }
+
+        let ratio300 = self.x300 as f64 / total as f64;
+        let ratio50 = self.x50 as f64 / total as f64;
+
+        if ratio300 > 0.9 && ratio50 < 0.01 && self.xmiss == 0 {
+            Grade::S
+        } else if (ratio300 > 0.8 && self.xmiss == 0) || ratio300 > 0.9 {
+            Grade::A
+        } else if (ratio300 > 0.7 && self.xmiss == 0) || ratio300 > 0.8 {
+            Grade::B
+        } else if ratio300 > 0.6 {
+            Grade::C
+        } else {
+            Grade::D
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::case;
+
+    use cgmath::Vector2;
+    use rosu_map::Beatmap;
+
+    use crate::hit_objects::Hit;
+    use crate::processor::judgement::{Judgement, JudgementKind};
+
+    use super::{Grade, ScoreProcessor};
+
+    fn circle(hit: Hit) -> Judgement {
+        Judgement {
+            at: 0.0,
+            pos: Vector2::new(0.0, 0.0),
+            object: 0,
+            kind: JudgementKind::Circle(hit),
+        }
+    }
+
+    #[test]
+    fn test_combo_scaling() {
+        let mut score = ScoreProcessor {
+            difficulty_multiplier: 5.0,
+            ..Default::default()
+        };
+
+        score.apply(&circle(Hit::X300));
+        score.apply(&circle(Hit::X300));
+        score.apply(&circle(Hit::X300));
+
+        // 300 + (300 + 0) + (300 + 300 * 1 * 5 / 25)
+        assert_eq!(score.score, 960);
+        assert_eq!(score.max_combo, 3);
+
+        score.apply(&circle(Hit::MISS));
+        assert_eq!(score.combo, 0);
+        assert_eq!(score.max_combo, 3);
+    }
+
+    #[test]
+    fn test_slider_break() {
+        let mut score = ScoreProcessor::default();
+
+        let slider_part = |kind| Judgement {
+            at: 0.0,
+            pos: Vector2::new(0.0, 0.0),
+            object: 0,
+            kind,
+        };
+
+        score.apply(&slider_part(JudgementKind::SliderHead(Hit::X300)));
+        score.apply(&slider_part(JudgementKind::SliderTick { hit: true }));
+        assert_eq!(score.combo, 2);
+
+        score.apply(&slider_part(JudgementKind::SliderRepeat { hit: false }));
+        assert_eq!(score.combo, 0);
+        assert_eq!(score.slider_breaks, 1);
+
+        // Missed end isn't a break
+        score.apply(&slider_part(JudgementKind::SliderEnd { hit: false }));
+        score.apply(&slider_part(JudgementKind::Slider(Hit::X100)));
+        assert_eq!(score.slider_breaks, 1);
+        assert_eq!(score.max_combo, 2);
+    }
+
+    #[test]
+    fn test_difficulty_multiplier_without_objects() {
+        let map = Beatmap {
+            hp_drain_rate: 5.0,
+            circle_size: 4.0,
+            overall_difficulty: 8.0,
+            ..Default::default()
+        };
+
+        // No drain time is treated as the highest density
+        assert_eq!(ScoreProcessor::calc_difficulty_multiplier(&map, &[]), 4.0);
+    }
+
+    #[case(&[Hit::X300, Hit::X300], Grade::SS)]
+    #[case(&[Hit::X300; 10], Grade::SS)]
+    #[case(&[Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X100], Grade::S)]
+    #[case(&[Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::X300, Hit::MISS], Grade::A)]
+    #[case(&[Hit::X300, Hit::X300, Hit::X300, Hit::X100], Grade::B)]
+    #[case(&[Hit::X300, Hit::X300, Hit::X100], Grade::C)]
+    #[case(&[Hit::X300, Hit::MISS], Grade::D)]
+    fn test_grade(hits: &[Hit], expected: Grade) {
+        let mut score = ScoreProcessor::default();
+
+        for hit in hits {
+            score.apply(&circle(*hit));
+        }
+
+        assert_eq!(score.grade(), expected);
+    }
+}
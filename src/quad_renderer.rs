@@ -249,6 +249,14 @@ impl<'qr> QuadRenderer<'qr> {
             ));
     }
 
+    pub fn resize_vertex_cropped(&self, width: f32, height: f32, crop: f32) {
+        self.graphics
+            .queue
+            .write_buffer(&self.quad_vertex_buffer, 0, bytemuck::cast_slice(
+                &Vertex::quad_cropped(width, height, crop)
+            ));
+    }
+
     pub fn create_instance_buffer(&self) -> Buffer {
         self.graphics
             .device
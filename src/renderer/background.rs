@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use image::DynamicImage;
+use wgpu::{util::DeviceExt, BufferUsages, TextureView};
+
+use crate::{graphics::Graphics, quad_instance::QuadInstance, quad_renderer::QuadRenderer, texture::Texture};
+
+/// Draws beatmap background behind the playfield,
+/// clearing the whole screen with black first
+pub struct BackgroundRenderer<'bg> {
+    graphics: Arc<Graphics<'bg>>,
+    quad_renderer: QuadRenderer<'bg>,
+    buffer: wgpu::Buffer,
+
+    texture: Option<Texture>,
+}
+
+impl<'bg> BackgroundRenderer<'bg> {
+    pub fn new(graphics: Arc<Graphics<'bg>>) -> Self {
+        let quad_renderer = QuadRenderer::new(graphics.clone(), false);
+        let buffer = quad_renderer.create_instance_buffer();
+
+        Self {
+            graphics,
+            quad_renderer,
+            buffer,
+            texture: None,
+        }
+    }
+
+    /// Beatmaps without background are shown on black
+    pub fn set_image(&mut self, image: Option<DynamicImage>) {
+        let _span = tracy_client::span!("background_renderer::set_image");
+
+        self.texture = image.map(|image| Texture::from_image(image, &self.graphics));
+        self.resize_vertex();
+    }
+
+    pub fn on_resize(&mut self, new_size: &winit::dpi::PhysicalSize<u32>) {
+        self.quad_renderer.resize_camera(new_size);
+        self.resize_vertex();
+    }
+
+    /// Covers the whole screen keeping aspect ratio of the image
+    fn resize_vertex(&self) {
+        let Some(texture) = &self.texture else {
+            return;
+        };
+
+        let (screen_width, screen_height) = self.graphics.get_surface_size();
+        let (screen_width, screen_height) = (screen_width as f32, screen_height as f32);
+
+        let image_ratio = texture.width / texture.height;
+        let screen_ratio = screen_width / screen_height;
+
+        if screen_ratio < image_ratio {
+            self.quad_renderer.resize_vertex_centered(screen_height * image_ratio, screen_height);
+        } else {
+            self.quad_renderer.resize_vertex_centered(screen_width, screen_width / image_ratio);
+        }
+    }
+
+    /// `dim` is in 0.0..=1.0, fully dimmed background is black
+    pub fn render_on_view(&mut self, view: &TextureView, dim: f32) {
+        let _span = tracy_client::span!("background_renderer::render_on_view");
+
+        self.clear(view);
+
+        let Some(texture) = &self.texture else {
+            return;
+        };
+
+        if dim >= 1.0 {
+            return;
+        }
+
+        let (screen_width, screen_height) = self.graphics.get_surface_size();
+
+        let instance = QuadInstance::from_xy_pos_alpha(
+            screen_width as f32 / 2.0,
+            screen_height as f32 / 2.0,
+            1.0 - dim.clamp(0.0, 1.0),
+        );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.buffer,
+            &[instance],
+            QuadInstance
+        );
+
+        self.quad_renderer.render_on_view_instanced(
+            view,
+            &texture.bind_group,
+            &self.buffer,
+            0..1
+        );
+    }
+
+    fn clear(&self, view: &TextureView) {
+        let mut encoder =
+            self.graphics
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("background clear encoder"),
+                });
+
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("background clear render pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        self.graphics.queue.submit([encoder.finish()]);
+    }
+}
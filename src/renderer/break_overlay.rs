@@ -0,0 +1,171 @@
+use std::sync::{Arc, RwLock};
+
+use image::{DynamicImage, Rgba, RgbaImage};
+use wgpu::{util::DeviceExt, BufferUsages, TextureView};
+
+use crate::{
+    graphics::Graphics,
+    hit_objects::break_period::{BreakPeriod, SectionResult},
+    math::calc_progress,
+    quad_instance::QuadInstance,
+    quad_renderer::QuadRenderer,
+    skin_manager::SkinManager,
+    texture::Texture,
+};
+
+/// Overlay elements are laid out for 768px high screen
+const BREAK_OVERLAY_BASE_HEIGHT: f32 = 768.0;
+/// Size of the remaining time bar at the start of the break
+const BREAK_BAR_WIDTH: f32 = 600.0;
+const BREAK_BAR_HEIGHT: f32 = 6.0;
+/// Vertical position of the bar relatively to the screen height
+const BREAK_BAR_POSITION: f32 = 0.3;
+
+/// Section pass or fail is shown that long after the middle of the break
+pub const SECTION_RESULT_TIME: f64 = 1500.0;
+
+/// Draws remaining time of the break as a bar shrinking towards
+/// the center and the section pass or fail indicator
+pub struct BreakOverlayRenderer<'bo> {
+    graphics: Arc<Graphics<'bo>>,
+    bar_renderer: QuadRenderer<'bo>,
+    result_renderer: QuadRenderer<'bo>,
+
+    skin_manager: Arc<RwLock<SkinManager>>,
+
+    /// Single white pixel stretched to the bar size
+    bar_texture: Texture,
+    bar_buffer: wgpu::Buffer,
+    result_buffer: wgpu::Buffer,
+
+    scale: f32,
+}
+
+impl<'bo> BreakOverlayRenderer<'bo> {
+    pub fn new(
+        graphics: Arc<Graphics<'bo>>,
+        skin_manager: Arc<RwLock<SkinManager>>,
+    ) -> Self {
+        let bar_renderer = QuadRenderer::new(graphics.clone(), false);
+        let result_renderer = QuadRenderer::new(graphics.clone(), false);
+
+        let bar_buffer = bar_renderer.create_instance_buffer();
+        let result_buffer = result_renderer.create_instance_buffer();
+
+        let bar_texture = Texture::from_image(
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([255, 255, 255, 255]))),
+            &graphics,
+        );
+
+        let surface_config = graphics.get_surface_config();
+
+        Self {
+            graphics,
+            bar_renderer,
+            result_renderer,
+            skin_manager,
+            bar_texture,
+            bar_buffer,
+            result_buffer,
+            scale: surface_config.height as f32 / BREAK_OVERLAY_BASE_HEIGHT,
+        }
+    }
+
+    pub fn on_resize(&mut self, new_size: &winit::dpi::PhysicalSize<u32>) {
+        self.bar_renderer.resize_camera(new_size);
+        self.result_renderer.resize_camera(new_size);
+
+        self.scale = new_size.height as f32 / BREAK_OVERLAY_BASE_HEIGHT;
+    }
+
+    pub fn render_on_view(
+        &mut self,
+        view: &TextureView,
+        time: f64,
+        period: &BreakPeriod,
+        section_result: Option<SectionResult>,
+    ) {
+        let _span = tracy_client::span!("break_overlay_renderer::render_on_view");
+
+        let (screen_width, screen_height) = self.graphics.get_surface_size();
+        let (screen_width, screen_height) = (screen_width as f32, screen_height as f32);
+
+        let alpha = period.visibility(time) as f32;
+
+        if alpha <= 0.0 {
+            return;
+        }
+
+        self.bar_renderer.resize_vertex_centered(
+            BREAK_BAR_WIDTH * self.scale * period.remaining(time) as f32,
+            BREAK_BAR_HEIGHT * self.scale,
+        );
+
+        let bar_instance = QuadInstance::from_xy_pos_alpha(
+            screen_width / 2.0,
+            screen_height * BREAK_BAR_POSITION,
+            alpha,
+        );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.bar_buffer,
+            &[bar_instance],
+            QuadInstance
+        );
+
+        self.bar_renderer.render_on_view_instanced(
+            view,
+            &self.bar_texture.bind_group,
+            &self.bar_buffer,
+            0..1
+        );
+
+        let Some(section_result) = section_result else {
+            return;
+        };
+
+        let progress = calc_progress(time, period.middle(), period.middle() + SECTION_RESULT_TIME);
+
+        if !(0.0..1.0).contains(&progress) {
+            return;
+        }
+
+        let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+
+        let texture = match section_result {
+            SectionResult::Pass => &skin.section_pass,
+            SectionResult::Fail => &skin.section_fail,
+        };
+
+        self.result_renderer.resize_vertex_centered(
+            texture.width * self.scale,
+            texture.height * self.scale,
+        );
+
+        // Stays for a while and quickly fades out at the end
+        let result_alpha = (1.0 - progress.powi(4)) as f32 * alpha;
+
+        let result_instance = QuadInstance::from_xy_pos_alpha(
+            screen_width / 2.0,
+            screen_height / 2.0,
+            result_alpha,
+        );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.result_buffer,
+            &[result_instance],
+            QuadInstance
+        );
+
+        self.result_renderer.render_on_view_instanced(
+            view,
+            &texture.bind_group,
+            &self.result_buffer,
+            0..1
+        );
+    }
+}
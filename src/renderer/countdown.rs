@@ -0,0 +1,90 @@
+use std::sync::{Arc, RwLock};
+
+use wgpu::{util::DeviceExt, BufferUsages, TextureView};
+
+use crate::{
+    countdown::Countdown,
+    graphics::Graphics,
+    quad_instance::QuadInstance,
+    quad_renderer::QuadRenderer,
+    skin_manager::SkinManager,
+};
+
+/// Sprites are laid out for 768px high screen
+const COUNTDOWN_BASE_HEIGHT: f32 = 768.0;
+
+/// Draws the current countdown step in the middle
+/// of the screen, fading it out until the next one
+pub struct CountdownRenderer<'cd> {
+    graphics: Arc<Graphics<'cd>>,
+    quad_renderer: QuadRenderer<'cd>,
+    buffer: wgpu::Buffer,
+
+    skin_manager: Arc<RwLock<SkinManager>>,
+
+    scale: f32,
+}
+
+impl<'cd> CountdownRenderer<'cd> {
+    pub fn new(
+        graphics: Arc<Graphics<'cd>>,
+        skin_manager: Arc<RwLock<SkinManager>>,
+    ) -> Self {
+        let quad_renderer = QuadRenderer::new(graphics.clone(), false);
+        let buffer = quad_renderer.create_instance_buffer();
+
+        let surface_config = graphics.get_surface_config();
+
+        Self {
+            graphics,
+            quad_renderer,
+            buffer,
+            skin_manager,
+            scale: surface_config.height as f32 / COUNTDOWN_BASE_HEIGHT,
+        }
+    }
+
+    pub fn on_resize(&mut self, new_size: &winit::dpi::PhysicalSize<u32>) {
+        self.quad_renderer.resize_camera(new_size);
+        self.scale = new_size.height as f32 / COUNTDOWN_BASE_HEIGHT;
+    }
+
+    pub fn render_on_view(&mut self, view: &TextureView, time: f64, countdown: &Countdown) {
+        let _span = tracy_client::span!("countdown_renderer::render_on_view");
+
+        let Some((step, progress)) = countdown.step_at(time) else {
+            return;
+        };
+
+        let (screen_width, screen_height) = self.graphics.get_surface_size();
+
+        let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+        let texture = skin.countdown_texture(step);
+
+        self.quad_renderer.resize_vertex_centered(
+            texture.width * self.scale,
+            texture.height * self.scale,
+        );
+
+        let instance = QuadInstance::from_xy_pos_alpha(
+            screen_width as f32 / 2.0,
+            screen_height as f32 / 2.0,
+            1.0 - progress as f32,
+        );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.buffer,
+            &[instance],
+            QuadInstance
+        );
+
+        self.quad_renderer.render_on_view_instanced(
+            view,
+            &texture.bind_group,
+            &self.buffer,
+            0..1
+        );
+    }
+}
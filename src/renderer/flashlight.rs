@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use cgmath::Vector2;
+use wgpu::{util::DeviceExt, BufferUsages, TextureView};
+
+use crate::{graphics::Graphics, math::calc_playfield};
+
+/// Radius of the visible area in osu!pixels, before combo scaling
+const FLASHLIGHT_RADIUS: f32 = 180.0;
+/// How much of the visible area is dimmed during slider holds
+const FLASHLIGHT_SLIDER_DIM: f32 = 0.8;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct FlashlightUniform {
+    pos: [f32; 2],
+    radius: f32,
+    dim: f32,
+}
+
+/// Visible area gets smaller on 100 and 200 combo
+pub fn combo_scale(combo: u32) -> f32 {
+    if combo >= 200 {
+        0.625
+    } else if combo >= 100 {
+        0.8125
+    } else {
+        1.0
+    }
+}
+
+/// Covers everything except an area around the cursor,
+/// used by flashlight mod
+pub struct FlashlightRenderer<'fl> {
+    graphics: Arc<Graphics<'fl>>,
+
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+
+    scale: f32,
+    offsets: Vector2<f32>,
+
+    uniform: FlashlightUniform,
+}
+
+impl<'fl> FlashlightRenderer<'fl> {
+    pub fn new(graphics: Arc<Graphics<'fl>>) -> Self {
+        let shader = graphics
+            .device
+            .create_shader_module(wgpu::include_wgsl!("../shaders/flashlight.wgsl"));
+
+        let surface_config = graphics.get_surface_config();
+
+        let (scale, offsets) = calc_playfield(
+            surface_config.width as f32,
+            surface_config.height as f32,
+        );
+
+        let uniform = FlashlightUniform {
+            pos: [0.0, 0.0],
+            radius: FLASHLIGHT_RADIUS * scale,
+            dim: 0.0,
+        };
+
+        let uniform_buffer = graphics
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("flashlight uniform buffer"),
+                contents: bytemuck::bytes_of(&uniform),
+                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            });
+
+        let bind_group_layout = graphics
+            .device
+            .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+                label: Some("flashlight bind group layout"),
+            });
+
+        let bind_group = graphics
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                }],
+                label: Some("flashlight bind group"),
+            });
+
+        let pipeline_layout = graphics
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("flashlight pipeline layout"),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let pipeline = graphics
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("flashlight render pipeline"),
+                cache: None,
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    compilation_options: Default::default(),
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: surface_config.format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            });
+
+        Self {
+            graphics,
+            pipeline,
+            uniform_buffer,
+            bind_group,
+            scale,
+            offsets,
+            uniform,
+        }
+    }
+
+    pub fn on_resize(&mut self, new_size: &winit::dpi::PhysicalSize<u32>) {
+        let (scale, offsets) = calc_playfield(new_size.width as f32, new_size.height as f32);
+
+        self.scale = scale;
+        self.offsets = offsets;
+    }
+
+    /// `pos` is the cursor position in osu!pixels
+    pub fn update(&mut self, pos: Vector2<f64>, combo: u32, is_holding_slider: bool) {
+        let pos = Vector2::new(pos.x as f32, pos.y as f32) * self.scale + self.offsets;
+
+        self.uniform = FlashlightUniform {
+            pos: pos.into(),
+            radius: FLASHLIGHT_RADIUS * combo_scale(combo) * self.scale,
+            dim: if is_holding_slider { FLASHLIGHT_SLIDER_DIM } else { 0.0 },
+        };
+    }
+
+    pub fn render_on_view(&mut self, view: &TextureView) {
+        let _span = tracy_client::span!("flashlight_renderer::render_on_view");
+
+        self.graphics.queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&self.uniform),
+        );
+
+        let mut encoder = self.graphics
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("flashlight encoder"),
+            });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("flashlight render pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        self.graphics.queue.submit([encoder.finish()]);
+    }
+}
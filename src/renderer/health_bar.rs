@@ -0,0 +1,118 @@
+use std::sync::{Arc, RwLock};
+
+use wgpu::{util::DeviceExt, BufferUsages, TextureView};
+
+use crate::{graphics::Graphics, quad_instance::QuadInstance, quad_renderer::QuadRenderer, skin_manager::SkinManager};
+
+/// Scorebar elements are laid out for 768px high screen
+const SCOREBAR_BASE_HEIGHT: f32 = 768.0;
+/// Position of the scorebar colour relatively to the background
+const SCOREBAR_COLOUR_OFFSET: [f32; 2] = [5.0, 16.0];
+
+/// Draws HP as a scorebar in the top left corner of the screen
+pub struct HealthBarRenderer<'hb> {
+    graphics: Arc<Graphics<'hb>>,
+    bg_renderer: QuadRenderer<'hb>,
+    colour_renderer: QuadRenderer<'hb>,
+
+    skin_manager: Arc<RwLock<SkinManager>>,
+
+    bg_buffer: wgpu::Buffer,
+    colour_buffer: wgpu::Buffer,
+
+    scale: f32,
+    health: f32,
+}
+
+impl<'hb> HealthBarRenderer<'hb> {
+    pub fn new(
+        graphics: Arc<Graphics<'hb>>,
+        skin_manager: Arc<RwLock<SkinManager>>,
+    ) -> Self {
+        let bg_renderer = QuadRenderer::new(graphics.clone(), false);
+        let colour_renderer = QuadRenderer::new(graphics.clone(), false);
+
+        let bg_buffer = bg_renderer.create_instance_buffer();
+        let colour_buffer = colour_renderer.create_instance_buffer();
+
+        let surface_config = graphics.get_surface_config();
+
+        Self {
+            graphics,
+            bg_renderer,
+            colour_renderer,
+            skin_manager,
+            bg_buffer,
+            colour_buffer,
+            scale: surface_config.height as f32 / SCOREBAR_BASE_HEIGHT,
+            health: 1.0,
+        }
+    }
+
+    /// 0.0 >= health <= 1.0
+    pub fn set_health(&mut self, health: f64) {
+        self.health = health.clamp(0.0, 1.0) as f32;
+    }
+
+    pub fn on_resize(&mut self, new_size: &winit::dpi::PhysicalSize<u32>) {
+        self.bg_renderer.resize_camera(new_size);
+        self.colour_renderer.resize_camera(new_size);
+
+        self.scale = new_size.height as f32 / SCOREBAR_BASE_HEIGHT;
+    }
+
+    pub fn render_on_view(&mut self, view: &TextureView) {
+        let _span = tracy_client::span!("health_bar_renderer::render_on_view");
+        let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+
+        // Skin might be changed at any moment, so
+        // vertices are resized on every render
+        self.bg_renderer.resize_vertex_cropped(
+            skin.scorebar_bg.width * self.scale,
+            skin.scorebar_bg.height * self.scale,
+            1.0,
+        );
+
+        self.colour_renderer.resize_vertex_cropped(
+            skin.scorebar_colour.width * self.scale,
+            skin.scorebar_colour.height * self.scale,
+            self.health,
+        );
+
+        let bg_instance = QuadInstance::from_xy_pos(0.0, 0.0);
+        let colour_instance = QuadInstance::from_xy_pos(
+            SCOREBAR_COLOUR_OFFSET[0] * self.scale,
+            SCOREBAR_COLOUR_OFFSET[1] * self.scale,
+        );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.bg_buffer,
+            &[bg_instance],
+            QuadInstance
+        );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.colour_buffer,
+            &[colour_instance],
+            QuadInstance
+        );
+
+        self.bg_renderer.render_on_view_instanced(
+            view,
+            &skin.scorebar_bg.bind_group,
+            &self.bg_buffer,
+            0..1
+        );
+
+        self.colour_renderer.render_on_view_instanced(
+            view,
+            &skin.scorebar_colour.bind_group,
+            &self.colour_buffer,
+            0..1
+        );
+    }
+}
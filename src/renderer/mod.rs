@@ -1 +1,8 @@
 pub mod cursor;
+pub mod health_bar;
+pub mod flashlight;
+pub mod background;
+pub mod break_overlay;
+pub mod countdown;
+pub mod skip;
+pub mod pause;
@@ -0,0 +1,241 @@
+use std::sync::{Arc, RwLock};
+
+use winit::dpi::PhysicalPosition;
+use wgpu::{util::DeviceExt, BufferUsages, TextureView};
+
+use crate::{
+    graphics::Graphics,
+    quad_instance::QuadInstance,
+    quad_renderer::QuadRenderer,
+    skin_manager::SkinManager,
+    texture::Texture,
+};
+
+/// Menu is laid out for 768px high screen
+const PAUSE_BASE_HEIGHT: f32 = 768.0;
+/// Vertical distance between centers of the buttons
+const PAUSE_BUTTON_SPACING: f32 = 110.0;
+/// Resume marker grows and shrinks by that much
+const RESUME_PULSE_SCALE: f32 = 0.15;
+/// Full pulse of the resume marker, ms
+const RESUME_PULSE_TIME: f64 = 800.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PauseButton {
+    Continue,
+    Retry,
+    Back,
+}
+
+impl PauseButton {
+    /// In order from the top of the screen
+    pub const ALL: [PauseButton; 3] = [
+        PauseButton::Continue,
+        PauseButton::Retry,
+        PauseButton::Back,
+    ];
+
+    fn texture(self, skin: &SkinManager) -> &Texture {
+        match self {
+            PauseButton::Continue => &skin.pause_continue,
+            PauseButton::Retry => &skin.pause_retry,
+            PauseButton::Back => &skin.pause_back,
+        }
+    }
+}
+
+/// Draws pause menu on top of the frozen gameplay and
+/// the marker that has to be clicked to resume it
+pub struct PauseRenderer<'pr> {
+    graphics: Arc<Graphics<'pr>>,
+    overlay_renderer: QuadRenderer<'pr>,
+    button_renderer: QuadRenderer<'pr>,
+    marker_renderer: QuadRenderer<'pr>,
+
+    skin_manager: Arc<RwLock<SkinManager>>,
+
+    overlay_buffer: wgpu::Buffer,
+    button_buffer: wgpu::Buffer,
+    marker_buffer: wgpu::Buffer,
+
+    scale: f32,
+}
+
+impl<'pr> PauseRenderer<'pr> {
+    pub fn new(
+        graphics: Arc<Graphics<'pr>>,
+        skin_manager: Arc<RwLock<SkinManager>>,
+    ) -> Self {
+        let overlay_renderer = QuadRenderer::new(graphics.clone(), false);
+        let button_renderer = QuadRenderer::new(graphics.clone(), false);
+        let marker_renderer = QuadRenderer::new(graphics.clone(), false);
+
+        let overlay_buffer = overlay_renderer.create_instance_buffer();
+        let button_buffer = button_renderer.create_instance_buffer();
+        let marker_buffer = marker_renderer.create_instance_buffer();
+
+        let surface_config = graphics.get_surface_config();
+
+        Self {
+            graphics,
+            overlay_renderer,
+            button_renderer,
+            marker_renderer,
+            skin_manager,
+            overlay_buffer,
+            button_buffer,
+            marker_buffer,
+            scale: surface_config.height as f32 / PAUSE_BASE_HEIGHT,
+        }
+    }
+
+    pub fn on_resize(&mut self, new_size: &winit::dpi::PhysicalSize<u32>) {
+        self.overlay_renderer.resize_camera(new_size);
+        self.button_renderer.resize_camera(new_size);
+        self.marker_renderer.resize_camera(new_size);
+
+        self.scale = new_size.height as f32 / PAUSE_BASE_HEIGHT;
+    }
+
+    /// Center of the button on the screen
+    fn button_pos(&self, index: usize) -> (f32, f32) {
+        let (screen_width, screen_height) = self.graphics.get_surface_size();
+
+        let offset = (index as f32 - 1.0) * PAUSE_BUTTON_SPACING * self.scale;
+
+        (screen_width as f32 / 2.0, screen_height as f32 / 2.0 + offset)
+    }
+
+    /// Button under the cursor `pos`
+    pub fn button_at(&self, pos: PhysicalPosition<f64>) -> Option<PauseButton> {
+        let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+
+        PauseButton::ALL.into_iter()
+            .enumerate()
+            .find(|(i, button)| {
+                let texture = button.texture(&skin);
+                let (x, y) = self.button_pos(*i);
+
+                (pos.x as f32 - x).abs() <= texture.width * self.scale / 2.0
+                    && (pos.y as f32 - y).abs() <= texture.height * self.scale / 2.0
+            })
+            .map(|(_, button)| button)
+    }
+
+    /// Cursor `pos` is over the resume marker placed at `marker`
+    pub fn is_over_marker(&self, pos: PhysicalPosition<f64>, marker: PhysicalPosition<f64>) -> bool {
+        let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+        let radius = (skin.resume_marker.width * self.scale / 2.0) as f64;
+
+        (pos.x - marker.x).hypot(pos.y - marker.y) <= radius
+    }
+
+    /// Darkens the whole screen, `alpha` is in 0.0..=1.0
+    pub fn render_overlay_on_view(&mut self, view: &TextureView, alpha: f32) {
+        let _span = tracy_client::span!("pause_renderer::render_overlay_on_view");
+
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let (screen_width, screen_height) = self.graphics.get_surface_size();
+        let (screen_width, screen_height) = (screen_width as f32, screen_height as f32);
+
+        self.overlay_renderer.resize_vertex_centered(screen_width, screen_height);
+
+        let instance = QuadInstance::from_xy_pos_alpha(
+            screen_width / 2.0,
+            screen_height / 2.0,
+            alpha,
+        );
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.overlay_buffer,
+            &[instance],
+            QuadInstance
+        );
+
+        let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+
+        self.overlay_renderer.render_on_view_instanced(
+            view,
+            &skin.pause_overlay.bind_group,
+            &self.overlay_buffer,
+            0..1
+        );
+    }
+
+    pub fn render_on_view(&mut self, view: &TextureView) {
+        let _span = tracy_client::span!("pause_renderer::render_on_view");
+
+        self.render_overlay_on_view(view, 1.0);
+
+        for (i, button) in PauseButton::ALL.into_iter().enumerate() {
+            let (x, y) = self.button_pos(i);
+
+            let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+            let texture = button.texture(&skin);
+
+            self.button_renderer.resize_vertex_centered(
+                texture.width * self.scale,
+                texture.height * self.scale,
+            );
+
+            let instance = QuadInstance::from_xy_pos_alpha(x, y, 1.0);
+
+            buffer_write_or_init!(
+                self.graphics.queue,
+                self.graphics.device,
+                self.button_buffer,
+                &[instance],
+                QuadInstance
+            );
+
+            self.button_renderer.render_on_view_instanced(
+                view,
+                &texture.bind_group,
+                &self.button_buffer,
+                0..1
+            );
+        }
+    }
+
+    /// `elapsed` is time since the marker was shown in ms
+    pub fn render_marker_on_view(
+        &mut self,
+        view: &TextureView,
+        pos: PhysicalPosition<f64>,
+        elapsed: f64,
+    ) {
+        let _span = tracy_client::span!("pause_renderer::render_marker_on_view");
+
+        let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+
+        let pulse = (elapsed / RESUME_PULSE_TIME * std::f64::consts::TAU).sin() as f32;
+        let scale = self.scale * (1.0 + pulse * RESUME_PULSE_SCALE);
+
+        self.marker_renderer.resize_vertex_centered(
+            skin.resume_marker.width * scale,
+            skin.resume_marker.height * scale,
+        );
+
+        let instance = QuadInstance::from_xy_pos_alpha(pos.x as f32, pos.y as f32, 1.0);
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.marker_buffer,
+            &[instance],
+            QuadInstance
+        );
+
+        self.marker_renderer.render_on_view_instanced(
+            view,
+            &skin.resume_marker.bind_group,
+            &self.marker_buffer,
+            0..1
+        );
+    }
+}
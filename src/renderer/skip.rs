@@ -0,0 +1,110 @@
+use std::sync::{Arc, RwLock};
+
+use winit::dpi::PhysicalPosition;
+use wgpu::{util::DeviceExt, BufferUsages, TextureView};
+
+use crate::{
+    graphics::Graphics,
+    quad_instance::QuadInstance,
+    quad_renderer::QuadRenderer,
+    skin_manager::SkinManager,
+};
+
+/// Button is laid out for 768px high screen
+const SKIP_BASE_HEIGHT: f32 = 768.0;
+/// Distance from the bottom right corner of the screen
+const SKIP_MARGIN: f32 = 16.0;
+
+/// Draws skip button at the bottom right corner
+/// of the screen, clicking it skips as well
+pub struct SkipRenderer<'sk> {
+    graphics: Arc<Graphics<'sk>>,
+    quad_renderer: QuadRenderer<'sk>,
+    buffer: wgpu::Buffer,
+
+    skin_manager: Arc<RwLock<SkinManager>>,
+
+    scale: f32,
+}
+
+impl<'sk> SkipRenderer<'sk> {
+    pub fn new(
+        graphics: Arc<Graphics<'sk>>,
+        skin_manager: Arc<RwLock<SkinManager>>,
+    ) -> Self {
+        let quad_renderer = QuadRenderer::new(graphics.clone(), false);
+        let buffer = quad_renderer.create_instance_buffer();
+
+        let surface_config = graphics.get_surface_config();
+
+        Self {
+            graphics,
+            quad_renderer,
+            buffer,
+            skin_manager,
+            scale: surface_config.height as f32 / SKIP_BASE_HEIGHT,
+        }
+    }
+
+    pub fn on_resize(&mut self, new_size: &winit::dpi::PhysicalSize<u32>) {
+        self.quad_renderer.resize_camera(new_size);
+        self.scale = new_size.height as f32 / SKIP_BASE_HEIGHT;
+    }
+
+    /// Center and size of the button on the screen
+    fn bounds(&self) -> (f32, f32, f32, f32) {
+        let (screen_width, screen_height) = self.graphics.get_surface_size();
+
+        let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+
+        let width = skin.play_skip.width * self.scale;
+        let height = skin.play_skip.height * self.scale;
+        let margin = SKIP_MARGIN * self.scale;
+
+        (
+            screen_width as f32 - margin - width / 2.0,
+            screen_height as f32 - margin - height / 2.0,
+            width,
+            height,
+        )
+    }
+
+    /// Cursor `pos` is over the button
+    pub fn contains(&self, pos: PhysicalPosition<f64>) -> bool {
+        let (x, y, width, height) = self.bounds();
+
+        (pos.x as f32 - x).abs() <= width / 2.0
+            && (pos.y as f32 - y).abs() <= height / 2.0
+    }
+
+    pub fn render_on_view(&mut self, view: &TextureView, alpha: f32) {
+        let _span = tracy_client::span!("skip_renderer::render_on_view");
+
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let (x, y, width, height) = self.bounds();
+
+        self.quad_renderer.resize_vertex_centered(width, height);
+
+        let instance = QuadInstance::from_xy_pos_alpha(x, y, alpha);
+
+        buffer_write_or_init!(
+            self.graphics.queue,
+            self.graphics.device,
+            self.buffer,
+            &[instance],
+            QuadInstance
+        );
+
+        let skin = self.skin_manager.read().expect("failed to acquire skin lock");
+
+        self.quad_renderer.render_on_view_instanced(
+            view,
+            &skin.play_skip.bind_group,
+            &self.buffer,
+            0..1
+        );
+    }
+}
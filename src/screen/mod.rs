@@ -1 +1,4 @@
 pub mod settings;
+pub mod mod_select;
+pub mod results;
+pub mod practice;
@@ -0,0 +1,255 @@
+use std::sync::mpsc::Sender;
+
+use egui::{Slider, Ui};
+
+use crate::{
+    hit_objects::ruleset::Ruleset,
+    mods::{DifficultyAdjust, Mods},
+    osu_state::OsuStateEvent,
+    transforms::{MirrorAxis, ScaleIn, TransformSettings},
+};
+
+/// Only mods that are implemented by the gameplay
+const MOD_GROUPS: [(&str, &[(Mods, &str)]); 3] = [
+    ("Difficulty reduction", &[
+        (Mods::EASY, "Easy"),
+        (Mods::HALF_TIME, "Half Time"),
+    ]),
+    ("Difficulty increase", &[
+        (Mods::HARD_ROCK, "Hard Rock"),
+        (Mods::DOUBLE_TIME, "Double Time"),
+        (Mods::NIGHTCORE, "Nightcore"),
+        (Mods::HIDDEN, "Hidden"),
+        (Mods::FLASHLIGHT, "Flashlight"),
+    ]),
+    ("Special", &[
+        (Mods::RELAX, "Relax"),
+        (Mods::AUTOPILOT, "Autopilot"),
+        (Mods::AUTOPLAY, "Autoplay"),
+        (Mods::MIRROR, "Mirror"),
+    ]),
+];
+
+/// Value difficulty adjust slider starts at once it's enabled
+const DEFAULT_ADJUST_VALUE: f32 = 5.0;
+
+/// Window of the song select where mods of the next play are picked.
+/// Every change is sent to the [`crate::osu_state::OsuState`] right away
+pub struct ModSelectScreen {
+    is_open: bool,
+
+    mods: Mods,
+    difficulty_adjust: DifficultyAdjust,
+    transforms: TransformSettings,
+    ruleset: Ruleset,
+
+    osu_state_tx: Sender<OsuStateEvent>,
+}
+
+impl ModSelectScreen {
+    pub fn new(osu_state_tx: Sender<OsuStateEvent>) -> Self {
+        Self {
+            is_open: false,
+            mods: Mods::empty(),
+            difficulty_adjust: DifficultyAdjust::default(),
+            transforms: TransformSettings::default(),
+            ruleset: Ruleset::default(),
+            osu_state_tx,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) {
+        let _span = tracy_client::span!("mod_select::render");
+
+        if !self.is_open {
+            return;
+        }
+
+        let mut is_open = self.is_open;
+        let mut changed = false;
+
+        egui::Window::new("Mods")
+            .open(&mut is_open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                changed |= self.show_mods_ui(ui);
+
+                ui.separator();
+
+                changed |= self.show_difficulty_adjust_ui(ui);
+
+                ui.separator();
+
+                changed |= self.show_transforms_ui(ui);
+
+                ui.separator();
+
+                self.show_ruleset_ui(ui);
+
+                ui.separator();
+
+                ui.label(format!("Score multiplier: {:.2}x", self.mods.score_multiplier()));
+
+                if ui.button("Reset").clicked() {
+                    self.mods = Mods::empty();
+                    self.difficulty_adjust = DifficultyAdjust::default();
+                    self.transforms = TransformSettings::default();
+                    changed = true;
+                }
+            });
+
+        self.is_open = is_open;
+
+        if changed {
+            let _ = self.osu_state_tx.send(OsuStateEvent::SetMods(
+                self.mods,
+                self.difficulty_adjust,
+                self.transforms,
+            ));
+        }
+    }
+
+    /// Returns `true` if any mod was toggled
+    fn show_mods_ui(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+
+        for (group, mods) in MOD_GROUPS {
+            ui.strong(group);
+
+            ui.horizontal_wrapped(|ui| {
+                for (m, name) in mods {
+                    if ui.selectable_label(self.mods.contains(*m), *name).clicked() {
+                        self.mods = self.mods.toggled(*m);
+                        changed = true;
+
+                        if !self.mods.contains(Mods::MIRROR) {
+                            self.transforms.mirror = None;
+                        }
+                    }
+                }
+            });
+        }
+
+        changed
+    }
+
+    /// Returns `true` if any value was changed
+    fn show_difficulty_adjust_ui(&mut self, ui: &mut Ui) -> bool {
+        let adjust = &mut self.difficulty_adjust;
+
+        ui.strong("Difficulty adjust");
+
+        adjust_value_ui(ui, &mut adjust.circle_size, "Circle size")
+            | adjust_value_ui(ui, &mut adjust.approach_rate, "Approach rate")
+            | adjust_value_ui(ui, &mut adjust.overall_difficulty, "Overall difficulty")
+            | adjust_value_ui(ui, &mut adjust.hp_drain_rate, "HP drain rate")
+    }
+
+    /// Returns `true` if any transform was changed
+    fn show_transforms_ui(&mut self, ui: &mut Ui) -> bool {
+        let transforms = &mut self.transforms;
+        let mut changed = false;
+
+        ui.strong("Transforms");
+
+        if self.mods.contains(Mods::MIRROR) {
+            let axis = transforms.mirror.unwrap_or(MirrorAxis::Horizontal);
+
+            egui::ComboBox::from_label("Mirror axis")
+                .selected_text(format!("{axis:?}"))
+                .show_ui(ui, |ui| {
+                    for option in [MirrorAxis::Horizontal, MirrorAxis::Vertical, MirrorAxis::Both] {
+                        if ui.selectable_label(axis == option, format!("{option:?}")).clicked() {
+                            transforms.mirror = Some(option);
+                            changed = true;
+                        }
+                    }
+                });
+        }
+
+        // Actual seed is picked once the play starts
+        let mut random = transforms.random_seed.is_some();
+        if ui.checkbox(&mut random, "Random").changed() {
+            transforms.random_seed = random.then_some(0);
+            changed = true;
+        }
+
+        egui::ComboBox::from_label("Scale in")
+            .selected_text(match transforms.scale_in {
+                Some(mode) => format!("{mode:?}"),
+                None => "Off".to_string(),
+            })
+            .show_ui(ui, |ui| {
+                for option in [None, Some(ScaleIn::Grow), Some(ScaleIn::Deflate)] {
+                    let text = match option {
+                        Some(mode) => format!("{mode:?}"),
+                        None => "Off".to_string(),
+                    };
+
+                    if ui.selectable_label(transforms.scale_in == option, text).clicked() {
+                        transforms.scale_in = option;
+                        changed = true;
+                    }
+                }
+            });
+
+        changed |= ui.checkbox(&mut transforms.wiggle, "Wiggle").changed();
+
+        changed
+    }
+
+    /// Ruleset isn't a mod, so it's sent on it's own
+    fn show_ruleset_ui(&mut self, ui: &mut Ui) {
+        let mut changed = false;
+
+        egui::ComboBox::from_label("Judgement rules")
+            .selected_text(format!("{:?}", self.ruleset))
+            .show_ui(ui, |ui| {
+                for option in [Ruleset::Stable, Ruleset::Lazer] {
+                    let text = format!("{option:?}");
+
+                    if ui.selectable_label(self.ruleset == option, text).clicked() {
+                        self.ruleset = option;
+                        changed = true;
+                    }
+                }
+            });
+
+        if changed {
+            let _ = self.osu_state_tx.send(OsuStateEvent::SetRuleset(self.ruleset));
+        }
+    }
+}
+
+/// Value is overridden only while it's checkbox is checked
+fn adjust_value_ui(ui: &mut Ui, value: &mut Option<f32>, name: &str) -> bool {
+    let mut enabled = value.is_some();
+    let mut current = value.unwrap_or(DEFAULT_ADJUST_VALUE);
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        changed |= ui.checkbox(&mut enabled, name).changed();
+        changed |= ui.add_enabled(
+            enabled,
+            Slider::new(&mut current, 0.0..=10.0).step_by(0.1),
+        ).changed();
+    });
+
+    *value = enabled.then_some(current);
+
+    changed
+}
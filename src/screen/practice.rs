@@ -0,0 +1,229 @@
+use std::sync::mpsc::Sender;
+
+use egui::{Color32, Pos2, Rect, Sense, Slider, Stroke, Ui, Vec2};
+
+use crate::{
+    osu_state::OsuStateEvent,
+    practice::{Practice, PracticeSection, MAX_PRACTICE_RATE, MIN_PRACTICE_RATE},
+};
+
+const PANEL_WIDTH: f32 = 360.0;
+const TIMELINE_HEIGHT: f32 = 24.0;
+const LOOPS_HEIGHT: f32 = 120.0;
+
+/// Length of the section picked when the panel is opened, ms
+const DEFAULT_SECTION_LENGTH: f64 = 10000.0;
+
+const SECTION_COLOR: Color32 = Color32::from_rgba_premultiplied(60, 90, 140, 200);
+const MISS_COLOR: Color32 = Color32::from_rgb(237, 17, 33);
+
+/// Window shown on top of the gameplay. Picks the section
+/// to practice on a timeline and shows accuracy of every loop
+pub struct PracticePanel {
+    is_open: bool,
+
+    /// A and B points of the section, `end` is used only if it's looped
+    start: f64,
+    end: f64,
+    looped: bool,
+    rate: f64,
+
+    osu_state_tx: Sender<OsuStateEvent>,
+}
+
+impl PracticePanel {
+    pub fn new(osu_state_tx: Sender<OsuStateEvent>) -> Self {
+        Self {
+            is_open: false,
+            start: 0.0,
+            end: DEFAULT_SECTION_LENGTH,
+            looped: true,
+            rate: 1.0,
+            osu_state_tx,
+        }
+    }
+
+    /// Section starts at the current `time` if nothing is practiced yet
+    pub fn toggle(&mut self, time: f64, practice: Option<&Practice>) {
+        self.is_open = !self.is_open;
+
+        if self.is_open && practice.is_none() {
+            self.start = time.max(0.0);
+            self.end = self.start + DEFAULT_SECTION_LENGTH;
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// `length` is the end of the last object
+    pub fn render(
+        &mut self,
+        input: egui::RawInput,
+        ctx: &egui::Context,
+        time: f64,
+        length: f64,
+        practice: Option<&Practice>,
+    ) -> egui::FullOutput {
+        let _span = tracy_client::span!("practice_panel::render");
+
+        ctx.begin_pass(input);
+
+        egui::Window::new("Practice")
+            .resizable(false)
+            .collapsible(false)
+            .default_width(PANEL_WIDTH)
+            .show(ctx, |ui| {
+                self.render_section(ui, time, length.max(1.0));
+
+                ui.add_space(8.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Start").clicked() {
+                        let section = PracticeSection::new(
+                            self.start,
+                            self.looped.then_some(self.end),
+                        );
+
+                        let _ = self.osu_state_tx.send(OsuStateEvent::StartPractice(section, self.rate));
+                    }
+
+                    if ui.add_enabled(practice.is_some(), egui::Button::new("Stop")).clicked() {
+                        let _ = self.osu_state_tx.send(OsuStateEvent::StopPractice);
+                    }
+                });
+
+                if let Some(practice) = practice {
+                    ui.add_space(8.0);
+                    Self::render_loops(ui, practice);
+                }
+            });
+
+        ctx.end_pass()
+    }
+
+    fn render_section(&mut self, ui: &mut Ui, time: f64, length: f64) {
+        ui.label(format!("Time: {}", format_time(time)));
+
+        self.render_timeline(ui, time, length);
+        ui.label("Left click sets A, right click sets B");
+
+        ui.add(
+            Slider::new(&mut self.start, 0.0..=length)
+                .text("A")
+                .step_by(1.0)
+                .custom_formatter(|x, _| format_time(x)),
+        );
+
+        ui.checkbox(&mut self.looped, "Loop until B");
+
+        ui.add_enabled(
+            self.looped,
+            Slider::new(&mut self.end, 0.0..=length)
+                .text("B")
+                .step_by(1.0)
+                .custom_formatter(|x, _| format_time(x)),
+        );
+
+        ui.add(
+            Slider::new(&mut self.rate, MIN_PRACTICE_RATE..=MAX_PRACTICE_RATE)
+                .text("Rate")
+                .step_by(0.05)
+                .suffix("x"),
+        );
+    }
+
+    /// Whole beatmap with the section highlighted,
+    /// clicking on it moves the points of the section
+    fn render_timeline(&mut self, ui: &mut Ui, time: f64, length: f64) {
+        let (response, painter) = ui.allocate_painter(
+            Vec2::new(PANEL_WIDTH, TIMELINE_HEIGHT),
+            Sense::click(),
+        );
+        let rect = response.rect;
+
+        let x_at = |time: f64| rect.left() + rect.width() * (time / length).clamp(0.0, 1.0) as f32;
+
+        if let Some(pos) = response.interact_pointer_pos() {
+            let clicked = ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0) as f64 * length;
+
+            if response.clicked() {
+                self.start = clicked.round();
+            } else if response.secondary_clicked() {
+                self.end = clicked.round();
+                self.looped = true;
+            }
+        }
+
+        painter.rect_filled(rect, 2.0, Color32::from_gray(40));
+
+        let end = if self.looped { self.end } else { length };
+
+        painter.rect_filled(
+            Rect::from_min_max(
+                Pos2::new(x_at(self.start), rect.top()),
+                Pos2::new(x_at(end), rect.bottom()),
+            ),
+            0.0,
+            SECTION_COLOR,
+        );
+
+        let x = x_at(time);
+
+        painter.line_segment(
+            [Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())],
+            Stroke::new(2.0, Color32::WHITE),
+        );
+    }
+
+    /// Latest loop is on the top
+    fn render_loops(ui: &mut Ui, practice: &Practice) {
+        if practice.loops.is_empty() {
+            ui.label("No loops finished yet");
+            return;
+        }
+
+        let average = practice.loops.iter()
+            .map(|x| x.accuracy())
+            .sum::<f64>() / practice.loops.len() as f64;
+
+        ui.label(format!(
+            "Loops: {}   Average accuracy: {:.2}%",
+            practice.loops.len(),
+            average * 100.0,
+        ));
+
+        egui::ScrollArea::vertical()
+            .max_height(LOOPS_HEIGHT)
+            .show(ui, |ui| {
+                for (i, result) in practice.loops.iter().enumerate().rev() {
+                    let text = format!(
+                        "#{}   {:.2}%   {} miss   {} breaks",
+                        i + 1,
+                        result.accuracy() * 100.0,
+                        result.xmiss,
+                        result.slider_breaks,
+                    );
+
+                    if result.xmiss > 0 {
+                        ui.colored_label(MISS_COLOR, text);
+                    } else {
+                        ui.label(text);
+                    }
+                }
+            });
+    }
+}
+
+/// `m:ss.mmm`, negative times are shown as zero
+fn format_time(time: f64) -> String {
+    let ms = time.max(0.0).round() as u64;
+
+    format!("{}:{:02}.{:03}", ms / 60000, ms / 1000 % 60, ms % 1000)
+}
@@ -0,0 +1,197 @@
+use std::sync::mpsc::Sender;
+
+use egui::{Color32, Pos2, Rect, RichText, Sense, Stroke, Ui, Vec2};
+
+use crate::{osu_state::OsuStateEvent, processor::results::PlayResults};
+
+const PANEL_WIDTH: f32 = 480.0;
+const HISTOGRAM_HEIGHT: f32 = 96.0;
+const HISTOGRAM_BINS: usize = 41;
+const TIMELINE_HEIGHT: f32 = 24.0;
+
+const X300_COLOR: Color32 = Color32::from_rgb(102, 204, 255);
+const X100_COLOR: Color32 = Color32::from_rgb(136, 204, 68);
+const X50_COLOR: Color32 = Color32::from_rgb(255, 204, 34);
+const MISS_COLOR: Color32 = Color32::from_rgb(237, 17, 33);
+
+/// Shown once the play is finished, the beatmap can
+/// be retried or the replay of the play can be watched
+pub struct ResultsScreen {
+    results: Option<PlayResults>,
+    /// Artist, title and difficulty of the beatmap
+    title: String,
+    has_replay: bool,
+
+    osu_state_tx: Sender<OsuStateEvent>,
+}
+
+impl ResultsScreen {
+    pub fn new(osu_state_tx: Sender<OsuStateEvent>) -> Self {
+        Self {
+            results: None,
+            title: String::new(),
+            has_replay: false,
+            osu_state_tx,
+        }
+    }
+
+    pub fn set_results(&mut self, results: PlayResults, title: String, has_replay: bool) {
+        self.results = Some(results);
+        self.title = title;
+        self.has_replay = has_replay;
+    }
+
+    pub fn render(&mut self, input: egui::RawInput, ctx: &egui::Context) -> egui::FullOutput {
+        let _span = tracy_client::span!("results_screen::render");
+
+        ctx.begin_pass(input);
+
+        egui::CentralPanel::default().frame(egui::Frame::NONE).show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                egui::Frame::default()
+                    .corner_radius(5.0)
+                    .inner_margin(16.0)
+                    .fill(Color32::from_rgba_unmultiplied(0, 0, 0, 220))
+                    .show(ui, |ui| {
+                        ui.set_width(PANEL_WIDTH);
+                        self.render_results(ui);
+                    });
+            });
+        });
+
+        ctx.end_pass()
+    }
+
+    fn render_results(&mut self, ui: &mut Ui) {
+        let Some(results) = &self.results else {
+            return;
+        };
+
+        ui.heading(&self.title);
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(format!("{:?}", results.grade)).size(64.0).strong());
+
+            ui.vertical(|ui| {
+                ui.label(RichText::new(format!("{}", results.score)).size(28.0).strong());
+                ui.label(format!("Accuracy: {:.2}%", results.accuracy * 100.0));
+                ui.label(format!("Max combo: {}x", results.max_combo));
+            });
+        });
+
+        ui.add_space(8.0);
+
+        egui::Grid::new("results_counts").num_columns(4).show(ui, |ui| {
+            ui.colored_label(X300_COLOR, format!("300: {}", results.x300));
+            ui.colored_label(X100_COLOR, format!("100: {}", results.x100));
+            ui.colored_label(X50_COLOR, format!("50: {}", results.x50));
+            ui.colored_label(MISS_COLOR, format!("Miss: {}", results.xmiss));
+            ui.end_row();
+
+            ui.label(format!("Geki: {}", results.xgeki));
+            ui.label(format!("Katu: {}", results.xkatu));
+            ui.label(format!("Slider breaks: {}", results.slider_breaks));
+            ui.end_row();
+        });
+
+        ui.add_space(8.0);
+
+        ui.label(format!(
+            "Unstable rate: {:.2}   Mean error: {:+.2}ms",
+            results.unstable_rate(),
+            results.mean_error(),
+        ));
+
+        Self::render_histogram(ui, results);
+
+        ui.add_space(8.0);
+        ui.label("Misses and slider breaks");
+
+        Self::render_timeline(ui, results);
+
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            if ui.button("Retry").clicked() {
+                let _ = self.osu_state_tx.send(OsuStateEvent::Retry);
+            }
+
+            if ui.add_enabled(self.has_replay, egui::Button::new("Watch replay")).clicked() {
+                let _ = self.osu_state_tx.send(OsuStateEvent::WatchReplay);
+            }
+
+            if ui.button("Back").clicked() {
+                let _ = self.osu_state_tx.send(OsuStateEvent::ToSongSelection);
+            }
+        });
+    }
+
+    /// Hit errors from early on the left to late on the right,
+    /// colored by the window they're in
+    fn render_histogram(ui: &mut Ui, results: &PlayResults) {
+        let (response, painter) = ui.allocate_painter(
+            Vec2::new(PANEL_WIDTH, HISTOGRAM_HEIGHT),
+            Sense::hover(),
+        );
+        let rect = response.rect;
+
+        let histogram = results.histogram(HISTOGRAM_BINS);
+        let max = histogram.iter().copied().max().unwrap_or(0).max(1);
+
+        let bin_width = rect.width() / HISTOGRAM_BINS as f32;
+        let range = results.hit_window.x50;
+
+        for (i, count) in histogram.iter().enumerate() {
+            let center = ((i as f64 + 0.5) / HISTOGRAM_BINS as f64 * 2.0 - 1.0) * range;
+
+            let color = if center.abs() <= results.hit_window.x300 {
+                X300_COLOR
+            } else if center.abs() <= results.hit_window.x100 {
+                X100_COLOR
+            } else {
+                X50_COLOR
+            };
+
+            let height = rect.height() * *count as f32 / max as f32;
+            let left = rect.left() + i as f32 * bin_width;
+
+            painter.rect_filled(
+                Rect::from_min_max(
+                    Pos2::new(left + 1.0, rect.bottom() - height),
+                    Pos2::new(left + bin_width - 1.0, rect.bottom()),
+                ),
+                0.0,
+                color,
+            );
+        }
+
+        painter.line_segment(
+            [rect.center_top(), rect.center_bottom()],
+            Stroke::new(1.0, Color32::WHITE),
+        );
+    }
+
+    fn render_timeline(ui: &mut Ui, results: &PlayResults) {
+        let (response, painter) = ui.allocate_painter(
+            Vec2::new(PANEL_WIDTH, TIMELINE_HEIGHT),
+            Sense::hover(),
+        );
+        let rect = response.rect;
+
+        painter.rect_filled(rect, 2.0, Color32::from_gray(40));
+
+        let marks = results.slider_break_times.iter()
+            .map(|x| (*x, X50_COLOR))
+            .chain(results.miss_times.iter().map(|x| (*x, MISS_COLOR)));
+
+        for (time, color) in marks {
+            let x = rect.left() + rect.width() * results.timeline_progress(time) as f32;
+
+            painter.line_segment(
+                [Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())],
+                Stroke::new(2.0, color),
+            );
+        }
+    }
+}
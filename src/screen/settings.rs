@@ -1,14 +1,20 @@
 use std::{path::PathBuf, sync::{mpsc::{Receiver, Sender}, Arc, RwLock}};
 
-use egui::{color_picker::show_color, Slider, TextStyle, Ui};
+use egui::{color_picker::show_color, PointerButton, Slider, TextStyle, Ui};
+use winit::{event::MouseButton, keyboard::KeyCode};
 
-use crate::{config::Config, osu_state::OsuStateEvent, skin_manager::SkinManager};
+use crate::{config::Config, keybindings::{Action, BindingInput, KeyBinding}, osu_state::OsuStateEvent, skin_manager::SkinManager};
 
 pub struct SettingsScreen {
     config: Arc<RwLock<Config>>,
     skin_manager: Arc<RwLock<SkinManager>>,
     is_open: bool,
 
+    /// Action that is waiting for a new binding
+    rebinding: Option<Action>,
+    /// Why the last rebinding was rejected
+    rebinding_error: Option<String>,
+
     osu_state_tx: Sender<OsuStateEvent>,
 }
 
@@ -21,6 +27,8 @@ impl SettingsScreen {
 
         Self {
             is_open: false,
+            rebinding: None,
+            rebinding_error: None,
             config,
             skin_manager,
             osu_state_tx,
@@ -33,6 +41,7 @@ impl SettingsScreen {
     
     pub fn close(&mut self) {
         self.is_open = false;
+        self.rebinding = None;
     }
 
     #[inline]
@@ -76,6 +85,7 @@ impl SettingsScreen {
                     .auto_shrink([false, false])
                     .show(ui, |ui| {
                         self.show_settings_ui(ui);
+                        self.show_keybindings_ui(ui);
                         self.show_skin_settings_ui(ui);
                     });
             });
@@ -186,6 +196,110 @@ impl SettingsScreen {
 
     }
 
+    /// Takes the pressed key as a new binding if waiting for it.
+    ///
+    /// Returns `true` if key was taken
+    pub fn on_pressed_down(&mut self, key_code: KeyCode, is_cntrl_pressed: bool) -> bool {
+        if self.rebinding.is_none() {
+            return false;
+        }
+
+        // Control is only a modifier, waiting for the actual key
+        if matches!(key_code, KeyCode::ControlLeft | KeyCode::ControlRight) {
+            return true;
+        }
+
+        self.rebind(KeyBinding {
+            input: BindingInput::Key(key_code),
+            ctrl: is_cntrl_pressed,
+        });
+
+        true
+    }
+
+    fn rebind(&mut self, binding: KeyBinding) {
+        let Some(action) = self.rebinding else {
+            return;
+        };
+
+        let mut config = self.config.write().expect("failed to acquire write lock");
+
+        match config.keybindings.set(action, binding) {
+            Ok(()) => {
+                self.rebinding = None;
+                self.rebinding_error = None;
+            },
+            Err(e) => self.rebinding_error = Some(e.to_string()),
+        }
+    }
+
+    pub fn show_keybindings_ui(&mut self, ui: &mut Ui) {
+        let heading_font = egui::FontId::new(20.0, egui::FontFamily::Proportional);
+
+        let bindings = self.config.read()
+            .expect("failed to acquire read lock")
+            .keybindings
+            .clone();
+
+        let mut new_binding = None;
+
+        ui.collapsing(egui::RichText::new("Key bindings").font(heading_font), |ui| {
+            // Config is not saved anywhere yet
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                "Bindings are kept only until the game is closed",
+            );
+
+            egui::Grid::new("keybindings_grid").num_columns(2).show(ui, |ui| {
+                for action in Action::ALL {
+                    ui.label(action.name());
+
+                    if self.rebinding == Some(action) {
+                        let text = if action.allows_mouse() {
+                            "Press a key or click here..."
+                        } else {
+                            "Press a key..."
+                        };
+
+                        let response = ui.button(text);
+
+                        if action.allows_mouse() && response.hovered() {
+                            new_binding = ui.input(|i| {
+                                [
+                                    PointerButton::Primary,
+                                    PointerButton::Secondary,
+                                    PointerButton::Middle,
+                                    PointerButton::Extra1,
+                                    PointerButton::Extra2,
+                                ].into_iter()
+                                    .find(|x| i.pointer.button_pressed(*x))
+                                    .map(|x| KeyBinding::mouse(to_mouse_button(x)))
+                            });
+                        }
+                    } else if ui.button(bindings.get(action).to_string()).clicked() {
+                        self.rebinding = Some(action);
+                        self.rebinding_error = None;
+                    }
+
+                    ui.end_row();
+                }
+            });
+
+            if self.rebinding.is_some() && ui.button("Cancel").clicked() {
+                self.rebinding = None;
+                self.rebinding_error = None;
+            }
+
+            if let Some(error) = &self.rebinding_error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+
+        if let Some(binding) = new_binding {
+            self.rebind(binding);
+        }
+    }
+
     fn spawn_skin_selector_dialog(&self) {
         let tx = self.osu_state_tx.clone();
 
@@ -199,3 +313,13 @@ impl SettingsScreen {
         });
     }
 }
+
+fn to_mouse_button(button: PointerButton) -> MouseButton {
+    match button {
+        PointerButton::Primary => MouseButton::Left,
+        PointerButton::Secondary => MouseButton::Right,
+        PointerButton::Middle => MouseButton::Middle,
+        PointerButton::Extra1 => MouseButton::Back,
+        PointerButton::Extra2 => MouseButton::Forward,
+    }
+}
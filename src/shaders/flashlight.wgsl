@@ -0,0 +1,38 @@
+// Vertex shader
+struct FlashlightUniform {
+	// In screen pixels
+	pos: vec2<f32>,
+	radius: f32,
+	dim: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> flashlight: FlashlightUniform;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+// Single triangle covering the whole screen
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    var out: VertexOutput;
+
+	let x = f32((index << 1u) & 2u);
+	let y = f32(index & 2u);
+
+    out.clip_position = vec4<f32>(x * 2.0 - 1.0, y * 2.0 - 1.0, 0.0, 1.0);
+
+    return out;
+}
+
+// Fragment shader
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+	let distance = distance(in.clip_position.xy, flashlight.pos);
+
+	// Soft edge on the last 20% of the radius
+	let alpha = smoothstep(flashlight.radius * 0.8, flashlight.radius, distance);
+
+	return vec4<f32>(0.0, 0.0, 0.0, max(alpha, flashlight.dim));
+}
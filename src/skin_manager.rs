@@ -1,5 +1,5 @@
-use std::path::Path;
-use crate::{graphics::Graphics, skin_ini::SkinIni, texture::{AtlasTexture, Texture}};
+use std::{path::Path, sync::Arc};
+use crate::{countdown::CountdownStep, graphics::Graphics, skin_ini::SkinIni, texture::{AtlasTexture, Texture}};
 use image::load_from_memory;
 
 macro_rules! load_or_fallback_image {
@@ -40,6 +40,20 @@ macro_rules! load_or_fallback_texture {
     }}
 }
 
+/// Sounds are optional, default skin doesn't have any.
+/// Tries `.wav` first and then `.ogg`, same as stable
+fn load_sound(path: impl AsRef<Path>, name: &str) -> Option<Arc<[u8]>> {
+    ["wav", "ogg"].iter()
+        .map(|ext| path.as_ref().join(format!("{name}.{ext}")))
+        .find(|x| x.is_file())
+        .and_then(|x| {
+            std::fs::read(&x)
+                .inspect_err(|e| tracing::error!("Failed to load sound {}: {e}", x.display()))
+                .ok()
+        })
+        .map(Arc::from)
+}
+
 /// Handles loading a skin & skin settings from an osu skin
 /// If texture requested image is not found will fallback to the 
 /// default skin
@@ -53,6 +67,30 @@ pub struct SkinManager {
     pub judgments_atlas: AtlasTexture,
     pub slider_tick: Texture,
     pub slider_reverse_arrow: Texture,
+    pub spinner_circle: Texture,
+    pub spinner_approach_circle: Texture,
+    pub spinner_rpm: Texture,
+    pub scorebar_bg: Texture,
+    pub scorebar_colour: Texture,
+    pub section_pass: Texture,
+    pub section_fail: Texture,
+    pub count3: Texture,
+    pub count2: Texture,
+    pub count1: Texture,
+    pub go: Texture,
+    pub play_skip: Texture,
+    pub pause_overlay: Texture,
+    pub pause_continue: Texture,
+    pub pause_retry: Texture,
+    pub pause_back: Texture,
+    /// Where the cursor has to be clicked to resume after the pause
+    pub resume_marker: Texture,
+
+    /// Undecoded sound files, shared by every playback
+    pub count3s: Option<Arc<[u8]>>,
+    pub count2s: Option<Arc<[u8]>>,
+    pub count1s: Option<Arc<[u8]>>,
+    pub gos: Option<Arc<[u8]>>,
 }
 
 impl SkinManager {
@@ -115,6 +153,33 @@ impl SkinManager {
         let slider_tick = load_or_fallback_texture!(path, "sliderscorepoint.png", "sliderscorepoint.png", graphics);
         let slider_reverse_arrow = load_or_fallback_texture!(path, "reversearrow.png", graphics);
 
+        let spinner_circle = load_or_fallback_texture!(path, "spinner-circle.png", graphics);
+        let spinner_approach_circle = load_or_fallback_texture!(path, "spinner-approachcircle.png", graphics);
+        let spinner_rpm = load_or_fallback_texture!(path, "spinner-rpm.png", graphics);
+
+        let scorebar_bg = load_or_fallback_texture!(path, "scorebar-bg.png", graphics);
+        let scorebar_colour = load_or_fallback_texture!(path, "scorebar-colour.png", graphics);
+
+        let section_pass = load_or_fallback_texture!(path, "section-pass.png", graphics);
+        let section_fail = load_or_fallback_texture!(path, "section-fail.png", graphics);
+
+        let count3 = load_or_fallback_texture!(path, "count3.png", graphics);
+        let count2 = load_or_fallback_texture!(path, "count2.png", graphics);
+        let count1 = load_or_fallback_texture!(path, "count1.png", graphics);
+        let go = load_or_fallback_texture!(path, "go.png", graphics);
+        let play_skip = load_or_fallback_texture!(path, "play-skip.png", graphics);
+
+        let pause_overlay = load_or_fallback_texture!(path, "pause-overlay.png", graphics);
+        let pause_continue = load_or_fallback_texture!(path, "pause-continue.png", graphics);
+        let pause_retry = load_or_fallback_texture!(path, "pause-retry.png", graphics);
+        let pause_back = load_or_fallback_texture!(path, "pause-back.png", graphics);
+        let resume_marker = load_or_fallback_texture!(path, "resume-marker.png", graphics);
+
+        let count3s = load_sound(&path, "count3s");
+        let count2s = load_sound(&path, "count2s");
+        let count1s = load_sound(&path, "count1s");
+        let gos = load_sound(&path, "gos");
+
         Self {
             ini: skin_ini,
             hit_circle,
@@ -124,7 +189,46 @@ impl SkinManager {
             cursor_trail,
             judgments_atlas,
             slider_tick,
-            slider_reverse_arrow,        
+            slider_reverse_arrow,
+            spinner_circle,
+            spinner_approach_circle,
+            spinner_rpm,
+            scorebar_bg,
+            scorebar_colour,
+            section_pass,
+            section_fail,
+            count3,
+            count2,
+            count1,
+            go,
+            play_skip,
+            pause_overlay,
+            pause_continue,
+            pause_retry,
+            pause_back,
+            resume_marker,
+            count3s,
+            count2s,
+            count1s,
+            gos,
+        }
+    }
+
+    pub fn countdown_texture(&self, step: CountdownStep) -> &Texture {
+        match step {
+            CountdownStep::Three => &self.count3,
+            CountdownStep::Two => &self.count2,
+            CountdownStep::One => &self.count1,
+            CountdownStep::Go => &self.go,
+        }
+    }
+
+    pub fn countdown_sound(&self, step: CountdownStep) -> Option<&Arc<[u8]>> {
+        match step {
+            CountdownStep::Three => self.count3s.as_ref(),
+            CountdownStep::Two => self.count2s.as_ref(),
+            CountdownStep::One => self.count1s.as_ref(),
+            CountdownStep::Go => self.gos.as_ref(),
         }
     }
 }
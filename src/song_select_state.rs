@@ -10,7 +10,7 @@ use rosu_map::Beatmap;
 use wgpu::{util::DeviceExt, BufferUsages, TextureView};
 use winit::{dpi::PhysicalSize, keyboard::KeyCode};
 
-use crate::{config::Config, graphics::Graphics, osu_db::{BeatmapEntry, OsuDatabase, DEFAULT_DB_PATH}, osu_state::OsuStateEvent, quad_instance::QuadInstance, quad_renderer::QuadRenderer, screen::settings::SettingsScreen, skin_manager::SkinManager, texture::Texture};
+use crate::{config::Config, graphics::Graphics, keybindings::{Action, ActionContext, BindingInput}, osu_db::{BeatmapEntry, OsuDatabase, DEFAULT_DB_PATH}, osu_state::OsuStateEvent, quad_instance::QuadInstance, quad_renderer::QuadRenderer, screen::{mod_select::ModSelectScreen, settings::SettingsScreen}, skin_manager::SkinManager, texture::Texture};
 
 const CARD_INNER_MARGIN: Margin = Margin {
     left: 5,
@@ -149,6 +149,7 @@ pub enum SongSelectionEvents {
     ImportSongsDirectory(SongsImportJob),
     ToggleSettings,
     CloseSettings,
+    ToggleModSelect,
 }
 
 pub struct SongSelectionState<'ss> {
@@ -183,6 +184,7 @@ pub struct SongSelectionState<'ss> {
     quad_test_instance_data: Vec<QuadInstance>,
 
     settings: SettingsScreen,
+    mod_select: ModSelectScreen,
 }
 
 impl<'ss> SongSelectionState<'ss> {
@@ -216,6 +218,7 @@ impl<'ss> SongSelectionState<'ss> {
             quad_test_buffer,
             quad_test_instance_data,
             settings: SettingsScreen::new(config.clone(), skin_manager.clone(), state_tx.clone()),
+            mod_select: ModSelectScreen::new(state_tx.clone()),
             config,
         }
     }
@@ -322,38 +325,49 @@ impl<'ss> SongSelectionState<'ss> {
     ) {
         let _span = tracy_client::span!("osu_song_select_state::on_pressed_down");
 
-        if key_code == KeyCode::Enter {
-            let current_in_cache = self.current - self.min;
-
-            self.inner_tx.send(
-                SongSelectionEvents::StartBeatmap(self.db.cache[current_in_cache].clone())
-            ).expect(
-                "Failed to send StartBeatmap event to the SongSelectState"
-            );
+        // Key is taken by the settings while rebinding
+        if self.settings.on_pressed_down(key_code, is_cntrl_pressed) {
+            return;
         }
 
-        if key_code == KeyCode::F2 {
-            let mut rng = rand::thread_rng();
+        let action = self.config.read()
+            .expect("failed to acquire read lock")
+            .keybindings
+            .action(ActionContext::SongSelection, BindingInput::Key(key_code), is_cntrl_pressed);
 
-            let random_beatmap = rng.gen_range(0..self.db.beatmaps_amount());
-
-            self.need_scroll_to = Some(random_beatmap);
-        }
+        match action {
+            Some(Action::StartBeatmap) => {
+                let current_in_cache = self.current - self.min;
 
-        if key_code == KeyCode::ArrowDown {
-            self.need_scroll_to = Some(self.current + 1);
-        }
-
-        if key_code == KeyCode::ArrowUp {
-            self.need_scroll_to = Some(self.current - 1);
-        }
+                self.inner_tx.send(
+                    SongSelectionEvents::StartBeatmap(self.db.cache[current_in_cache].clone())
+                ).expect(
+                    "Failed to send StartBeatmap event to the SongSelectState"
+                );
+            },
+            Some(Action::RandomBeatmap) => {
+                let mut rng = rand::thread_rng();
 
-        if key_code == KeyCode::KeyO && is_cntrl_pressed {
-            let _ = self.inner_tx.send(SongSelectionEvents::ToggleSettings);
-        }
+                let random_beatmap = rng.gen_range(0..self.db.beatmaps_amount());
 
-        if key_code == KeyCode::Escape && self.settings.is_open() {
-            let _ = self.inner_tx.send(SongSelectionEvents::CloseSettings);
+                self.need_scroll_to = Some(random_beatmap);
+            },
+            Some(Action::NextBeatmap) => {
+                self.need_scroll_to = Some(self.current + 1);
+            },
+            Some(Action::PreviousBeatmap) => {
+                self.need_scroll_to = Some(self.current - 1);
+            },
+            Some(Action::ToggleSettings) => {
+                let _ = self.inner_tx.send(SongSelectionEvents::ToggleSettings);
+            },
+            Some(Action::CloseSettings) if self.settings.is_open() || self.mod_select.is_open() => {
+                let _ = self.inner_tx.send(SongSelectionEvents::CloseSettings);
+            },
+            Some(Action::ToggleModSelect) => {
+                let _ = self.inner_tx.send(SongSelectionEvents::ToggleModSelect);
+            },
+            _ => {},
         }
     }
     
@@ -466,10 +480,15 @@ impl<'ss> SongSelectionState<'ss> {
                     },
                     SongSelectionEvents::CloseSettings => {
                         self.settings.close();
+                        self.mod_select.close();
+                    },
+                    SongSelectionEvents::ToggleModSelect => {
+                        self.mod_select.toggle();
                     },
                     SongSelectionEvents::StartBeatmap(entry) => {
                         let _span = tracy_client::span!("osu_song_select_state::update::event::start_beatmap");
                         self.settings.close();
+                        self.mod_select.close();
                         self.state_tx.send(OsuStateEvent::StartBeatmap(entry))
                             .expect("Failed to send StartBeatmap event to the OsuState");
                     },
@@ -535,6 +554,17 @@ impl<'ss> SongSelectionState<'ss> {
                 .selectable(false)
             );
 
+            egui::Frame::none()
+                .show(ui, |ui| {
+                    ui.set_min_width(50.0);
+                    ui.set_max_width(50.0);
+                    ui.set_width(50.0);
+
+                    if ui.button("Mods").clicked() {
+                        self.mod_select.toggle();
+                    };
+                });
+
             egui::Frame::none()
                 .show(ui, |ui| {
                     ui.set_min_width(50.0);
@@ -560,6 +590,7 @@ impl<'ss> SongSelectionState<'ss> {
         ctx.begin_pass(input);
 
         self.settings.render(ctx);
+        self.mod_select.render(ctx);
         
         // TODO: God THIS IS SO TERRIBLE LMAO
         egui::CentralPanel::default().frame(egui::Frame::none()).show(ctx, |ui| {
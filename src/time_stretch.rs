@@ -0,0 +1,194 @@
+use std::{collections::VecDeque, time::Duration};
+
+use rodio::{source::SeekError, Source};
+
+/// Length of a single grain in frames, ~46ms at 44100hz
+const GRAIN_FRAMES: usize = 2048;
+/// Grains are written with 50% overlap
+const HOP_FRAMES: usize = GRAIN_FRAMES / 2;
+
+/// Changes playback speed of the source without changing it's pitch.
+///
+/// Plain overlap-add: source is split into overlapping windowed grains
+/// which are read `rate` times faster than they are written back
+pub struct TimeStretch<I>
+where
+    I: Source<Item = f32>,
+{
+    inner: I,
+    rate: f64,
+    channels: usize,
+    window: Vec<f32>,
+
+    /// Source samples starting from `input_frame`
+    input: VecDeque<f32>,
+    input_frame: usize,
+
+    grain: usize,
+    /// Overlapped grains, first hop is ready after every grain
+    mix: Vec<f32>,
+    ready: VecDeque<f32>,
+}
+
+impl<I> TimeStretch<I>
+where
+    I: Source<Item = f32>,
+{
+    pub fn new(inner: I, rate: f64) -> Self {
+        let channels = inner.channels().max(1) as usize;
+
+        // Periodic hann window, two of them overlapped
+        // by a half always sum up to 1.0
+        let window = (0..GRAIN_FRAMES)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / GRAIN_FRAMES as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+
+        Self {
+            inner,
+            rate,
+            channels,
+            window,
+            input: VecDeque::with_capacity(GRAIN_FRAMES * channels * 2),
+            input_frame: 0,
+            grain: 0,
+            mix: vec![0.0; GRAIN_FRAMES * channels],
+            ready: VecDeque::with_capacity(HOP_FRAMES * channels),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.input.clear();
+        self.input_frame = 0;
+        self.grain = 0;
+        self.mix.fill(0.0);
+        self.ready.clear();
+    }
+
+    /// Adds next grain to the mix.
+    /// Returns `false` once source is fully consumed
+    fn process_grain(&mut self) -> bool {
+        let channels = self.channels;
+        let start_frame = (self.grain as f64 * HOP_FRAMES as f64 * self.rate) as usize;
+        let skip = (start_frame - self.input_frame) * channels;
+
+        while self.input.len() < skip + GRAIN_FRAMES * channels {
+            match self.inner.next() {
+                Some(sample) => self.input.push_back(sample),
+                None => break,
+            }
+        }
+
+        let skip = skip.min(self.input.len());
+        self.input.drain(..skip);
+        self.input_frame = start_frame;
+
+        if self.input.is_empty() {
+            // Flushing tail of the last grains
+            if self.mix.iter().all(|x| *x == 0.0) {
+                return false;
+            }
+
+            self.ready.extend(self.mix.iter());
+            self.mix.fill(0.0);
+
+            return true;
+        }
+
+        for (i, sample) in self.input.iter().take(GRAIN_FRAMES * channels).enumerate() {
+            self.mix[i] += sample * self.window[i / channels];
+        }
+
+        let hop = HOP_FRAMES * channels;
+        self.ready.extend(self.mix.drain(..hop));
+        self.mix.resize(GRAIN_FRAMES * channels, 0.0);
+
+        self.grain += 1;
+
+        true
+    }
+}
+
+impl<I> Iterator for TimeStretch<I>
+where
+    I: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rate == 1.0 {
+            return self.inner.next();
+        }
+
+        loop {
+            if let Some(sample) = self.ready.pop_front() {
+                return Some(sample);
+            }
+
+            if !self.process_grain() {
+                return None;
+            }
+        }
+    }
+}
+
+impl<I> Source for TimeStretch<I>
+where
+    I: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+            .map(|x| x.div_f64(self.rate))
+    }
+
+    /// `pos` is the position inside of the source, not
+    /// the stretched one, so it matches beatmap time
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        self.reset();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rodio::{buffer::SamplesBuffer, Source};
+    use test_case::case;
+
+    use super::TimeStretch;
+
+    #[case(1.0)]
+    #[case(1.5)]
+    #[case(0.75)]
+    fn test_stretched_length(rate: f64) {
+        let samples: Vec<f32> = (0..44100 * 2)
+            .map(|i| (i as f32 * 0.01).sin())
+            .collect();
+
+        let source = SamplesBuffer::new(2, 44100, samples);
+        let stretched = TimeStretch::new(source, rate);
+
+        assert_eq!(stretched.channels(), 2);
+
+        let len = stretched.count() as f64;
+        let expected = 44100.0 * 2.0 / rate;
+
+        // Grains are padding the end a bit
+        assert!((len - expected).abs() < 4096.0 * 2.0, "{len} != {expected}");
+    }
+}
@@ -6,6 +6,46 @@ cfg_if::cfg_if! {
     }
 }
 
+/// State of the [`Timer`] at some moment, time keeps going
+/// linearly from it until the timer is changed again.
+/// Lets other threads convert instants to gameplay time
+#[derive(Debug, Copy, Clone)]
+pub struct ClockSnapshot {
+    pub at: Instant,
+    /// Milliseconds
+    pub time: f64,
+    pub rate: f64,
+    pub paused: bool,
+}
+
+impl ClockSnapshot {
+    /// Clock stopped at `time`
+    pub fn paused_at(time: f64) -> Self {
+        Self {
+            at: Instant::now(),
+            time,
+            rate: 1.0,
+            paused: true,
+        }
+    }
+
+    /// Gameplay time at the `instant`, which
+    /// can be before the snapshot as well
+    pub fn time_at(&self, instant: Instant) -> f64 {
+        if self.paused {
+            return self.time;
+        }
+
+        let diff = if instant >= self.at {
+            instant.duration_since(self.at).as_secs_f64()
+        } else {
+            -self.at.duration_since(instant).as_secs_f64()
+        };
+
+        self.time + diff * 1000.0 * self.rate
+    }
+}
+
 pub struct Timer {
     now: Instant,
     started_at: Instant,
@@ -14,6 +54,10 @@ pub struct Timer {
     pub last_time: f64,
 
     paused: bool,
+
+    /// Time passes `rate` times faster than real time,
+    /// used by rate changing mods
+    rate: f64,
 }
 
 impl Timer {
@@ -23,6 +67,7 @@ impl Timer {
             last_time: 0.0,
             paused: true,
             started_at: Instant::now(),
+            rate: 1.0,
         }
     }
     
@@ -38,9 +83,14 @@ impl Timer {
 
     #[inline]
     pub fn unpause(&mut self) {
+        self.unpause_at(Instant::now());
+    }
+
+    /// Time starts going from the `now`
+    pub fn unpause_at(&mut self, now: Instant) {
         self.paused = false;
 
-        self.now = Instant::now();
+        self.now = now;
     }
     
     #[inline]
@@ -48,37 +98,68 @@ impl Timer {
         self.last_time
     }
 
+    #[inline]
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub fn set_rate(&mut self, rate: f64) {
+        // Time passed so far should be counted with the old rate
+        self.update();
+        self.rate = rate;
+    }
+
     pub fn set_time(&mut self, time: f64) {
         self.last_time = time;
     }
 
     pub fn reset_time(&mut self) {
+        self.reset_to(0.0);
+    }
+
+    /// Pauses the timer at `time`, which can be negative
+    /// to have some time before the audio starts
+    pub fn reset_to(&mut self, time: f64) {
         self.started_at = Instant::now();
-        self.last_time = 0.0;
+        self.last_time = time;
         self.paused = true;
     }
 
     /// Updates and returns current time
+    #[inline]
     pub fn update(&mut self) -> f64 {
+        self.update_at(Instant::now())
+    }
+
+    /// Updates and returns time at the `now`,
+    /// which shouldn't be before the last update
+    pub fn update_at(&mut self, now: Instant) -> f64 {
         // TODO refactor
         if self.paused {
             return self.last_time
         };
 
-        let now = Instant::now();
-
         let diff = now.duration_since(self.now);
 
         // Converting to millis
-        self.last_time += diff.as_secs_f64() * 1000.0;
+        self.last_time += diff.as_secs_f64() * 1000.0 * self.rate;
 
         self.now = now;
 
         self.last_time
     }
 
+    pub fn snapshot(&self) -> ClockSnapshot {
+        ClockSnapshot {
+            at: self.now,
+            time: self.last_time,
+            rate: self.rate,
+            paused: self.paused,
+        }
+    }
+
     pub fn since_start(&mut self) -> f64 {
-        (self.now.elapsed().as_secs_f64() * 1000.0) + self.last_time
+        (self.now.elapsed().as_secs_f64() * 1000.0 * self.rate) + self.last_time
     }
 }
 
@@ -102,3 +183,66 @@ fn test_timer_logic() {
 
     assert!(clock.update() == expected)
 }
+
+#[test]
+fn test_timer_rate() {
+    let start = Instant::now();
+
+    let mut clock = Timer::new();
+    clock.set_rate(1.5);
+    clock.unpause_at(start);
+
+    let time = clock.update_at(start + Duration::from_millis(20));
+
+    assert!((time - 30.0).abs() < 1e-6);
+
+    let time = clock.update_at(start + Duration::from_millis(40));
+
+    assert!((time - 60.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_timer_negative_start() {
+    let start = Instant::now();
+
+    let mut clock = Timer::new();
+    clock.reset_to(-10.0);
+
+    // Paused timer stays at the start
+    assert_eq!(clock.update_at(start + Duration::from_millis(5)), -10.0);
+
+    clock.unpause_at(start);
+
+    let time = clock.update_at(start + Duration::from_millis(4));
+
+    assert!((time + 6.0).abs() < 1e-6);
+
+    let time = clock.update_at(start + Duration::from_millis(20));
+
+    assert!((time - 10.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_clock_snapshot() {
+    let mut clock = Timer::new();
+    clock.set_rate(1.5);
+    clock.unpause();
+
+    std::thread::sleep(Duration::from_millis(10));
+    clock.update();
+
+    let snapshot = clock.snapshot();
+    let time = snapshot.time_at(snapshot.at + Duration::from_millis(20));
+
+    assert!((time - (clock.get_time() + 30.0)).abs() < 1e-6);
+
+    let time = snapshot.time_at(snapshot.at - Duration::from_millis(4));
+
+    assert!((time - (clock.get_time() - 6.0)).abs() < 1e-6);
+
+    clock.pause();
+
+    let snapshot = clock.snapshot();
+
+    assert_eq!(snapshot.time_at(snapshot.at + Duration::from_millis(20)), clock.get_time());
+}
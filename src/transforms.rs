@@ -0,0 +1,300 @@
+use std::f32::consts::{PI, TAU};
+
+use cgmath::{InnerSpace, Vector2};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rosu_map::util::Pos;
+
+use crate::{
+    hit_objects::{Object, ObjectKind},
+    math::{calc_progress, lerp, OSU_COORDS_HEIGHT, OSU_COORDS_WIDTH},
+    mods::Mods,
+};
+
+/// Max change of the jump direction between two objects with random
+const RANDOM_MAX_ANGLE_CHANGE: f32 = PI / 2.0;
+
+/// Direction of the wiggle changes that often, in ms
+const WIGGLE_DURATION: f64 = 90.0;
+/// Max wiggle offset in osu!pixels
+const WIGGLE_STRENGTH: f64 = 10.0;
+
+/// Transformation of the converted objects, applied
+/// before stacking. Transforms that are moving objects
+/// must keep sliders consistent, see [`crate::hit_objects::slider::Slider::transform`]
+pub trait ObjectTransform {
+    fn apply(&self, objects: &mut [Object]);
+}
+
+/// Transforms applied one after another
+#[derive(Default)]
+pub struct TransformPipeline {
+    transforms: Vec<Box<dyn ObjectTransform>>,
+}
+
+impl TransformPipeline {
+    pub fn push(&mut self, transform: impl ObjectTransform + 'static) {
+        self.transforms.push(Box::new(transform));
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    pub fn apply(&self, objects: &mut [Object]) {
+        let _span = tracy_client::span!("transforms::apply");
+
+        for transform in &self.transforms {
+            transform.apply(objects);
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MirrorAxis {
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+/// Whether objects are scaled up or down while approaching
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScaleIn {
+    Grow,
+    Deflate,
+}
+
+/// Settings of lazer-like transforming mods.
+///
+/// Mirror and random are moving objects, so they are part of
+/// the [`TransformPipeline`]. Scale in and wiggle are visual only
+/// and are applied by the renderer
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct TransformSettings {
+    /// Overrides axis of [`Mods::MIRROR`], which is horizontal as in stable
+    pub mirror: Option<MirrorAxis>,
+    /// Random is enabled when the seed is present
+    pub random_seed: Option<u64>,
+    pub scale_in: Option<ScaleIn>,
+    pub wiggle: bool,
+}
+
+impl TransformSettings {
+    pub fn pipeline(&self, mods: Mods) -> TransformPipeline {
+        let mut pipeline = TransformPipeline::default();
+
+        let mirror = match self.mirror {
+            Some(axis) => Some(axis),
+            None if mods.contains(Mods::MIRROR) => Some(MirrorAxis::Horizontal),
+            None => None,
+        };
+
+        if let Some(axis) = mirror {
+            pipeline.push(Mirror { axis });
+        }
+
+        if let Some(seed) = self.random_seed {
+            pipeline.push(Random { seed });
+        }
+
+        pipeline
+    }
+}
+
+fn to_vector(pos: Pos) -> Vector2<f32> {
+    Vector2::new(pos.x, pos.y)
+}
+
+fn to_pos(pos: Vector2<f32>) -> Pos {
+    Pos {
+        x: pos.x,
+        y: pos.y,
+    }
+}
+
+/// Flips objects along the playfield axis
+pub struct Mirror {
+    pub axis: MirrorAxis,
+}
+
+impl Mirror {
+    fn flip(&self, pos: Vector2<f32>) -> Vector2<f32> {
+        match self.axis {
+            MirrorAxis::Horizontal => Vector2::new(OSU_COORDS_WIDTH - pos.x, pos.y),
+            MirrorAxis::Vertical => Vector2::new(pos.x, OSU_COORDS_HEIGHT - pos.y),
+            MirrorAxis::Both => Vector2::new(OSU_COORDS_WIDTH - pos.x, OSU_COORDS_HEIGHT - pos.y),
+        }
+    }
+}
+
+impl ObjectTransform for Mirror {
+    fn apply(&self, objects: &mut [Object]) {
+        for object in objects.iter_mut() {
+            match &mut object.kind {
+                ObjectKind::Circle(circle) => {
+                    circle.pos = to_pos(self.flip(to_vector(circle.pos)));
+                },
+                ObjectKind::Slider(slider) => slider.transform(|pos| self.flip(pos)),
+                // Spinners are always in the center
+                ObjectKind::Spinner(_) => {},
+            }
+        }
+    }
+}
+
+/// Randomizes object positions, keeping distances between them.
+///
+/// Every jump is rotated a bit relative to the previous one and
+/// bounced off the playfield edges, sliders are rotated around
+/// their head. Same seed always gives the same positions
+pub struct Random {
+    pub seed: u64,
+}
+
+impl Random {
+    /// Position `distance` away from `from` in `direction`,
+    /// turning around if that ends up outside of the playfield
+    fn jump(from: Vector2<f32>, distance: f32, direction: &mut f32) -> Vector2<f32> {
+        let target = |direction: f32| from + Vector2::new(direction.cos(), direction.sin()) * distance;
+
+        let mut pos = target(*direction);
+
+        if !is_inside_playfield(pos) {
+            *direction += PI;
+            pos = target(*direction);
+        }
+
+        clamp_to_playfield(pos)
+    }
+}
+
+impl ObjectTransform for Random {
+    fn apply(&self, objects: &mut [Object]) {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut direction = rng.gen_range(0.0..TAU);
+
+        // End positions of the previous object before and after randomization
+        let mut prev: Option<(Vector2<f32>, Vector2<f32>)> = None;
+
+        for object in objects.iter_mut() {
+            match &mut object.kind {
+                ObjectKind::Circle(circle) => {
+                    let original = to_vector(circle.pos);
+
+                    let pos = match prev {
+                        Some((prev_original, prev_new)) => {
+                            direction += rng.gen_range(-RANDOM_MAX_ANGLE_CHANGE..RANDOM_MAX_ANGLE_CHANGE);
+                            Random::jump(prev_new, (original - prev_original).magnitude(), &mut direction)
+                        },
+                        None => original,
+                    };
+
+                    circle.pos = to_pos(pos);
+                    prev = Some((original, pos));
+                },
+                ObjectKind::Slider(slider) => {
+                    let original = to_vector(slider.pos);
+                    let original_end = to_vector(slider.end_pos());
+
+                    let head = match prev {
+                        Some((prev_original, prev_new)) => {
+                            direction += rng.gen_range(-RANDOM_MAX_ANGLE_CHANGE..RANDOM_MAX_ANGLE_CHANGE);
+                            Random::jump(prev_new, (original - prev_original).magnitude(), &mut direction)
+                        },
+                        None => original,
+                    };
+
+                    let angle = rng.gen_range(-PI..PI);
+                    let (sin, cos) = angle.sin_cos();
+
+                    slider.transform(|pos| {
+                        let relative = pos - original;
+
+                        head + Vector2::new(
+                            relative.x * cos - relative.y * sin,
+                            relative.x * sin + relative.y * cos,
+                        )
+                    });
+
+                    // Rotated body can end up outside, moving it back
+                    let bounds = slider.bounding_box(0.0);
+                    let shift = Vector2::new(
+                        shift_inside(bounds.top_left.x, bounds.bottom_right.x, OSU_COORDS_WIDTH),
+                        shift_inside(bounds.top_left.y, bounds.bottom_right.y, OSU_COORDS_HEIGHT),
+                    );
+
+                    if shift != Vector2::new(0.0, 0.0) {
+                        slider.transform(|pos| pos + shift);
+                    }
+
+                    prev = Some((original_end, to_vector(slider.end_pos())));
+                },
+                // Spinners are always in the center and
+                // are not affecting the next jump
+                ObjectKind::Spinner(_) => {},
+            }
+        }
+    }
+}
+
+fn is_inside_playfield(pos: Vector2<f32>) -> bool {
+    (0.0..=OSU_COORDS_WIDTH).contains(&pos.x)
+        && (0.0..=OSU_COORDS_HEIGHT).contains(&pos.y)
+}
+
+fn clamp_to_playfield(pos: Vector2<f32>) -> Vector2<f32> {
+    Vector2::new(
+        pos.x.clamp(0.0, OSU_COORDS_WIDTH),
+        pos.y.clamp(0.0, OSU_COORDS_HEIGHT),
+    )
+}
+
+/// Smallest offset that keeps both edges of `min..max`
+/// inside of `0..size`, longer ranges are centered
+fn shift_inside(min: f32, max: f32, size: f32) -> f32 {
+    if max - min > size {
+        return (size - min - max) / 2.0;
+    }
+
+    0.0_f32.clamp(-min, size - max)
+}
+
+/// Hit circle scale with grow or deflate, reaches
+/// normal size at the object start time, same as lazer
+pub fn scale_in(mode: ScaleIn, time: f64, start_time: f64, preempt: f64) -> f64 {
+    let start_scale = match mode {
+        ScaleIn::Grow => 0.5,
+        ScaleIn::Deflate => 2.0,
+    };
+
+    let progress = calc_progress(time, start_time - preempt, start_time).clamp(0.0, 1.0);
+
+    lerp(start_scale, 1.0, progress)
+}
+
+/// Offset of the wiggling object, it's strongest when object
+/// appears and settles down at the start time. Directions
+/// are derived from the start time, so they are the same every frame
+pub fn wiggle_offset(time: f64, start_time: f64, preempt: f64) -> Vector2<f32> {
+    let appear_time = start_time - preempt;
+
+    if time < appear_time || time >= start_time {
+        return Vector2::new(0.0, 0.0);
+    }
+
+    let step = (time - appear_time) / WIGGLE_DURATION;
+    let from = wiggle_direction(start_time, step.floor());
+    let to = wiggle_direction(start_time, step.floor() + 1.0);
+
+    let strength = WIGGLE_STRENGTH * (1.0 - calc_progress(time, appear_time, start_time));
+    let offset = (from + (to - from) * step.fract()) * strength;
+
+    Vector2::new(offset.x as f32, offset.y as f32)
+}
+
+fn wiggle_direction(start_time: f64, step: f64) -> Vector2<f64> {
+    let hash = ((start_time * 12.9898 + step * 78.233).sin() * 43758.5453).fract();
+    let angle = hash * std::f64::consts::TAU;
+
+    Vector2::new(angle.cos(), angle.sin())
+}
@@ -101,4 +101,17 @@ impl Vertex {
             Vertex {pos: [origin_x + width, origin_y, 0.0].into(), uv:[1.0, 0.0]},
         ]
     }
+
+    /// Quad with top left origin which shows only `crop`
+    /// part of the texture, cropping it from the right side
+    pub fn quad_cropped(width: f32, height: f32, crop: f32) -> [Vertex; 4] {
+        let cropped_width = width * crop;
+
+        [
+            Vertex {pos: [0.0, 0.0, 0.0].into(), uv:[0.0, 0.0]},
+            Vertex {pos: [0.0, height, 0.0].into(), uv:[0.0, 1.0]},
+            Vertex {pos: [cropped_width, height, 0.0].into(), uv:[crop, 1.0]},
+            Vertex {pos: [cropped_width, 0.0, 0.0].into(), uv:[crop, 0.0]},
+        ]
+    }
 }
@@ -1,7 +1,8 @@
 use std::path::{Path, PathBuf};
 
-use osu_replay_parser::replay::Replay;
-use rosu::{hit_objects::{hit_window::HitWindow, slider::SliderResultState, Hit, Object}, math::calc_hitcircle_diameter, processor::OsuProcessor};
+use cgmath::Vector2;
+use osu_replay_parser::replay::{replay_data::Keys, Replay};
+use rosu::{hit_objects::{hit_window::HitWindow, ruleset::Ruleset, slider::{Slider, SliderPart, SliderResultState}, ComboResult, Hit, Object, ObjectKind}, math::calc_hitcircle_diameter, mods::{self, DifficultyAdjust, Mods}, osu_input::{KeyboardState, OsuInput}, processor::{autoplay, judgement::{Judgement, JudgementKind}, score_processor::ScoreProcessor, OsuProcessor}};
 use rosu_map::Beatmap;
 use test_case::case;
 
@@ -18,16 +19,26 @@ struct Expected {
 }
 
 fn test_gameplay<T: AsRef<Path>>(replay_file: T, beatmap: T, expected: Expected) {
-    let mut processor: OsuProcessor = Replay::open(replay_file.as_ref()).unwrap().into();
-    let beatmap = Beatmap::from_path(beatmap.as_ref()).unwrap();
+    let replay = Replay::open(replay_file.as_ref()).unwrap();
+    let mods = Mods::from_bits_truncate(replay.mods as u32);
+
+    let mut processor: OsuProcessor = replay.into();
+    let mut beatmap = Beatmap::from_path(beatmap.as_ref()).unwrap();
+    mods::apply_to_beatmap(&mut beatmap, mods, &DifficultyAdjust::default());
+
     let mut beatmap_objects = Object::from_rosu(&beatmap);
 
     let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
 
     let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
 
+    let inputs = processor.queued_inputs().to_vec();
+
     processor.process_all(&mut beatmap_objects, &hit_window, circle_diameter);
 
+    // Replay might end before last objects are finished
+    processor.process_misses(&mut beatmap_objects, f64::MAX, &hit_window);
+
     let mut out = Expected {
         x300: 0,
         x100: 0,
@@ -44,6 +55,12 @@ fn test_gameplay<T: AsRef<Path>>(replay_file: T, beatmap: T, expected: Expected)
     let mut circles_with_result = 0;
 
     beatmap_objects.iter().for_each(|x| {
+        match x.combo_result {
+            Some(ComboResult::Geki) => out.xgeki += 1,
+            Some(ComboResult::Katu) => out.xkatu += 1,
+            None => {},
+        }
+
         match &x.kind {
             rosu::hit_objects::ObjectKind::Circle(circle) => {
                 proccessed_circles += 1;
@@ -73,12 +90,13 @@ fn test_gameplay<T: AsRef<Path>>(replay_file: T, beatmap: T, expected: Expected)
                     match hit_result.state {
                         SliderResultState::Passed(hit) => {
                             sliders_with_result += 1;
+                            check_slider_parts(slider);
 
                             match hit {
                                 rosu::hit_objects::Hit::X300 => out.x300 += 1,
                                 rosu::hit_objects::Hit::X100 => out.x100 += 1,
                                 rosu::hit_objects::Hit::X50 => out.x50 += 1,
-                                rosu::hit_objects::Hit::MISS => {}, //out.xmiss += 1,
+                                rosu::hit_objects::Hit::MISS => out.xmiss += 1,
                             }
                         },
                         _ => { panic!("super bad") }
@@ -135,6 +153,18 @@ fn test_gameplay<T: AsRef<Path>>(replay_file: T, beatmap: T, expected: Expected)
                 }
 
             },
+            rosu::hit_objects::ObjectKind::Spinner(spinner) => {
+                let Some(hit_result) = &spinner.hit_result else {
+                    panic!("uncovered spinner");
+                };
+
+                match hit_result.result {
+                    rosu::hit_objects::Hit::X300 => out.x300 += 1,
+                    rosu::hit_objects::Hit::X100 => out.x100 += 1,
+                    rosu::hit_objects::Hit::X50 => out.x50 += 1,
+                    rosu::hit_objects::Hit::MISS => out.xmiss += 1,
+                }
+            },
         }
     });
     
@@ -151,10 +181,126 @@ fn test_gameplay<T: AsRef<Path>>(replay_file: T, beatmap: T, expected: Expected)
                 }
             },
             rosu::hit_objects::ObjectKind::Slider(slider) => return,
+            rosu::hit_objects::ObjectKind::Spinner(_) => return,
         }
     });
 
     assert_eq!(out, expected, "Left - Result from processor, Right - expected");
+
+    // Incremental processing, a few inputs per "frame",
+    // should end up with exactly the same judgements
+    let mut incremental = OsuProcessor::default();
+    incremental.set_rate(processor.rate());
+    let mut incremental_objects = Object::from_rosu(&beatmap);
+    let mut combo_ends = 0;
+
+    for frame in inputs.chunks(4) {
+        for input in frame {
+            incremental.store_input(input.clone());
+        }
+
+        let ts = frame.last().map(|x| x.ts).unwrap_or(0.0);
+        incremental.process(ts, &mut incremental_objects, &hit_window, circle_diameter);
+        combo_ends += count_combo_ends(&incremental.take_judgements());
+    }
+
+    incremental.process_misses(&mut incremental_objects, f64::MAX, &hit_window);
+    combo_ends += count_combo_ends(&incremental.take_judgements());
+
+    assert_eq!(
+        final_results(&beatmap_objects),
+        final_results(&incremental_objects),
+        "Left - process_all, Right - incremental process"
+    );
+
+    // Geki and katu are reported once per combo, after all of it is judged
+    assert_eq!(
+        combo_results(&beatmap_objects),
+        combo_results(&incremental_objects),
+        "Left - process_all, Right - incremental process"
+    );
+    assert_eq!(combo_ends, (expected.xgeki + expected.xkatu) as usize);
+}
+
+#[test]
+fn test_incremental_reports_early_hits() {
+    let beatmap = Beatmap::from_path(get_gameplay_tests_path().join("single_hit_circle.osu")).unwrap();
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+    let inputs = replay_inputs("single_hit_circle1.osr");
+
+    let mut full = OsuProcessor::default();
+    let mut full_objects = Object::from_rosu(&beatmap);
+
+    for input in &inputs {
+        full.store_input(input.clone());
+    }
+
+    full.process_all(&mut full_objects, &hit_window, circle_diameter);
+    let expected: Vec<_> = full.take_judgements().iter().map(|x| x.kind).collect();
+
+    // Every input is already stored, but processed at the time
+    // way before the circle, so it's hit ahead of the clock
+    let mut incremental = OsuProcessor::default();
+    let mut incremental_objects = Object::from_rosu(&beatmap);
+
+    for input in inputs {
+        incremental.store_input(input);
+    }
+
+    let early = incremental_objects[0].start_time - hit_window.xmiss - 100.0;
+    incremental.process(early, &mut incremental_objects, &hit_window, circle_diameter);
+
+    assert_eq!(incremental.active_object(), 1);
+
+    let mut judgements = incremental.take_judgements();
+    incremental.process(f64::MAX, &mut incremental_objects, &hit_window, circle_diameter);
+    judgements.extend(incremental.take_judgements());
+
+    let judged: Vec<_> = judgements.iter().map(|x| x.kind).collect();
+    assert_eq!(judged, expected, "Left - incremental process, Right - process_all");
+    assert_eq!(incremental_objects[0].final_hit(), Some(Hit::X300));
+}
+
+fn count_combo_ends(judgements: &[Judgement]) -> usize {
+    judgements.iter()
+        .filter(|x| matches!(x.kind, JudgementKind::ComboEnd(_)))
+        .count()
+}
+
+/// Every part of the finished slider is judged exactly once
+/// and matches the state final judgement was calculated from
+fn check_slider_parts(slider: &Slider) {
+    let hit_result = slider.hit_result.as_ref().unwrap();
+    let parts = &hit_result.parts;
+
+    assert_eq!(parts.len(), slider.checkpoints.len() + 2, "slider at {}", slider.start_time);
+    assert_eq!(parts.first().map(|x| x.part), Some(SliderPart::Head));
+    assert_eq!(parts.last().map(|x| x.part), Some(SliderPart::End));
+    assert_eq!(parts.last().map(|x| x.hit), Some(hit_result.lenience_passed));
+
+    for (i, checkpoint) in slider.checkpoints.iter().enumerate() {
+        let judged: Vec<_> = parts.iter()
+            .filter(|x| x.checkpoint == Some(i))
+            .collect();
+
+        assert_eq!(judged.len(), 1, "checkpoint at {}", checkpoint.time);
+        assert_eq!(judged[0].hit, hit_result.passed_checkpoints.contains(&i));
+        assert_eq!(judged[0].pos, checkpoint.pos);
+        assert_eq!(judged[0].at, checkpoint.time);
+    }
+}
+
+fn final_results(objects: &[Object]) -> Vec<Option<Hit>> {
+    objects.iter()
+        .map(|x| x.final_hit())
+        .collect()
+}
+
+fn combo_results(objects: &[Object]) -> Vec<Option<ComboResult>> {
+    objects.iter()
+        .map(|x| x.combo_result)
+        .collect()
 }
 
 fn get_gameplay_tests_path() -> PathBuf {
@@ -169,7 +315,7 @@ fn get_gameplay_tests_path() -> PathBuf {
         x100: 0,
         x50: 0,
         xkatu: 0,
-        xgeki: 0,
+        xgeki: 1,
         xmiss: 0,
     };
     "1 x300"
@@ -181,7 +327,7 @@ Expected {
     x300: 0,
     x100: 1,
     x50: 0,
-    xkatu: 0,
+    xkatu: 1,
     xgeki: 0,
     xmiss: 0,
 };
@@ -220,7 +366,7 @@ fn test_single_hit_circle(replay: &str, beatmap: &str, expected: Expected) {
         x300: 5,
         x100: 1,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -233,7 +379,7 @@ fn test_single_hit_circle(replay: &str, beatmap: &str, expected: Expected) {
         x300: 4,
         x100: 2,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -246,7 +392,7 @@ fn test_single_hit_circle(replay: &str, beatmap: &str, expected: Expected) {
         x300: 5,
         x100: 1,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -260,7 +406,7 @@ fn test_single_hit_circle(replay: &str, beatmap: &str, expected: Expected) {
         x100: 0,
         x50: 0,
         xkatu: 0,
-        xgeki: 0,
+        xgeki: 1,
         xmiss: 0,
     };
     "6 x300"
@@ -278,6 +424,87 @@ fn test_simple_jumps(replay: &str, beatmap: &str, expected: Expected) {
     );
 }
 
+// Synthetic, frames of the real jumps_simple4 play are flipped upside
+// down. HR makes OD 10, so with 20ms x300 window (edge excluded)
+// presses 19ms early/late are x300 and 20ms or more are x100
+#[case(
+    "jumps_simple_hr.osr", 
+    "jumps_simple.osu",
+    Expected {
+        x300: 3,
+        x100: 3,
+        x50: 0,
+        xkatu: 1,
+        xgeki: 0,
+        xmiss: 0,
+    };
+    "jumps_simple4 flipped with HR, 3 x300 3 x100"
+)]
+fn test_hard_rock(replay: &str, beatmap: &str, expected: Expected) {
+    let base = get_gameplay_tests_path();
+
+    let replay_file = base.join(replay);
+    let beatmap_file = base.join(beatmap);
+
+    test_gameplay(
+        replay_file, 
+        beatmap_file, 
+        expected
+    );
+}
+
+#[test]
+fn test_hard_rock_flips_objects() {
+    let mut beatmap = Beatmap::from_path(get_gameplay_tests_path().join("jumps_simple.osu")).unwrap();
+    mods::apply_to_beatmap(&mut beatmap, Mods::HARD_ROCK, &DifficultyAdjust::default());
+
+    // Frames that weren't flipped are far away from most of the flipped circles
+    let results = assisted_results(replay_inputs("jumps_simple4.osr"), Mods::empty(), &beatmap);
+    assert!(results.contains(&Some(Hit::MISS)), "{results:?}");
+}
+
+// Synthetic spinner replay (spinner1 frames with the mods field changed).
+// Spinning speed is capped in real time, with HT a beatmap millisecond
+// is longer in real time so the cap isn't reached, DT is where it binds
+#[case(
+    "spinner1_ht.osr", 
+    "spinner.osu",
+    Expected {
+        x300: 1,
+        x100: 0,
+        x50: 0,
+        xkatu: 0,
+        xgeki: 1,
+        xmiss: 0,
+    };
+    "spinner1 with HT, not capped, 20 spins out of 15 required"
+)]
+#[case(
+    "spinner1_dt.osr", 
+    "spinner.osu",
+    Expected {
+        x300: 1,
+        x100: 0,
+        x50: 0,
+        xkatu: 0,
+        xgeki: 1,
+        xmiss: 0,
+    };
+    "spinner1 with DT, capped to 15.8 spins out of 15 required"
+)]
+fn test_rate_mods(replay: &str, beatmap: &str, expected: Expected) {
+    let base = get_gameplay_tests_path();
+
+    let replay_file = base.join(replay);
+    let beatmap_file = base.join(beatmap);
+
+    test_gameplay(
+        replay_file, 
+        beatmap_file, 
+        expected
+    );
+}
+
 #[case(
     "slider.osr", 
     "slider.osu",
@@ -286,7 +513,7 @@ fn test_simple_jumps(replay: &str, beatmap: &str, expected: Expected) {
         x100: 0,
         x50: 0,
         xkatu: 0,
-        xgeki: 0,
+        xgeki: 1,
         xmiss: 0,
     };
     "1 x300"
@@ -311,7 +538,7 @@ fn test_simple_jumps(replay: &str, beatmap: &str, expected: Expected) {
         x300: 0,
         x100: 1,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -337,7 +564,7 @@ fn test_slider(replay: &str, beatmap: &str, expected: Expected) {
         x300: 0,
         x100: 1,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -351,7 +578,7 @@ fn test_slider(replay: &str, beatmap: &str, expected: Expected) {
         x100: 0,
         x50: 0,
         xkatu: 0,
-        xgeki: 0,
+        xgeki: 1,
         xmiss: 0,
     };
     "hit slider completly, 1 x300"
@@ -363,7 +590,7 @@ fn test_slider(replay: &str, beatmap: &str, expected: Expected) {
         x300: 0,
         x100: 1,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -376,7 +603,7 @@ fn test_slider(replay: &str, beatmap: &str, expected: Expected) {
         x300: 0,
         x100: 1,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -403,7 +630,7 @@ fn test_slider_two_ticks(replay: &str, beatmap: &str, expected: Expected) {
         x100: 0,
         x50: 0,
         xkatu: 0,
-        xgeki: 0,
+        xgeki: 1,
         xmiss: 0,
     };
     "perfect sliders hit, 2 x300"
@@ -415,7 +642,7 @@ fn test_slider_two_ticks(replay: &str, beatmap: &str, expected: Expected) {
         x300: 1,
         x100: 1,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -442,8 +669,8 @@ fn test_two_sliders(replay: &str, beatmap: &str, expected: Expected) {
         x300: 6,
         x100: 1,
         x50: 0,
-        xkatu: 0,
-        xgeki: 0,
+        xkatu: 1,
+        xgeki: 2,
         xmiss: 0,
     };
     "1 x100, 6x300"
@@ -469,7 +696,7 @@ fn test_sliders_and_jumps(replay: &str, beatmap: &str, expected: Expected) {
         x100: 0,
         x50: 0,
         xkatu: 0,
-        xgeki: 0,
+        xgeki: 1,
         xmiss: 0,
     };
     "4 x300"
@@ -481,7 +708,7 @@ fn test_sliders_and_jumps(replay: &str, beatmap: &str, expected: Expected) {
         x300: 3,
         x100: 6,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -508,7 +735,7 @@ fn test_sliders_with_stacks(replay: &str, beatmap: &str, expected: Expected) {
         x100: 0,
         x50: 0,
         xkatu: 0,
-        xgeki: 0,
+        xgeki: 1,
         xmiss: 0,
     };
     "perfect hit, 1 x300"
@@ -520,7 +747,7 @@ fn test_sliders_with_stacks(replay: &str, beatmap: &str, expected: Expected) {
         x300: 0,
         x100: 1,
         x50: 0,
-        xkatu: 0,
+        xkatu: 1,
         xgeki: 0,
         xmiss: 0,
     };
@@ -539,6 +766,114 @@ fn test_slider_with_ticks_and_reverse(replay: &str, beatmap: &str, expected: Exp
     );
 }
 
+// Synthetic replays spinning an even circle with 16ms frames, they
+// aren't recorded in stable. Counts are worked out from the spin ratio
+// thresholds of the judgement, so they only guard against regressions
+#[case(
+    "spinner1.osr", 
+    "spinner.osu",
+    Expected {
+        x300: 1,
+        x100: 0,
+        x50: 0,
+        xkatu: 0,
+        xgeki: 1,
+        xmiss: 0,
+    };
+    "20 spins out of 15 required, 1 x300"
+)]
+#[case(
+    "spinner2.osr", 
+    "spinner.osu",
+    Expected {
+        x300: 0,
+        x100: 1,
+        x50: 0,
+        xkatu: 1,
+        xgeki: 0,
+        xmiss: 0,
+    };
+    "14 spins, 1 x100"
+)]
+#[case(
+    "spinner3.osr", 
+    "spinner.osu",
+    Expected {
+        x300: 0,
+        x100: 0,
+        x50: 1,
+        xkatu: 0,
+        xgeki: 0,
+        xmiss: 0,
+    };
+    "12 spins, 1 x50"
+)]
+#[case(
+    "spinner4.osr", 
+    "spinner.osu",
+    Expected {
+        x300: 0,
+        x100: 0,
+        x50: 0,
+        xkatu: 0,
+        xgeki: 0,
+        xmiss: 1,
+    };
+    "5 spins, 1 miss"
+)]
+fn test_spinner(replay: &str, beatmap: &str, expected: Expected) {
+    let base = get_gameplay_tests_path();
+
+    let replay_file = base.join(replay);
+    let beatmap_file = base.join(beatmap);
+
+    test_gameplay(
+        replay_file, 
+        beatmap_file, 
+        expected
+    );
+}
+
+// Same synthetic replays, bonus starts from the third extra spin
+#[case("spinner1.osr", 3)]
+#[case("spinner2.osr", 0)]
+#[case("spinner4.osr", 0)]
+#[case("spinner1_ht.osr", 3; "half time doesn't reach the real time cap")]
+#[case("spinner1_dt.osr", 0; "double time caps spinning speed in real time")]
+fn test_spinner_bonus(replay: &str, expected_bonus: u32) {
+    let base = get_gameplay_tests_path();
+
+    let replay = Replay::open(&base.join(replay)).unwrap();
+    let beatmap = Beatmap::from_path(base.join("spinner.osu")).unwrap();
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+
+    let mut processor: OsuProcessor = replay.into();
+    processor.process_all(&mut beatmap_objects, &hit_window, circle_diameter);
+    processor.process_misses(&mut beatmap_objects, f64::MAX, &hit_window);
+
+    let ObjectKind::Spinner(spinner) = &beatmap_objects[0].kind else {
+        panic!("should be spinner");
+    };
+
+    // 5 spins per second at OD 5 during 3 seconds
+    assert_eq!(spinner.required_spins, 15.0);
+
+    let hit_result = spinner.hit_result.as_ref().unwrap();
+    assert_eq!(hit_result.bonus_spins, expected_bonus);
+
+    let judgements = processor.take_judgements();
+    let bonus = judgements.iter().find_map(|x| match x.kind {
+        JudgementKind::SpinnerSpins { bonus_spins, .. } => Some(bonus_spins),
+        _ => None,
+    });
+
+    assert_eq!(bonus.unwrap_or(0), expected_bonus);
+}
+
 #[case(
     "koise.osr", 
     "koise.osu",
@@ -547,7 +882,7 @@ fn test_slider_with_ticks_and_reverse(replay: &str, beatmap: &str, expected: Exp
         x100: 0,
         x50: 0,
         xkatu: 0,
-        xgeki: 0,
+        xgeki: 14,
         xmiss: 0,
     };
     "koise normal diff, 46 x300 an SS"
@@ -559,8 +894,8 @@ fn test_slider_with_ticks_and_reverse(replay: &str, beatmap: &str, expected: Exp
         x300: 41,
         x100: 5,
         x50: 0,
-        xkatu: 0,
-        xgeki: 0,
+        xkatu: 3,
+        xgeki: 11,
         xmiss: 0,
     };
     "koise normal diff, 41 x300 5 x100 an A"
@@ -572,8 +907,8 @@ fn test_slider_with_ticks_and_reverse(replay: &str, beatmap: &str, expected: Exp
         x300: 65,
         x100: 2,
         x50: 0,
-        xkatu: 0,
-        xgeki: 0,
+        xkatu: 2,
+        xgeki: 18,
         xmiss: 0,
     };
     "aozora hard diff, 65 x300 2 x100 an S"
@@ -585,11 +920,11 @@ fn test_slider_with_ticks_and_reverse(replay: &str, beatmap: &str, expected: Exp
         x300: 236,
         x100: 6,
         x50: 1,
-        xkatu: 0,
-        xgeki: 0,
-        xmiss: 0,
+        xkatu: 4,
+        xgeki: 53,
+        xmiss: 2,
     };
-    "getta banban hard diff, 236 x300 6 x100 x50 an A"
+    "getta banban hard diff, 236 x300 6 x100 x50 2 misses an A"
 )]
 #[case(
     "gin_no_kaze.osr", 
@@ -598,11 +933,11 @@ fn test_slider_with_ticks_and_reverse(replay: &str, beatmap: &str, expected: Exp
         x300: 332,
         x100: 21,
         x50: 1,
-        xkatu: 0,
-        xgeki: 0,
-        xmiss: 0,
+        xkatu: 14,
+        xgeki: 73,
+        xmiss: 7,
     };
-    "gin no kaze, an A"
+    "gin no kaze, 7 missed sliders an A"
 )]
 fn test_actual_ranked_map(replay: &str, beatmap: &str, expected: Expected) {
     let base = get_gameplay_tests_path();
@@ -625,7 +960,7 @@ fn test_actual_ranked_map(replay: &str, beatmap: &str, expected: Expected) {
         x100: 0,
         x50: 0,
         xkatu: 0,
-        xgeki: 0,
+        xgeki: 1,
         xmiss: 0,
     };
     "stack of five, with one key beign held"
@@ -642,3 +977,500 @@ fn test_stacks_one_key_hold(replay: &str, beatmap: &str, expected: Expected) {
         expected
     );
 }
+
+#[case("single_hit_circle1.osr", "single_hit_circle.osu")]
+#[case("jumps_simple1.osr", "jumps_simple.osu")]
+#[case("slider.osr", "slider.osu")]
+#[case("slider_two_ticks.osr", "slider_two_ticks.osu")]
+#[case("slider_with_ticks_and_reverse.osr", "slider_with_ticks_and_reverse.osu")]
+#[case("stacks.osr", "stacks.osu")]
+#[case("koise.osr", "koise.osu")]
+#[case("aozora_hard.osr", "aozora_hard.osu")]
+#[case("getta_banban.osr", "getta_banban.osu")]
+#[case("gin_no_kaze.osr", "gin_no_kaze.osu")]
+fn test_score_matches_replay_header(replay: &str, beatmap: &str) {
+    let replay = Replay::open(&get_gameplay_tests_path().join(replay)).unwrap();
+
+    let expected_score = replay.score as u64;
+    let expected_combo = replay.max_combo as u32;
+
+    let score = replay_score(replay, beatmap);
+
+    assert_eq!(score.score, expected_score, "Left - Result from processor, Right - replay header");
+    assert_eq!(score.max_combo, expected_combo, "Left - Result from processor, Right - replay header");
+}
+
+// Synthetic replays have no score in the header, so ScoreV1 is worked
+// out by hand: 100 per spin, 1000 per bonus spin and the hit value plus
+// `value * (combo - 1) * difficulty * mods / 25`. jumps_simple with HR
+// has difficulty multiplier 4 and mods multiplier 1.06
+#[case("spinner1.osr", "spinner.osu", 1700 + 3000 + 300; "17 spins and 3 bonus")]
+#[case("spinner1_ht.osr", "spinner.osu", 1700 + 3000 + 300; "half time spins as much as nomod")]
+#[case("spinner1_dt.osr", "spinner.osu", 1500 + 300; "double time is capped to 15 spins")]
+#[case("jumps_simple_hr.osr", "jumps_simple.osu", 300 + 300 + 116 + 133 + 452 + 167; "hard rock multiplier")]
+fn test_score_of_synthetic_replays(replay: &str, beatmap: &str, expected_score: u64) {
+    let replay = Replay::open(&get_gameplay_tests_path().join(replay)).unwrap();
+
+    assert_eq!(replay_score(replay, beatmap).score, expected_score);
+}
+
+/// ScoreV1 of the replay, with mods of the replay
+fn replay_score(replay: Replay, beatmap: &str) -> ScoreProcessor {
+    let base = get_gameplay_tests_path();
+    let mods = Mods::from_bits_truncate(replay.mods as u32);
+
+    let original = Beatmap::from_path(base.join(beatmap)).unwrap();
+    let mut beatmap = original.clone();
+    mods::apply_to_beatmap(&mut beatmap, mods, &DifficultyAdjust::default());
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+
+    let mut processor: OsuProcessor = replay.into();
+    processor.process_all(&mut beatmap_objects, &hit_window, circle_diameter);
+    processor.process_misses(&mut beatmap_objects, f64::MAX, &hit_window);
+
+    let mut score = ScoreProcessor::new(&original, &beatmap_objects);
+    score.mod_multiplier = mods.score_multiplier();
+    score.apply_all(&processor.take_judgements());
+
+    score
+}
+
+#[case("single_hit_circle.osu")]
+#[case("jumps_simple.osu")]
+#[case("slider.osu")]
+#[case("slider_leniency.osu")]
+#[case("slider_two_ticks.osu")]
+#[case("slider_with_stack.osu")]
+#[case("slider_with_stack2.osu")]
+#[case("slider_with_ticks_and_reverse.osu")]
+#[case("sliders_and_jumps.osu")]
+#[case("stacks.osu")]
+#[case("two_sliders.osu")]
+#[case("aozora_hard.osu")]
+#[case("getta_banban.osu")]
+#[case("gin_no_kaze.osu")]
+#[case("koise.osu")]
+fn test_autoplay_full_accuracy(beatmap: &str) {
+    let beatmap = Beatmap::from_path(get_gameplay_tests_path().join(beatmap)).unwrap();
+
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+
+    for ruleset in [Ruleset::Stable, Ruleset::Lazer] {
+        let mut beatmap_objects = Object::from_rosu(&beatmap);
+
+        let mut processor = OsuProcessor::default();
+        processor.set_ruleset(ruleset);
+
+        for input in autoplay::generate(&beatmap_objects, 1.0) {
+            processor.store_input(input);
+        }
+
+        processor.process_all(&mut beatmap_objects, &hit_window, circle_diameter);
+        processor.process_misses(&mut beatmap_objects, f64::MAX, &hit_window);
+
+        for object in &beatmap_objects {
+            assert_eq!(
+                object.final_hit(),
+                Some(Hit::X300),
+                "Object at {} wasn't perfectly hit by autoplay with {:?}",
+                object.start_time,
+                ruleset
+            );
+        }
+    }
+}
+
+fn lazer_objects(replay: &str, beatmap: &str) -> Vec<Object> {
+    let base = get_gameplay_tests_path();
+
+    let replay = Replay::open(&base.join(replay)).unwrap();
+    let mods = Mods::from_bits_truncate(replay.mods as u32);
+
+    let mut processor: OsuProcessor = replay.into();
+    processor.set_ruleset(Ruleset::Lazer);
+
+    let mut beatmap = Beatmap::from_path(base.join(beatmap)).unwrap();
+    mods::apply_to_beatmap(&mut beatmap, mods, &DifficultyAdjust::default());
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+
+    processor.process_all(&mut beatmap_objects, &hit_window, circle_diameter);
+    processor.process_misses(&mut beatmap_objects, f64::MAX, &hit_window);
+
+    beatmap_objects
+}
+
+// Regression tests of the lazer ruleset, no lazer recordings are in
+// the corpus. Expectations are worked out from lazer's rules: head is
+// judged by it's hit error with OD 7 windows of 38/84/130ms, ticks and
+// the tail are hit only while the key is held and cursor is inside of
+// the follow circle, which is 2.4 times bigger than the hit circle once
+// the ball is tracked. slider_late.osr is synthetic, frames of slider.osr
+// are shifted by 60ms
+#[case(
+    "slider.osr",
+    "slider.osu",
+    Hit::X300,
+    vec![true],
+    true;
+    "head 21ms early, cursor is 40px away from the ball at the tick"
+)]
+#[case(
+    "slider_late.osr",
+    "slider.osu",
+    Hit::X100,
+    vec![false],
+    false;
+    "same play 60ms late, head 39ms late and follow circle is lost"
+)]
+#[case(
+    "slider_with_ticks_and_reverse.osr",
+    "slider_with_ticks_and_reverse.osu",
+    Hit::X300,
+    vec![true, true, true],
+    true;
+    "head 14ms early, every tick and repeat tracked"
+)]
+fn test_replay_lazer_ruleset(
+    replay: &str,
+    beatmap: &str,
+    head: Hit,
+    checkpoints: Vec<bool>,
+    tail: bool,
+) {
+    let beatmap_objects = lazer_objects(replay, beatmap);
+
+    let ObjectKind::Slider(slider) = &beatmap_objects[0].kind else {
+        panic!("should be slider");
+    };
+
+    let result = slider.hit_result.as_ref().unwrap();
+
+    assert_eq!(result.head.result, head);
+
+    let judged_checkpoints: Vec<_> = (0..slider.checkpoints.len())
+        .map(|i| result.passed_checkpoints.contains(&i))
+        .collect();
+
+    assert_eq!(judged_checkpoints, checkpoints);
+    assert_eq!(result.lenience_passed, tail);
+
+    // Lazer sliders are judged by the head accuracy
+    assert_eq!(beatmap_objects[0].final_hit(), Some(head));
+    check_slider_parts(slider);
+}
+
+#[case("slider_two_ticks2.osr", "slider_two_ticks.osu")]
+#[case("aozora_hard.osr", "aozora_hard.osu")]
+fn test_replay_lazer_ruleset_parts(replay: &str, beatmap: &str) {
+    for object in &lazer_objects(replay, beatmap) {
+        assert!(object.is_finished(), "Object at {} wasn't judged", object.start_time);
+
+        if let ObjectKind::Slider(slider) = &object.kind {
+            check_slider_parts(slider);
+        }
+    }
+}
+
+fn replay_results(replay: Replay, beatmap: &Beatmap) -> Vec<Option<Hit>> {
+    let mods = Mods::from_bits_truncate(replay.mods as u32);
+
+    let mut beatmap = beatmap.clone();
+    mods::apply_to_beatmap(&mut beatmap, mods, &DifficultyAdjust::default());
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+
+    let mut processor: OsuProcessor = replay.into();
+    processor.process_all(&mut beatmap_objects, &hit_window, circle_diameter);
+    processor.process_misses(&mut beatmap_objects, f64::MAX, &hit_window);
+
+    final_results(&beatmap_objects)
+}
+
+#[case("jumps_simple1.osr", "jumps_simple.osu")]
+#[case("slider_two_ticks2.osr", "slider_two_ticks.osu")]
+#[case("slider_with_ticks_and_reverse.osr", "slider_with_ticks_and_reverse.osu")]
+#[case("stacks.osr", "stacks.osu")]
+fn test_mouse_buttons_replay(replay: &str, beatmap: &str) {
+    let base = get_gameplay_tests_path();
+
+    let beatmap = Beatmap::from_path(base.join(beatmap)).unwrap();
+
+    let keyboard = Replay::open(&base.join(replay)).unwrap();
+    let mut mouse = Replay::open(&base.join(replay)).unwrap();
+
+    // Same play, but with mouse buttons only, like tablet players do
+    for frame in &mut mouse.replay_data.frames {
+        let mut keys = Keys::empty();
+
+        if frame.z.contains(Keys::K1) || frame.z.contains(Keys::M1) {
+            keys |= Keys::M1;
+        }
+
+        if frame.z.contains(Keys::K2) || frame.z.contains(Keys::M2) {
+            keys |= Keys::M2;
+        }
+
+        frame.z = keys;
+    }
+
+    let expected = replay_results(keyboard, &beatmap);
+
+    assert!(expected.iter().any(|x| matches!(x, Some(hit) if *hit != Hit::MISS)));
+    assert_eq!(replay_results(mouse, &beatmap), expected);
+}
+
+#[test]
+fn test_assist_mods_replay() {
+    let base = get_gameplay_tests_path();
+
+    let mut replay = Replay::open(&base.join("jumps_simple4.osr")).unwrap();
+
+    // Relax and autopilot are already baked into replay frames
+    let mods = Mods::from_bits_truncate(replay.mods as u32) | Mods::RELAX | Mods::AUTOPILOT;
+    replay.mods = mods.bits() as _;
+
+    let beatmap = Beatmap::from_path(base.join("jumps_simple.osu")).unwrap();
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+
+    let mut processor: OsuProcessor = replay.into();
+    assert!(processor.assists().is_empty());
+
+    processor.process_all(&mut beatmap_objects, &hit_window, circle_diameter);
+    processor.process_misses(&mut beatmap_objects, f64::MAX, &hit_window);
+
+    assert_eq!(final_results(&beatmap_objects), vec![Some(Hit::X300); 6]);
+}
+
+/// Frames of the `replay` the same way processor reads them
+fn replay_inputs(replay: &str) -> Vec<OsuInput> {
+    let replay = Replay::open(&get_gameplay_tests_path().join(replay)).unwrap();
+    let processor: OsuProcessor = replay.into();
+
+    processor.queued_inputs().to_vec()
+}
+
+fn assisted_results(inputs: Vec<OsuInput>, assists: Mods, beatmap: &Beatmap) -> Vec<Option<Hit>> {
+    let mut beatmap_objects = Object::from_rosu(beatmap);
+
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+
+    let mut processor = OsuProcessor::default();
+    processor.set_assists(assists);
+
+    for input in inputs {
+        processor.store_input(input);
+    }
+
+    processor.process_all(&mut beatmap_objects, &hit_window, circle_diameter);
+    processor.process_misses(&mut beatmap_objects, f64::MAX, &hit_window);
+
+    final_results(&beatmap_objects)
+}
+
+#[test]
+fn test_relax_cursor_only() {
+    let beatmap = Beatmap::from_path(get_gameplay_tests_path().join("jumps_simple.osu")).unwrap();
+
+    // Cursor of the real play, but no keys are pressed
+    let inputs: Vec<_> = replay_inputs("jumps_simple4.osr").into_iter()
+        .map(|x| OsuInput {
+            keys: KeyboardState::empty(),
+            hold: KeyboardState::empty(),
+            ..x
+        })
+        .collect();
+
+    assert_eq!(assisted_results(inputs.clone(), Mods::empty(), &beatmap), vec![Some(Hit::MISS); 6]);
+
+    // Relax presses once the object starts and cursor is inside of it,
+    // every press of this cursor path is within 16ms after the start
+    assert_eq!(assisted_results(inputs, Mods::RELAX, &beatmap), vec![Some(Hit::X300); 6]);
+}
+
+#[test]
+fn test_autopilot_keys_only() {
+    let beatmap = Beatmap::from_path(get_gameplay_tests_path().join("jumps_simple.osu")).unwrap();
+
+    // Keys of the real play, cursor stays in the corner
+    let inputs: Vec<_> = replay_inputs("jumps_simple4.osr").into_iter()
+        .map(|x| OsuInput {
+            pos: Vector2::new(0.0, 0.0),
+            ..x
+        })
+        .collect();
+
+    assert_eq!(assisted_results(inputs.clone(), Mods::empty(), &beatmap), vec![Some(Hit::MISS); 6]);
+
+    // Presses are judged at their own time, same as without autopilot
+    assert_eq!(assisted_results(inputs, Mods::AUTOPILOT, &beatmap), vec![Some(Hit::X300); 6]);
+}
+
+#[test]
+fn test_assisted_replay_playback() {
+    let beatmap = Beatmap::from_path(get_gameplay_tests_path().join("jumps_simple.osu")).unwrap();
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+
+    // Every key of the play was pressed by relax
+    let inputs = replay_inputs("jumps_simple4.osr").into_iter()
+        .map(|x| OsuInput {
+            keys: KeyboardState::empty(),
+            hold: KeyboardState::empty(),
+            ..x
+        });
+
+    let mut processor = OsuProcessor::default();
+    processor.set_assists(Mods::RELAX);
+
+    for input in inputs {
+        processor.store_input(input);
+    }
+
+    processor.process_all(&mut beatmap_objects, &hit_window, circle_diameter);
+    processor.process_misses(&mut beatmap_objects, f64::MAX, &hit_window);
+
+    let played = final_results(&beatmap_objects);
+    assert_eq!(played, vec![Some(Hit::X300); 6]);
+
+    // Recorded frames are already assisted, so they're watched without assists
+    let frames = processor.replay_frames().to_vec();
+    assert_eq!(assisted_results(frames, Mods::empty(), &beatmap), played);
+}
+
+#[test]
+fn test_slider_parts_judgements() {
+    let beatmap = Beatmap::from_path(
+        get_gameplay_tests_path().join("slider_with_ticks_and_reverse.osu")
+    ).unwrap();
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+
+    let mut processor = OsuProcessor::default();
+    for input in autoplay::generate(&beatmap_objects, 1.0) {
+        processor.store_input(input);
+    }
+
+    processor.process_all(
+        &mut beatmap_objects,
+        &hit_window,
+        calc_hitcircle_diameter(beatmap.circle_size),
+    );
+
+    let ObjectKind::Slider(slider) = &beatmap_objects[0].kind else {
+        panic!("should be slider");
+    };
+
+    let judgements = processor.take_judgements();
+
+    let mut expected = vec![JudgementKind::SliderHead(Hit::X300)];
+    for checkpoint in &slider.checkpoints {
+        expected.push(if checkpoint.is_reverse {
+            JudgementKind::SliderRepeat { hit: true }
+        } else {
+            JudgementKind::SliderTick { hit: true }
+        });
+    }
+    expected.push(JudgementKind::SliderEnd { hit: true });
+    expected.push(JudgementKind::Slider(Hit::X300));
+    expected.push(JudgementKind::ComboEnd(ComboResult::Geki));
+
+    assert_eq!(judgements.iter().map(|x| x.kind).collect::<Vec<_>>(), expected);
+
+    let end_pos = slider.end_pos();
+
+    assert_eq!((judgements[0].pos.x, judgements[0].pos.y), (slider.pos.x, slider.pos.y));
+    assert_eq!((judgements[1].pos.x, judgements[1].pos.y), (slider.checkpoints[0].pos.x, slider.checkpoints[0].pos.y));
+    assert_eq!((judgements.last().unwrap().pos.x, judgements.last().unwrap().pos.y), (end_pos.x, end_pos.y));
+}
+
+#[test]
+fn test_slider_break() {
+    let beatmap = Beatmap::from_path(
+        get_gameplay_tests_path().join("slider_with_ticks_and_reverse.osu")
+    ).unwrap();
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+    let hit_window = HitWindow::from_od(beatmap.overall_difficulty);
+    let circle_diameter = calc_hitcircle_diameter(beatmap.circle_size);
+
+    let ObjectKind::Slider(slider) = &beatmap_objects[0].kind else {
+        panic!("should be slider");
+    };
+
+    let first_tick = slider.checkpoints.iter()
+        .find(|x| !x.is_reverse)
+        .map(|x| x.time)
+        .expect("slider should have ticks");
+
+    // Releasing keys right before the first tick
+    // and pressing them again after it
+    let mut inputs = autoplay::generate(&beatmap_objects, 1.0);
+    let mut last_keys = KeyboardState::empty();
+
+    for input in &mut inputs {
+        if (first_tick - 50.0..=first_tick + 20.0).contains(&input.ts) {
+            input.keys = KeyboardState::empty();
+        }
+
+        input.hold = input.keys.intersection(&last_keys);
+        last_keys = input.keys;
+    }
+
+    let split_at = first_tick + 100.0;
+    let (before, after): (Vec<_>, Vec<_>) = inputs.into_iter()
+        .partition(|x| x.ts <= split_at);
+
+    let mut processor = OsuProcessor::default();
+    for input in before {
+        processor.store_input(input);
+    }
+
+    // Break is reported before the slider ends
+    processor.process(split_at, &mut beatmap_objects, &hit_window, circle_diameter);
+    let judgements = processor.take_judgements();
+
+    let breaks: Vec<_> = judgements.iter()
+        .filter(|x| x.is_slider_break())
+        .collect();
+
+    assert_eq!(breaks.len(), 1);
+    assert_eq!(breaks[0].at, first_tick);
+    assert!(judgements.iter().all(|x| x.final_hit().is_none()));
+
+    let mut score = ScoreProcessor::default();
+    score.apply_all(&judgements);
+
+    assert_eq!(score.slider_breaks, 1);
+    assert_eq!(score.combo, 0);
+
+    for input in after {
+        processor.store_input(input);
+    }
+
+    processor.process(f64::MAX, &mut beatmap_objects, &hit_window, circle_diameter);
+    score.apply_all(&processor.take_judgements());
+
+    // Everything else is still hit
+    assert_eq!(beatmap_objects[0].final_hit(), Some(Hit::X100));
+    assert_eq!(score.slider_breaks, 1);
+    assert!(score.combo > 0);
+}
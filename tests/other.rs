@@ -1,13 +1,63 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::{Duration, Instant}};
 
 use approx::assert_relative_eq;
-use rosu::hit_objects::{Object, ObjectKind};
+use cgmath::{InnerSpace, Vector2};
+use rosu::{gameplay_thread::{GameplayMessage, GameplaySettings, GameplayThread, GameplayUpdate}, hit_objects::{hit_window::HitWindow, ruleset::Ruleset, Hit, Object, ObjectKind}, math::{calc_hitcircle_diameter, OSU_COORDS_HEIGHT, OSU_COORDS_WIDTH}, mods::{self, DifficultyAdjust, Mods}, osu_input::{KeyboardState, OsuInput}, practice::{LoopResult, PracticeSection, PRACTICE_LEAD_IN, PRACTICE_LOOP_TAIL}, processor::{autoplay, results::PlayResults, score_processor::{Grade, ScoreProcessor}, OsuProcessor}, timer::ClockSnapshot, transforms::{self, MirrorAxis, ScaleIn, TransformSettings}};
 use rosu_map::Beatmap;
+use test_case::case;
 
 fn get_other_tests_path() -> PathBuf {
     PathBuf::from("tests/data/other/")
 }
 
+fn get_gameplay_tests_path() -> PathBuf {
+    PathBuf::from("tests/data/gameplay/")
+}
+
+fn load_beatmap(path: PathBuf) -> (Beatmap, Vec<Object>) {
+    let beatmap = Beatmap::from_path(path).unwrap();
+    let objects = Object::from_rosu(&beatmap);
+
+    (beatmap, objects)
+}
+
+/// Hit window and circle diameter, everything processor needs from the beatmap
+fn processing_params(beatmap: &Beatmap) -> (HitWindow, f32) {
+    (
+        HitWindow::from_od(beatmap.overall_difficulty),
+        calc_hitcircle_diameter(beatmap.circle_size),
+    )
+}
+
+/// Stores `inputs` and processes all of them
+fn process_inputs(
+    processor: &mut OsuProcessor,
+    inputs: impl IntoIterator<Item = OsuInput>,
+    beatmap: &Beatmap,
+    objects: &mut [Object],
+) {
+    let (hit_window, circle_diameter) = processing_params(beatmap);
+
+    for input in inputs {
+        processor.store_input(input);
+    }
+
+    processor.process_all(objects, &hit_window, circle_diameter);
+}
+
+/// Same as [`process_inputs`], but objects left without result are missed
+fn process_inputs_till_end(
+    processor: &mut OsuProcessor,
+    inputs: impl IntoIterator<Item = OsuInput>,
+    beatmap: &Beatmap,
+    objects: &mut [Object],
+) {
+    process_inputs(processor, inputs, beatmap, objects);
+
+    let (hit_window, _) = processing_params(beatmap);
+    processor.process_misses(objects, f64::MAX, &hit_window);
+}
+
 #[test]
 fn test_slider_slides_stuff() {
     let base = PathBuf::from("tests/data/gameplay/")
@@ -47,7 +97,7 @@ fn test_slider_ticks() {
     assert!(matches!(beatmap_objects[0].kind, ObjectKind::Slider(_)));
 
     match &beatmap_objects[0].kind {
-        ObjectKind::Circle(_) => panic!("should be slider"),
+        ObjectKind::Circle(_) | ObjectKind::Spinner(_) => panic!("should be slider"),
         ObjectKind::Slider(slider) => {
             assert_eq!(slider.ticks.len(), 3)
         },
@@ -65,7 +115,7 @@ fn test_slider_reverse_slides() {
     assert!(matches!(beatmap_objects[0].kind, ObjectKind::Slider(_)));
 
     match &beatmap_objects[0].kind {
-        ObjectKind::Circle(_) => panic!("should be slider"),
+        ObjectKind::Circle(_) | ObjectKind::Spinner(_) => panic!("should be slider"),
         ObjectKind::Slider(slider) => {
             assert_eq!(slider.checkpoints.len(), 8);
         },
@@ -85,7 +135,7 @@ fn test_slider_reverse_slides2() {
     assert!(matches!(beatmap_objects[1].kind, ObjectKind::Slider(_)));
 
     match &beatmap_objects[0].kind {
-        ObjectKind::Circle(_) => panic!("should be slider"),
+        ObjectKind::Circle(_) | ObjectKind::Spinner(_) => panic!("should be slider"),
         ObjectKind::Slider(slider) => {
             dbg!(&slider.checkpoints);
             assert_eq!(slider.checkpoints.len(), 7);
@@ -93,7 +143,7 @@ fn test_slider_reverse_slides2() {
     }
 
     match &beatmap_objects[1].kind {
-        ObjectKind::Circle(_) => panic!("should be slider"),
+        ObjectKind::Circle(_) | ObjectKind::Spinner(_) => panic!("should be slider"),
         ObjectKind::Slider(slider) => {
             dbg!(&slider.checkpoints);
             assert_eq!(slider.checkpoints.len(), 3);
@@ -102,3 +152,964 @@ fn test_slider_reverse_slides2() {
 }
 // 
 
+
+/// Feeds spinner with cursor going around it's center
+/// with `rpm` speed while K1 is held
+fn spin_inputs(start: f64, end: f64, rpm: f64) -> Vec<OsuInput> {
+    let mut inputs = Vec::new();
+    let radians_per_ms = rpm * 2.0 * std::f64::consts::PI / 60000.0;
+
+    let mut ts = start;
+    while ts <= end + 16.0 {
+        let angle = (ts - start) * radians_per_ms;
+
+        inputs.push(OsuInput {
+            ts,
+            pos: Vector2::new(256.0 + angle.cos() * 50.0, 192.0 + angle.sin() * 50.0),
+            keys: KeyboardState { k1: true, ..KeyboardState::empty() },
+            hold: KeyboardState { k1: ts > start, ..KeyboardState::empty() },
+        });
+
+        ts += 8.0;
+    }
+
+    inputs
+}
+
+#[test]
+fn test_spinner_required_spins() {
+    let (_, beatmap_objects) = load_beatmap(get_other_tests_path().join("spinner.osu"));
+
+    assert_eq!(beatmap_objects.len(), 1);
+
+    match &beatmap_objects[0].kind {
+        ObjectKind::Spinner(spinner) => {
+            assert_eq!(spinner.duration, 2000.0);
+            // OD5 => 5 spins per second
+            assert_eq!(spinner.required_spins, 10.0);
+        },
+        _ => panic!("should be spinner"),
+    }
+}
+
+#[case(400.0, Hit::X300; "full clear with bonus")]
+#[case(285.0, Hit::X100; "almost cleared")]
+#[case(240.0, Hit::X50; "barely spinning")]
+#[case(100.0, Hit::MISS; "too slow")]
+fn test_spinner_judgement(rpm: f64, expected: Hit) {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_other_tests_path().join("spinner.osu"));
+
+    let mut processor = OsuProcessor::default();
+    process_inputs(&mut processor, spin_inputs(1000.0, 3000.0, rpm), &beatmap, &mut beatmap_objects);
+
+    let ObjectKind::Spinner(spinner) = &beatmap_objects[0].kind else {
+        panic!("should be spinner");
+    };
+
+    let result = spinner.hit_result.as_ref().expect("spinner should be judged");
+    assert_eq!(result.result, expected);
+
+    if expected == Hit::X300 {
+        assert!(result.bonus_spins > 0);
+    }
+}
+
+#[test]
+fn test_spinner_rate_cap() {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_other_tests_path().join("spinner.osu"));
+
+    // Double time, spinning speed is capped in real time
+    let mut processor = OsuProcessor::default();
+    processor.set_rate(1.5);
+
+    process_inputs(&mut processor, spin_inputs(1000.0, 3000.0, 400.0), &beatmap, &mut beatmap_objects);
+
+    let ObjectKind::Spinner(spinner) = &beatmap_objects[0].kind else {
+        panic!("should be spinner");
+    };
+
+    let result = spinner.hit_result.as_ref().expect("spinner should be judged");
+
+    // 2000ms of beatmap time is ~1333ms of real time
+    assert!(result.spins > 10.0 && result.spins < 10.7, "{}", result.spins);
+    assert_eq!(result.result, Hit::X300);
+    assert_eq!(result.bonus_spins, 0);
+}
+
+fn press_at(ts: f64, x: f64, y: f64) -> [OsuInput; 2] {
+    [
+        OsuInput {
+            ts,
+            pos: Vector2::new(x, y),
+            keys: KeyboardState { k1: true, ..KeyboardState::empty() },
+            hold: KeyboardState { k1: false, ..KeyboardState::empty() },
+        },
+        OsuInput {
+            ts: ts + 1.0,
+            pos: Vector2::new(x, y),
+            keys: KeyboardState { k1: false, ..KeyboardState::empty() },
+            hold: KeyboardState { k1: false, ..KeyboardState::empty() },
+        },
+    ]
+}
+
+#[test]
+fn test_note_lock() {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_other_tests_path().join("note_lock.osu"));
+
+    let mut processor = OsuProcessor::default();
+
+    // Second circle is inside hit window, but first one
+    // is still hittable so press should be ignored
+    process_inputs(&mut processor, press_at(1000.0, 300.0, 100.0), &beatmap, &mut beatmap_objects);
+
+    assert!(beatmap_objects[0].shake_at.is_none());
+    assert_eq!(beatmap_objects[1].shake_at, Some(1000.0));
+
+    for object in &beatmap_objects {
+        let ObjectKind::Circle(circle) = &object.kind else {
+            panic!("should be circle");
+        };
+
+        assert!(circle.hit_result.is_none());
+    }
+
+    let inputs = press_at(1010.0, 100.0, 100.0)
+        .into_iter()
+        .chain(press_at(1050.0, 300.0, 100.0));
+
+    process_inputs(&mut processor, inputs, &beatmap, &mut beatmap_objects);
+
+    for object in &beatmap_objects {
+        let ObjectKind::Circle(circle) = &object.kind else {
+            panic!("should be circle");
+        };
+
+        let result = circle.hit_result.as_ref().expect("circle should be hit");
+        assert_eq!(result.result, Hit::X300);
+    }
+}
+
+#[case(Ruleset::Stable, 990.0, Some(990.0), [None, None]; "stable before start time")]
+#[case(Ruleset::Lazer, 990.0, Some(990.0), [None, None]; "lazer before start time")]
+#[case(Ruleset::Stable, 1020.0, Some(1020.0), [None, None]; "stable inside hit window")]
+#[case(Ruleset::Lazer, 1020.0, None, [Some(Hit::MISS), Some(Hit::X300)]; "lazer inside hit window")]
+fn test_note_lock_rulesets(
+    ruleset: Ruleset,
+    press: f64,
+    shake_at: Option<f64>,
+    expected: [Option<Hit>; 2],
+) {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_other_tests_path().join("note_lock.osu"));
+
+    let mut processor = OsuProcessor::default();
+    processor.set_ruleset(ruleset);
+
+    // Pressing the second circle while the first one wasn't hit
+    process_inputs(&mut processor, press_at(press, 300.0, 100.0), &beatmap, &mut beatmap_objects);
+
+    assert_eq!(beatmap_objects[1].shake_at, shake_at);
+
+    let results: Vec<_> = beatmap_objects.iter()
+        .map(|x| x.final_hit())
+        .collect();
+
+    assert_eq!(results, expected);
+}
+
+#[test]
+fn test_lazer_same_time_isnt_skipped() {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_other_tests_path().join("same_time.osu"));
+
+    let mut processor = OsuProcessor::default();
+    processor.set_ruleset(Ruleset::Lazer);
+
+    // Second circle is hit first, the other one
+    // starts at the same time and can still be hit
+    process_inputs(&mut processor, press_at(1000.0, 300.0, 100.0), &beatmap, &mut beatmap_objects);
+
+    assert_eq!(beatmap_objects[0].final_hit(), None);
+    assert_eq!(beatmap_objects[1].final_hit(), Some(Hit::X300));
+
+    process_inputs(&mut processor, press_at(1010.0, 100.0, 100.0), &beatmap, &mut beatmap_objects);
+
+    assert_eq!(beatmap_objects[0].final_hit(), Some(Hit::X300));
+}
+
+#[case("stacks_v14.osu"; "current stacking")]
+#[case("stacks_v5.osu"; "legacy stacking")]
+fn test_stacking(beatmap: &str) {
+    let (beatmap, beatmap_objects) = load_beatmap(get_other_tests_path().join(beatmap));
+
+    assert_eq!(beatmap_objects.len(), 5);
+
+    let stack_offset = calc_hitcircle_diameter(beatmap.circle_size) / 20.0;
+
+    let positions: Vec<(f32, f32)> = beatmap_objects.iter()
+        .map(|object| match &object.kind {
+            ObjectKind::Circle(circle) => (circle.pos.x, circle.pos.y),
+            ObjectKind::Slider(slider) => (slider.pos.x, slider.pos.y),
+            ObjectKind::Spinner(_) => panic!("there are no spinners"),
+        })
+        .collect();
+
+    // Stack of three grows to the top left
+    assert_relative_eq!(positions[0].0, 256.0 - stack_offset * 2.0, max_relative = 0.001);
+    assert_relative_eq!(positions[0].1, 192.0 - stack_offset * 2.0, max_relative = 0.001);
+    assert_relative_eq!(positions[1].0, 256.0 - stack_offset, max_relative = 0.001);
+    assert_relative_eq!(positions[2].0, 256.0, max_relative = 0.001);
+
+    // Circle placed on the slider end goes to the bottom right
+    assert_relative_eq!(positions[3].0, 100.0, max_relative = 0.001);
+    assert_relative_eq!(positions[4].0, 200.0 + stack_offset, max_relative = 0.001);
+    assert_relative_eq!(positions[4].1, 100.0 + stack_offset, max_relative = 0.001);
+}
+
+#[test]
+fn test_time_driven_misses() {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_other_tests_path().join("note_lock.osu"));
+    let (hit_window, _) = processing_params(&beatmap);
+
+    let mut processor = OsuProcessor::default();
+
+    // Cursor is moving around without any presses
+    let input = OsuInput {
+        ts: 1100.0,
+        pos: Vector2::new(100.0, 100.0),
+        keys: KeyboardState::empty(),
+        hold: KeyboardState::empty(),
+    };
+
+    process_inputs(&mut processor, [input], &beatmap, &mut beatmap_objects);
+
+    let ObjectKind::Circle(circle) = &beatmap_objects[0].kind else {
+        panic!("should be circle");
+    };
+    assert!(circle.hit_result.is_none());
+
+    // Without any inputs clock alone is enough
+    processor.process_misses(&mut beatmap_objects, 1300.0, &hit_window);
+
+    for object in &beatmap_objects {
+        let ObjectKind::Circle(circle) = &object.kind else {
+            panic!("should be circle");
+        };
+
+        let result = circle.hit_result.as_ref().expect("circle should be missed");
+        assert_eq!(result.result, Hit::MISS);
+        assert_eq!(result.at, circle.start_time + hit_window.x50.round());
+    }
+}
+
+#[case(-500.0, None; "before miss window")]
+#[case(-300.0, Some(Hit::MISS); "inside miss window")]
+#[case(-60.0, Some(Hit::X100); "inside hit window")]
+fn test_early_click_circle(offset: f64, expected: Option<Hit>) {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_other_tests_path().join("early_click.osu"));
+
+    let mut processor = OsuProcessor::default();
+    process_inputs(&mut processor, press_at(1000.0 + offset, 256.0, 192.0), &beatmap, &mut beatmap_objects);
+
+    let ObjectKind::Circle(circle) = &beatmap_objects[0].kind else {
+        panic!("should be circle");
+    };
+
+    assert_eq!(circle.hit_result.as_ref().map(|x| x.result), expected);
+}
+
+#[case(-500.0, Ruleset::Stable, None; "before miss window")]
+#[case(-300.0, Ruleset::Stable, Some(Hit::MISS); "inside miss window")]
+#[case(-60.0, Ruleset::Stable, Some(Hit::X300); "inside hit window")]
+#[case(-300.0, Ruleset::Lazer, Some(Hit::MISS); "lazer inside miss window")]
+#[case(-60.0, Ruleset::Lazer, Some(Hit::X100); "lazer inside hit window")]
+#[case(-30.0, Ruleset::Lazer, Some(Hit::X300); "lazer perfect hit")]
+fn test_early_click_slider_head(offset: f64, ruleset: Ruleset, expected: Option<Hit>) {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_other_tests_path().join("early_click.osu"));
+    let (hit_window, _) = processing_params(&beatmap);
+
+    // Circle shouldn't note lock the slider
+    if let ObjectKind::Circle(circle) = &mut beatmap_objects[0].kind {
+        circle.update_miss(f64::MAX, &hit_window, Ruleset::Stable);
+    }
+
+    let mut processor = OsuProcessor::default();
+    processor.set_ruleset(ruleset);
+
+    process_inputs(&mut processor, press_at(3000.0 + offset, 100.0, 100.0), &beatmap, &mut beatmap_objects);
+
+    let ObjectKind::Slider(slider) = &beatmap_objects[1].kind else {
+        panic!("should be slider");
+    };
+
+    assert_eq!(slider.hit_result.as_ref().map(|x| x.head.result), expected);
+}
+
+#[test]
+fn test_hard_rock_flip() {
+    let (beatmap, nomod_objects) = load_beatmap(get_other_tests_path().join("hard_rock.osu"));
+
+    let mut hard_rock = beatmap.clone();
+    mods::apply_to_beatmap(&mut hard_rock, Mods::HARD_ROCK, &DifficultyAdjust::default());
+    let hard_rock_objects = Object::from_rosu(&hard_rock);
+
+    let (ObjectKind::Circle(nomod), ObjectKind::Circle(flipped)) = (&nomod_objects[0].kind, &hard_rock_objects[0].kind) else {
+        panic!("should be circles");
+    };
+
+    assert_eq!(flipped.pos.x, nomod.pos.x);
+    assert_eq!(flipped.pos.y, OSU_COORDS_HEIGHT - nomod.pos.y);
+
+    let (ObjectKind::Slider(nomod), ObjectKind::Slider(flipped)) = (&nomod_objects[1].kind, &hard_rock_objects[1].kind) else {
+        panic!("should be sliders");
+    };
+
+    assert_eq!(flipped.pos.y, OSU_COORDS_HEIGHT - nomod.pos.y);
+    assert_relative_eq!(flipped.end_pos().x, nomod.end_pos().x, max_relative = 0.001);
+    assert_relative_eq!(flipped.end_pos().y, OSU_COORDS_HEIGHT - nomod.end_pos().y, max_relative = 0.001);
+
+    assert!(!nomod.ticks.is_empty());
+    assert_eq!(flipped.ticks.len(), nomod.ticks.len());
+
+    for (flipped, nomod) in flipped.ticks.iter().zip(&nomod.ticks) {
+        assert_relative_eq!(flipped.pos.x, nomod.pos.x, max_relative = 0.001);
+        assert_relative_eq!(flipped.pos.y, OSU_COORDS_HEIGHT - nomod.pos.y, max_relative = 0.001);
+    }
+}
+
+#[case(Mods::empty(), DifficultyAdjust::default(), [4.0, 5.0, 5.0, 5.0]; "no mods")]
+#[case(Mods::HARD_ROCK, DifficultyAdjust::default(), [5.2, 7.0, 7.0, 7.0]; "hard rock")]
+#[case(Mods::EASY, DifficultyAdjust::default(), [2.0, 2.5, 2.5, 2.5]; "easy")]
+#[case(
+    Mods::HARD_ROCK,
+    DifficultyAdjust { approach_rate: Some(10.0), overall_difficulty: Some(2.0), ..Default::default() },
+    [5.2, 10.0, 2.0, 7.0];
+    "difficulty adjust overrides hard rock"
+)]
+fn test_difficulty_mods(mods_to_apply: Mods, adjust: DifficultyAdjust, expected: [f32; 4]) {
+    let base = get_other_tests_path().join("hard_rock.osu");
+
+    let mut beatmap = Beatmap::from_path(base).unwrap();
+    mods::apply_to_difficulty(&mut beatmap, mods_to_apply, &adjust);
+
+    assert_relative_eq!(beatmap.circle_size, expected[0], max_relative = 0.001);
+    assert_relative_eq!(beatmap.approach_rate, expected[1], max_relative = 0.001);
+    assert_relative_eq!(beatmap.overall_difficulty, expected[2], max_relative = 0.001);
+    assert_relative_eq!(beatmap.hp_drain_rate, expected[3], max_relative = 0.001);
+}
+
+#[case(Mods::HIDDEN, Mods::HARD_ROCK, Mods::HIDDEN | Mods::HARD_ROCK; "compatible")]
+#[case(Mods::HARD_ROCK, Mods::EASY, Mods::EASY; "easy replaces hard rock")]
+#[case(Mods::HALF_TIME, Mods::NIGHTCORE, Mods::DOUBLE_TIME | Mods::NIGHTCORE; "nightcore enables double time")]
+#[case(Mods::DOUBLE_TIME | Mods::NIGHTCORE, Mods::DOUBLE_TIME, Mods::empty(); "double time disables nightcore")]
+#[case(Mods::NO_FAIL, Mods::PERFECT, Mods::SUDDEN_DEATH | Mods::PERFECT; "perfect replaces no fail")]
+#[case(Mods::RELAX | Mods::HIDDEN, Mods::AUTOPLAY, Mods::AUTOPLAY | Mods::HIDDEN; "autoplay replaces relax")]
+fn test_toggle_mods(current: Mods, toggled: Mods, expected: Mods) {
+    assert_eq!(current.toggled(toggled), expected);
+}
+
+#[case(1.0; "no mod")]
+#[case(1.5; "double time")]
+fn test_autoplay_spinner(rate: f64) {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_other_tests_path().join("spinner.osu"));
+
+    let mut processor = OsuProcessor::default();
+    processor.set_rate(rate);
+
+    let inputs = autoplay::generate(&beatmap_objects, rate);
+    process_inputs(&mut processor, inputs, &beatmap, &mut beatmap_objects);
+
+    let ObjectKind::Spinner(spinner) = &beatmap_objects[0].kind else {
+        panic!("should be spinner");
+    };
+
+    let result = spinner.hit_result.as_ref().expect("spinner should be judged");
+    assert_eq!(result.result, Hit::X300);
+}
+
+/// Autoplay positions with all keys released
+fn cursor_only_inputs(objects: &[Object]) -> Vec<OsuInput> {
+    autoplay::generate(objects, 1.0)
+        .into_iter()
+        .map(|x| OsuInput {
+            keys: KeyboardState::empty(),
+            hold: KeyboardState::empty(),
+            ..x
+        })
+        .collect()
+}
+
+#[test]
+fn test_relax() {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+
+    let mut processor = OsuProcessor::default();
+    processor.set_assists(Mods::RELAX);
+
+    let inputs = cursor_only_inputs(&beatmap_objects);
+    process_inputs_till_end(&mut processor, inputs, &beatmap, &mut beatmap_objects);
+
+    for object in &beatmap_objects {
+        assert_eq!(object.final_hit(), Some(Hit::X300), "object at {}", object.start_time);
+    }
+}
+
+#[test]
+fn test_autopilot() {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+
+    let mut processor = OsuProcessor::default();
+    processor.set_assists(Mods::AUTOPILOT | Mods::HIDDEN);
+    assert_eq!(processor.assists(), Mods::AUTOPILOT);
+
+    // Player only presses keys, cursor stays in the corner
+    let inputs: Vec<_> = autoplay::generate(&beatmap_objects, 1.0)
+        .into_iter()
+        .map(|x| OsuInput {
+            pos: Vector2::new(0.0, 0.0),
+            ..x
+        })
+        .collect();
+
+    process_inputs_till_end(&mut processor, inputs, &beatmap, &mut beatmap_objects);
+
+    for object in &beatmap_objects {
+        assert_eq!(object.final_hit(), Some(Hit::X300), "object at {}", object.start_time);
+    }
+}
+
+#[case(MirrorAxis::Horizontal, true, false)]
+#[case(MirrorAxis::Vertical, false, true)]
+#[case(MirrorAxis::Both, true, true)]
+fn test_mirror(axis: MirrorAxis, flip_x: bool, flip_y: bool) {
+    let (beatmap, nomod_objects) = load_beatmap(get_other_tests_path().join("hard_rock.osu"));
+
+    let settings = TransformSettings {
+        mirror: Some(axis),
+        ..Default::default()
+    };
+    let mirrored_objects = Object::from_rosu_transformed(&beatmap, &settings.pipeline(Mods::empty()));
+
+    let expected = |x: f32, y: f32| (
+        if flip_x { OSU_COORDS_WIDTH - x } else { x },
+        if flip_y { OSU_COORDS_HEIGHT - y } else { y },
+    );
+
+    let (ObjectKind::Circle(nomod), ObjectKind::Circle(mirrored)) = (&nomod_objects[0].kind, &mirrored_objects[0].kind) else {
+        panic!("should be circles");
+    };
+
+    assert_eq!((mirrored.pos.x, mirrored.pos.y), expected(nomod.pos.x, nomod.pos.y));
+
+    let (ObjectKind::Slider(nomod), ObjectKind::Slider(mirrored)) = (&nomod_objects[1].kind, &mirrored_objects[1].kind) else {
+        panic!("should be sliders");
+    };
+
+    assert_eq!((mirrored.pos.x, mirrored.pos.y), expected(nomod.pos.x, nomod.pos.y));
+
+    let (x, y) = expected(nomod.end_pos().x, nomod.end_pos().y);
+    assert_relative_eq!(mirrored.end_pos().x, x, max_relative = 0.001);
+    assert_relative_eq!(mirrored.end_pos().y, y, max_relative = 0.001);
+
+    assert!(!nomod.checkpoints.is_empty());
+    assert_eq!(mirrored.ticks.len(), nomod.ticks.len());
+    assert_eq!(mirrored.checkpoints.len(), nomod.checkpoints.len());
+
+    let ticks = mirrored.ticks.iter().zip(&nomod.ticks);
+    let checkpoints = mirrored.checkpoints.iter().zip(&nomod.checkpoints);
+
+    for (mirrored, nomod) in ticks.chain(checkpoints) {
+        let (x, y) = expected(nomod.pos.x, nomod.pos.y);
+
+        assert_eq!(mirrored.time, nomod.time);
+        assert_relative_eq!(mirrored.pos.x, x, max_relative = 0.001);
+        assert_relative_eq!(mirrored.pos.y, y, max_relative = 0.001);
+    }
+}
+
+#[test]
+fn test_mirror_mod_is_horizontal() {
+    let (beatmap, nomod_objects) = load_beatmap(get_other_tests_path().join("hard_rock.osu"));
+    let mirrored_objects = Object::from_rosu_transformed(
+        &beatmap,
+        &TransformSettings::default().pipeline(Mods::MIRROR),
+    );
+
+    let (ObjectKind::Circle(nomod), ObjectKind::Circle(mirrored)) = (&nomod_objects[0].kind, &mirrored_objects[0].kind) else {
+        panic!("should be circles");
+    };
+
+    assert_eq!(mirrored.pos.x, OSU_COORDS_WIDTH - nomod.pos.x);
+    assert_eq!(mirrored.pos.y, nomod.pos.y);
+}
+
+#[case(ScaleIn::Grow, 0.0, 0.5; "grow before appearing")]
+#[case(ScaleIn::Grow, 400.0, 0.5; "grow appears")]
+#[case(ScaleIn::Grow, 700.0, 0.75; "grow halfway")]
+#[case(ScaleIn::Grow, 1000.0, 1.0; "grow at start time")]
+#[case(ScaleIn::Deflate, 400.0, 2.0; "deflate appears")]
+#[case(ScaleIn::Deflate, 700.0, 1.5; "deflate halfway")]
+#[case(ScaleIn::Deflate, 1200.0, 1.0; "deflate after start time")]
+fn test_scale_in(mode: ScaleIn, time: f64, expected: f64) {
+    assert_relative_eq!(transforms::scale_in(mode, time, 1000.0, 600.0), expected);
+}
+
+#[test]
+fn test_wiggle() {
+    let (start_time, preempt) = (1000.0, 600.0);
+
+    assert_eq!(transforms::wiggle_offset(300.0, start_time, preempt), Vector2::new(0.0, 0.0));
+    assert_eq!(transforms::wiggle_offset(1000.0, start_time, preempt), Vector2::new(0.0, 0.0));
+
+    let mut wiggled = false;
+
+    for time in (400..1000).step_by(10) {
+        let time = time as f64;
+        let offset = transforms::wiggle_offset(time, start_time, preempt);
+
+        // 10 osu!pixels at most, settles down while approaching
+        let max_offset = 10.0 * (1.0 - (time - 400.0) / preempt) as f32;
+        assert!(offset.magnitude() <= max_offset + 1e-3, "{time}: {offset:?}");
+
+        // Same every frame
+        assert_eq!(offset, transforms::wiggle_offset(time, start_time, preempt));
+
+        wiggled |= offset.magnitude() > 0.1;
+    }
+
+    assert!(wiggled);
+}
+
+fn random_positions(beatmap: &Beatmap, seed: u64) -> Vec<(f32, f32)> {
+    let settings = TransformSettings {
+        random_seed: Some(seed),
+        ..Default::default()
+    };
+
+    Object::from_rosu_transformed(beatmap, &settings.pipeline(Mods::empty()))
+        .iter()
+        .filter_map(|object| match &object.kind {
+            ObjectKind::Circle(circle) => Some((circle.pos.x, circle.pos.y)),
+            ObjectKind::Slider(slider) => Some((slider.pos.x, slider.pos.y)),
+            ObjectKind::Spinner(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_random_seed() {
+    let (beatmap, _) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+
+    let positions = random_positions(&beatmap, 727);
+
+    assert_eq!(positions, random_positions(&beatmap, 727));
+    assert_ne!(positions, random_positions(&beatmap, 1337));
+
+    // Stack offsets can move objects a bit outside
+    for (x, y) in positions {
+        assert!((-10.0..=OSU_COORDS_WIDTH).contains(&x), "{x}");
+        assert!((-10.0..=OSU_COORDS_HEIGHT).contains(&y), "{y}");
+    }
+}
+
+#[case(1)]
+#[case(727)]
+#[case(1337)]
+fn test_random_sliders_inside_playfield(seed: u64) {
+    let (beatmap, _) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+
+    let settings = TransformSettings {
+        random_seed: Some(seed),
+        ..Default::default()
+    };
+
+    let objects = Object::from_rosu_transformed(&beatmap, &settings.pipeline(Mods::empty()));
+
+    // Stack offsets can move objects a bit outside
+    for object in &objects {
+        let ObjectKind::Slider(slider) = &object.kind else {
+            continue;
+        };
+
+        let bounds = slider.bounding_box(0.0);
+
+        assert!(bounds.top_left.x >= -10.0 && bounds.top_left.y >= -10.0, "slider at {}", object.start_time);
+        assert!(bounds.bottom_right.x <= OSU_COORDS_WIDTH + 0.01, "slider at {}", object.start_time);
+        assert!(bounds.bottom_right.y <= OSU_COORDS_HEIGHT + 0.01, "slider at {}", object.start_time);
+    }
+}
+
+#[case(1)]
+#[case(727)]
+#[case(1337)]
+fn test_random_autoplay(seed: u64) {
+    let (beatmap, _) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+
+    let settings = TransformSettings {
+        random_seed: Some(seed),
+        ..Default::default()
+    };
+    let mut beatmap_objects = Object::from_rosu_transformed(&beatmap, &settings.pipeline(Mods::empty()));
+
+    // Checkpoints are following the moved curve,
+    // so autoplay is still getting everything
+    let mut processor = OsuProcessor::default();
+    let inputs = autoplay::generate(&beatmap_objects, 1.0);
+    process_inputs_till_end(&mut processor, inputs, &beatmap, &mut beatmap_objects);
+
+    for object in &beatmap_objects {
+        assert_eq!(object.final_hit(), Some(Hit::X300), "object at {}", object.start_time);
+    }
+}
+
+#[test]
+fn test_autoplay_results() {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+    let (hit_window, _) = processing_params(&beatmap);
+
+    let mut processor = OsuProcessor::default();
+    let inputs = autoplay::generate(&beatmap_objects, 1.0);
+    process_inputs_till_end(&mut processor, inputs, &beatmap, &mut beatmap_objects);
+
+    let mut score = ScoreProcessor::new(&beatmap, &beatmap_objects);
+    score.apply_all(&processor.take_judgements());
+
+    let results = PlayResults::new(&score, &beatmap_objects, &hit_window, 1.0);
+
+    let heads = beatmap_objects.iter()
+        .filter(|x| !matches!(x.kind, ObjectKind::Spinner(_)))
+        .count();
+
+    assert_eq!(results.hit_errors.len(), heads);
+    assert_eq!(results.grade, Grade::SS);
+    assert_eq!(results.xmiss, 0);
+    assert!(results.miss_times.is_empty());
+    assert!(results.slider_break_times.is_empty());
+
+    // Autoplay hits everything right on time
+    for error in &results.hit_errors {
+        assert!(error.abs() <= results.hit_window.x300, "{error}");
+    }
+    assert_eq!(results.histogram(10).iter().sum::<u32>(), heads as u32);
+}
+
+fn gameplay_settings(beatmap: &Beatmap, autoplay_inputs: Vec<OsuInput>) -> GameplaySettings {
+    let (hit_window, circle_diameter) = processing_params(beatmap);
+
+    GameplaySettings {
+        hit_window,
+        circle_diameter,
+        rate: 1.0,
+        ruleset: Ruleset::Stable,
+        assists: Mods::empty(),
+        autoplay_inputs,
+    }
+}
+
+/// Applies published updates to `objects` until all of them are finished
+fn receive_until_finished(gameplay: &GameplayThread, objects: &mut [Object]) -> Vec<GameplayUpdate> {
+    let timeout = Instant::now() + Duration::from_secs(5);
+    let mut updates = Vec::new();
+
+    while !objects.iter().all(|x| x.is_finished()) {
+        assert!(Instant::now() < timeout, "gameplay thread didn't finish the objects");
+
+        for update in gameplay.updates() {
+            for (i, state) in &update.objects {
+                objects[*i].set_state(state.clone());
+            }
+
+            updates.push(update);
+        }
+
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    updates
+}
+
+#[test]
+fn test_gameplay_thread_autoplay() {
+    let (beatmap, objects) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+    let inputs = autoplay::generate(&objects, 1.0);
+    let end_time = objects.last().unwrap().end_time();
+
+    // Clock is already past the whole beatmap
+    let clock = ClockSnapshot {
+        at: Instant::now(),
+        time: end_time + 1000.0,
+        rate: 1.0,
+        paused: false,
+    };
+
+    let gameplay = GameplayThread::spawn(objects, gameplay_settings(&beatmap, inputs), clock);
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+    let updates = receive_until_finished(&gameplay, &mut beatmap_objects);
+
+    for object in &beatmap_objects {
+        assert_eq!(object.final_hit(), Some(Hit::X300), "object at {}", object.start_time);
+    }
+
+    let finished = updates.iter()
+        .flat_map(|x| &x.judgements)
+        .filter(|x| x.final_hit().is_some())
+        .count();
+
+    assert_eq!(finished, beatmap_objects.len());
+
+    let replay = updates.iter()
+        .find_map(|x| x.replay.as_ref())
+        .expect("replay should be published once everything is finished");
+
+    assert!(!replay.is_empty());
+    assert_eq!(updates.iter().filter(|x| x.replay.is_some()).count(), 1);
+}
+
+#[test]
+fn test_gameplay_thread_input_timestamps() {
+    let (beatmap, objects) = load_beatmap(get_gameplay_tests_path().join("single_hit_circle.osu"));
+
+    // OD 1.8: x300 60.8, x100 114.4, x50 168
+    let start_time = 1995.0;
+    let now = Instant::now();
+
+    // Clock is slowed down, so the circle can't be missed
+    // before the inputs reach the thread even on a busy machine
+    let clock = ClockSnapshot {
+        at: now,
+        time: start_time + 80.0,
+        rate: 0.01,
+        paused: false,
+    };
+
+    let gameplay = GameplayThread::spawn(objects, gameplay_settings(&beatmap, Vec::new()), clock);
+
+    // Inputs are already in the past of the clock, they're judged
+    // by the time they were stamped with, not by the time
+    // they are processed at
+    let pressed_at = now - Duration::from_millis(1000);
+    let keys = KeyboardState { k1: true, ..KeyboardState::empty() };
+
+    gameplay.send(GameplayMessage::CursorMoved(pressed_at, Vector2::new(387.0, 104.0)));
+    gameplay.send(GameplayMessage::Pressed(pressed_at, keys));
+    gameplay.send(GameplayMessage::Released(pressed_at + Duration::from_millis(500), keys));
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+    receive_until_finished(&gameplay, &mut beatmap_objects);
+
+    let ObjectKind::Circle(circle) = &beatmap_objects[0].kind else {
+        panic!("should be circle");
+    };
+
+    let result = circle.hit_result.as_ref().expect("circle should be hit");
+    assert_relative_eq!(result.at, start_time + 70.0, epsilon = 0.001);
+    assert_eq!(result.result, Hit::X100);
+}
+
+#[test]
+fn test_processor_restart_from() {
+    let (beatmap, mut beatmap_objects) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+    let (hit_window, circle_diameter) = processing_params(&beatmap);
+    let inputs = autoplay::generate(&beatmap_objects, 1.0);
+    let end_time = beatmap_objects.last().unwrap().end_time();
+
+    let mut processor = OsuProcessor::default();
+
+    for input in &inputs {
+        processor.store_input(input.clone());
+        processor.process(input.ts, &mut beatmap_objects, &hit_window, circle_diameter);
+    }
+
+    processor.process(end_time + 1000.0, &mut beatmap_objects, &hit_window, circle_diameter);
+    processor.take_judgements();
+
+    // Second half of the beatmap is played again
+    let section = PracticeSection::new(beatmap_objects[beatmap_objects.len() / 2].start_time, None);
+    let first = section.objects(&beatmap_objects).start;
+    assert_eq!(first, beatmap_objects.len() / 2);
+
+    for object in beatmap_objects.iter_mut().skip(first) {
+        object.reset_state();
+    }
+
+    processor.restart_from(first);
+    assert!(processor.replay_frames().is_empty());
+
+    let loop_start = section.loop_start(1200.0);
+
+    for input in inputs.iter().filter(|x| x.ts >= loop_start) {
+        processor.store_input(input.clone());
+        processor.process(input.ts, &mut beatmap_objects, &hit_window, circle_diameter);
+    }
+
+    processor.process(end_time + 1000.0, &mut beatmap_objects, &hit_window, circle_diameter);
+
+    // Objects before the section are neither judged nor missed again
+    let judgements = processor.take_judgements();
+    assert!(!judgements.is_empty());
+    assert!(judgements.iter().all(|x| x.object >= first));
+
+    for object in &beatmap_objects {
+        assert_eq!(object.final_hit(), Some(Hit::X300), "object at {}", object.start_time);
+    }
+
+    let result = LoopResult::from_objects(&beatmap_objects[section.objects(&beatmap_objects)]);
+    assert_eq!(result.x300 as usize, beatmap_objects.len() - first);
+    assert_eq!(result.accuracy(), 1.0);
+}
+
+#[test]
+fn test_practice_loop_end() {
+    let (_, beatmap_objects) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+
+    // Section ends right after the start of the second object
+    let start = beatmap_objects[0].start_time;
+    let end = beatmap_objects[1].start_time + 1.0;
+    let section = PracticeSection::new(start, Some(end));
+
+    assert_eq!(section.objects(&beatmap_objects), 0..2);
+
+    // Second object is played till it's end
+    let expected = beatmap_objects[1].end_time().max(end) + PRACTICE_LOOP_TAIL;
+    assert_eq!(section.loop_end(&beatmap_objects), Some(expected));
+}
+
+#[test]
+fn test_gameplay_thread_restart() {
+    let (beatmap, objects) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+    let inputs = autoplay::generate(&objects, 1.0);
+    let end_time = objects.last().unwrap().end_time();
+    let first = objects.len() / 2;
+
+    let clock = ClockSnapshot {
+        at: Instant::now(),
+        time: end_time + 1000.0,
+        rate: 1.0,
+        paused: false,
+    };
+
+    let mut gameplay = GameplayThread::spawn(objects, gameplay_settings(&beatmap, inputs), clock);
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+    receive_until_finished(&gameplay, &mut beatmap_objects);
+
+    for object in beatmap_objects.iter_mut().skip(first) {
+        object.reset_state();
+    }
+
+    let loop_start = beatmap_objects[first].start_time - PRACTICE_LEAD_IN;
+
+    // Clock is moved to the lead-in of the section, and
+    // then past the whole beatmap, autoplay picks up from there
+    let clock = ClockSnapshot {
+        at: Instant::now() - Duration::from_millis((end_time + 1000.0 - loop_start) as u64),
+        time: loop_start,
+        rate: 1.0,
+        paused: false,
+    };
+
+    gameplay.restart(first, clock);
+
+    let updates = receive_until_finished(&gameplay, &mut beatmap_objects);
+
+    for object in &beatmap_objects {
+        assert_eq!(object.final_hit(), Some(Hit::X300), "object at {}", object.start_time);
+    }
+
+    let judged = updates.iter()
+        .flat_map(|x| &x.judgements)
+        .filter(|x| x.final_hit().is_some())
+        .map(|x| x.object)
+        .collect::<Vec<_>>();
+
+    assert_eq!(judged.len(), beatmap_objects.len() - first);
+    assert!(judged.iter().all(|x| *x >= first));
+    assert_eq!(updates.iter().filter(|x| x.replay.is_some()).count(), 1);
+}
+
+#[test]
+fn test_gameplay_thread_retry_mid_map() {
+    let (beatmap, objects) = load_beatmap(get_gameplay_tests_path().join("sliders_and_jumps.osu"));
+    let inputs = autoplay::generate(&objects, 1.0);
+    let end_time = objects.last().unwrap().end_time();
+    let start_time = objects[0].start_time - 1000.0;
+
+    // Retried while the previous play was in the middle of the beatmap,
+    // thread of the new play waits until it's clock is synced
+    let gameplay = GameplayThread::spawn(objects, gameplay_settings(&beatmap, inputs), ClockSnapshot::paused_at(start_time));
+
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(gameplay.updates().count(), 0);
+
+    // New play is started and runs past the whole beatmap
+    let clock = ClockSnapshot {
+        at: Instant::now() - Duration::from_millis((end_time + 1000.0 - start_time) as u64),
+        time: start_time,
+        rate: 1.0,
+        paused: false,
+    };
+
+    gameplay.sync_clock(clock);
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+    let updates = receive_until_finished(&gameplay, &mut beatmap_objects);
+
+    // Nothing was missed with the time of the previous play
+    for object in &beatmap_objects {
+        assert_eq!(object.final_hit(), Some(Hit::X300), "object at {}", object.start_time);
+    }
+
+    let finished = updates.iter()
+        .flat_map(|x| &x.judgements)
+        .filter(|x| x.final_hit().is_some())
+        .count();
+
+    assert_eq!(finished, beatmap_objects.len());
+}
+
+#[test]
+fn test_gameplay_thread_waits_for_every_object() {
+    let (beatmap, objects) = load_beatmap(get_other_tests_path().join("circle_during_slider.osu"));
+    let slider_end = objects[0].end_time();
+    let circle_start = objects[1].start_time;
+
+    // Last circle is already missed, but the slider is still going
+    let clock = ClockSnapshot {
+        at: Instant::now(),
+        time: circle_start + 300.0,
+        rate: 0.01,
+        paused: false,
+    };
+    assert!(clock.time < slider_end);
+
+    let gameplay = GameplayThread::spawn(objects, gameplay_settings(&beatmap, Vec::new()), clock);
+
+    let mut beatmap_objects = Object::from_rosu(&beatmap);
+    let timeout = Instant::now() + Duration::from_secs(5);
+
+    while !beatmap_objects[1].is_finished() {
+        assert!(Instant::now() < timeout, "circle should be missed");
+
+        for update in gameplay.updates() {
+            assert!(update.replay.is_none(), "replay is published before the slider ends");
+
+            for (i, state) in &update.objects {
+                beatmap_objects[*i].set_state(state.clone());
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert!(!beatmap_objects[0].is_finished());
+
+    gameplay.sync_clock(ClockSnapshot {
+        at: Instant::now(),
+        time: slider_end + 1000.0,
+        rate: 1.0,
+        paused: false,
+    });
+
+    let updates = receive_until_finished(&gameplay, &mut beatmap_objects);
+    assert_eq!(updates.iter().filter(|x| x.replay.is_some()).count(), 1);
+}
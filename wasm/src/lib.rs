@@ -12,11 +12,13 @@ use winit::event_loop::{ControlFlow, EventLoopProxy};
 use winit::window::Window;
 use winit::{event_loop::EventLoop, platform::web::WindowAttributesExtWebSys};
 use rosu::hit_objects::ObjectKind;
-use rosu::{math::calculate_preempt_fadein, config::Config, graphics::Graphics, osu_renderer::OsuRenderer};
+use rosu::{math::{calc_hitcircle_diameter, calculate_preempt_fadein}, config::Config, graphics::Graphics, osu_renderer::OsuRenderer};
 use std::sync::{Arc, RwLock};
 use rosu::skin_manager::SkinManager;
 use rosu::hit_objects::Object;
 use rosu::hit_objects::hit_window::HitWindow;
+use rosu::osu_input::OsuInput;
+use rosu::processor::{autoplay, OsuProcessor};
 use winit::platform::web::WindowExtWebSys;
 use wasm_bindgen_futures::spawn_local;
 use web_time::{Instant};
@@ -33,7 +35,13 @@ struct OsuWasmState<'ows> {
     current_preempt: f32,
     current_fadein: f32,
     current_hit_window: HitWindow,
+    current_hit_circle_diameter: f32,
     last_frame_ts: Instant,
+
+    // Demo is played by autoplay
+    processor: OsuProcessor,
+    autoplay_inputs: Vec<OsuInput>,
+    autoplay_index: usize,
 }
 
 impl<'ows> OsuWasmState<'ows> {
@@ -49,6 +57,11 @@ impl<'ows> OsuWasmState<'ows> {
         self.current_preempt = preempt;
         self.current_fadein = fadein;
         self.current_hit_window = hit_window;
+        self.current_hit_circle_diameter = calc_hitcircle_diameter(cs);
+
+        self.processor = OsuProcessor::default();
+        self.autoplay_inputs = autoplay::generate(&self.objects, 1.0);
+        self.autoplay_index = 0;
 
         self.clock.reset_time();
         self.objects_render_queue.clear();
@@ -65,6 +78,22 @@ impl<'ows> OsuWasmState<'ows> {
 
         let time = self.clock.update();
 
+        while let Some(input) = self.autoplay_inputs.get(self.autoplay_index) {
+            if input.ts > time {
+                break;
+            }
+
+            self.processor.store_input(input.clone());
+            self.autoplay_index += 1;
+        }
+
+        self.processor.process(
+            time,
+            &mut self.objects,
+            &self.current_hit_window,
+            self.current_hit_circle_diameter,
+        );
+
         // TODO: For now i'm just copied it from
         // OsuState, for the future i probably 
         // needed to keep them in sync :)
@@ -330,6 +359,71 @@ async fn initialize_skin(graphics: &Graphics<'_>) -> SkinManager {
 
     info!("Fetched empty.png");
 
+    let spinner_circle = Texture::from_bytes(
+        &client.get("http://127.0.0.1:8000/static/skin/spinner-circle.png")
+            .send()
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap(),
+        &graphics
+    );
+
+    info!("Fetched spinner-circle.png");
+
+    let spinner_approach_circle = Texture::from_bytes(
+        &client.get("http://127.0.0.1:8000/static/skin/spinner-approachcircle.png")
+            .send()
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap(),
+        &graphics
+    );
+
+    info!("Fetched spinner-approachcircle.png");
+
+    let spinner_rpm = Texture::from_bytes(
+        &client.get("http://127.0.0.1:8000/static/skin/spinner-rpm.png")
+            .send()
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap(),
+        &graphics
+    );
+
+    info!("Fetched spinner-rpm.png");
+
+    let scorebar_bg = Texture::from_bytes(
+        &client.get("http://127.0.0.1:8000/static/skin/scorebar-bg.png")
+            .send()
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap(),
+        &graphics
+    );
+
+    info!("Fetched scorebar-bg.png");
+
+    let scorebar_colour = Texture::from_bytes(
+        &client.get("http://127.0.0.1:8000/static/skin/scorebar-colour.png")
+            .send()
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap(),
+        &graphics
+    );
+
+    info!("Fetched scorebar-colour.png");
+
     let judgments_atlas = AtlasTexture::from_images(
         graphics, 
         &[hit_300, hit_100, hit_50, hit_miss]
@@ -345,6 +439,11 @@ async fn initialize_skin(graphics: &Graphics<'_>) -> SkinManager {
         judgments_atlas,
         slider_tick,
         slider_reverse_arrow: reverse_arrow,
+        spinner_circle,
+        spinner_approach_circle,
+        spinner_rpm,
+        scorebar_bg,
+        scorebar_colour,
     }
 }
 
@@ -435,7 +534,11 @@ impl<'app> ApplicationHandler<AppEvents> for App<'app> {
                     current_preempt: 0.0,
                     current_fadein: 0.0,
                     current_hit_window: HitWindow::from_od(5.0),
+                    current_hit_circle_diameter: 1.0,
                     last_frame_ts: Instant::now(),
+                    processor: OsuProcessor::default(),
+                    autoplay_inputs: Vec::new(),
+                    autoplay_index: 0,
                 };
 
                 state.open_beatmap_from_bytes(&TEST_BEATMAP_BYTES);